@@ -0,0 +1,75 @@
+//! Raw byte access for plain-old-data element types, available with the
+//! `bytemuck` feature.
+
+use bytemuck::Pod;
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+impl<T: Pod> Tensor<T> {
+    /// Returns the elements as raw bytes in native endianness, without copying.
+    ///
+    /// Returns `None` if the tensor is not contiguous, since the bytes would not
+    /// be in row-major order.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        self.as_slice().map(bytemuck::cast_slice)
+    }
+
+    /// Creates a tensor by reinterpreting native-endian `bytes` as elements of `T`.
+    ///
+    /// The bytes are copied into a new buffer, so `bytes` need not be aligned
+    /// for `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the number of bytes does not match
+    /// `shape` and the element size.
+    pub fn from_bytes(bytes: &[u8], shape: Vec<usize>) -> Result<Self, TensorError> {
+        let size = std::mem::size_of::<T>();
+        if !bytes.len().is_multiple_of(size) {
+            return Err(TensorError::ShapeError(format!(
+                "byte length {} is not a multiple of the element size {}",
+                bytes.len(),
+                size
+            )));
+        }
+
+        Tensor::new(bytemuck::pod_collect_to_vec(bytes), shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let tensor = Tensor::new(vec![1.0f32, -2.5, 3.25, 0.0], vec![2, 2]).unwrap();
+
+        let bytes = tensor.as_bytes().unwrap();
+        let restored = Tensor::<f32>::from_bytes(bytes, vec![2, 2]).unwrap();
+
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(&bytes[..4], &1.0f32.to_ne_bytes());
+        assert_eq!(restored, tensor);
+    }
+
+    #[test]
+    fn test_as_bytes_requires_contiguous() {
+        let tensor = Tensor::new(vec![1u16, 2, 3, 4], vec![2, 2]).unwrap();
+
+        assert!(tensor.permute(&[1, 0]).unwrap().as_bytes().is_none());
+    }
+
+    #[test]
+    fn test_from_bytes_errors() {
+        assert!(matches!(
+            Tensor::<u32>::from_bytes(&[0; 7], vec![2]),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            Tensor::<u32>::from_bytes(&[0; 8], vec![3]),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+//! Cooperative cancellation of long-running kernels.
+//!
+//! A [`CancellationToken`] is shared between the thread running a computation
+//! and whoever may want to stop it, such as a request handler or a UI thread.
+//! While a computation runs inside [`CancellationToken::scoped`], the iterative
+//! kernels on that thread poll the token between iterations and return
+//! `TensorError::Cancelled` once it has been cancelled. The kernels that poll
+//! are the Jacobi sweeps of `linalg::svd` and `linalg::eigh` (and everything
+//! built on them), `decomp::cp_als`, `graph::pagerank` and `Tensor::conv2d`.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::TensorError;
+
+/// A shared flag that requests cancellation of the computations it scopes.
+///
+/// Clones share the same flag, so a clone can be moved to another thread and
+/// cancelled from there.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+impl CancellationToken {
+    /// Returns a token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation. Kernels notice at their next check.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether [`cancel`](Self::cancel) has been called on this token
+    /// or one of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Runs `body` with `self` as the current thread's token, restoring the
+    /// previous token afterwards, even if `body` panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::cancel::CancellationToken;
+    /// use tiny_tensor::error::TensorError;
+    /// use tiny_tensor::linalg::{SvdMode, svd};
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let a = Tensor::new(vec![2.0f64, 1.0, 1.0, 3.0], vec![2, 2]).unwrap();
+    /// let token = CancellationToken::new();
+    /// assert!(token.scoped(|| svd(&a, SvdMode::Thin)).is_ok());
+    ///
+    /// token.cancel();
+    /// let result = token.scoped(|| svd(&a, SvdMode::Thin));
+    /// assert!(matches!(result, Err(TensorError::Cancelled(_))));
+    /// ```
+    pub fn scoped<R>(&self, body: impl FnOnce() -> R) -> R {
+        struct Restore(Option<CancellationToken>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|current| current.replace(Some(self.clone()))));
+        body()
+    }
+}
+
+/// Returns `TensorError::Cancelled` if the current thread's token has been
+/// cancelled. `what` names the interrupted operation.
+pub(crate) fn check(what: &str) -> Result<(), TensorError> {
+    let cancelled = CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    });
+    if cancelled {
+        return Err(TensorError::Cancelled(format!("{} was cancelled", what)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_checks_and_restores_token() {
+        let outer = CancellationToken::new();
+        let inner = CancellationToken::new();
+        inner.cancel();
+
+        outer.scoped(|| {
+            assert_eq!(check("outer"), Ok(()));
+            assert!(matches!(
+                inner.scoped(|| check("inner")),
+                Err(TensorError::Cancelled(_))
+            ));
+            assert_eq!(check("outer"), Ok(()));
+        });
+        assert_eq!(check("unscoped"), Ok(()));
+    }
+
+    #[test]
+    fn test_cancel_from_another_thread() {
+        let token = CancellationToken::new();
+        let remote = token.clone();
+
+        std::thread::spawn(move || remote.cancel()).join().unwrap();
+
+        assert!(token.is_cancelled());
+        assert!(token.scoped(|| check("kernel")).is_err());
+    }
+}
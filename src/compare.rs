@@ -0,0 +1,70 @@
+//! Elementwise comparisons producing boolean tensors.
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+macro_rules! impl_comparisons {
+    ($bound:ident; $($name:ident, $scalar:ident, $op:tt, $doc:expr;)*) => {
+        impl<T: Copy + $bound> Tensor<T> {
+            $(
+                #[doc = concat!("Returns `true` where `self` is ", $doc, " `other`, broadcasting both")]
+                /// to their common shape.
+                ///
+                /// # Errors
+                ///
+                /// Returns `TensorError::ShapeError` if the shapes cannot be broadcast together.
+                pub fn $name(&self, other: &Tensor<T>) -> Result<Tensor<bool>, TensorError> {
+                    self.zip_with(other, |a, b| a $op b)
+                }
+
+                #[doc = concat!("Returns `true` where an element is ", $doc, " `value`.")]
+                pub fn $scalar(&self, value: T) -> Tensor<bool> {
+                    self.map(|a| a $op value)
+                }
+            )*
+        }
+    };
+}
+
+impl_comparisons! {
+    PartialOrd;
+    gt, gt_scalar, >, "greater than";
+    ge, ge_scalar, >=, "greater than or equal to";
+    lt, lt_scalar, <, "less than";
+    le, le_scalar, <=, "less than or equal to";
+}
+
+impl_comparisons! {
+    PartialEq;
+    eq_elem, eq_scalar, ==, "equal to";
+    ne_elem, ne_scalar, !=, "not equal to";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordering_comparisons_broadcast() {
+        let a = Tensor::new(vec![1, 5, 3, 7], vec![2, 2]).unwrap();
+        let b = Tensor::new(vec![3, 5], vec![2]).unwrap();
+
+        assert_eq!(a.gt(&b).unwrap().data, vec![false, false, false, true]);
+        assert_eq!(a.ge(&b).unwrap().data, vec![false, true, true, true]);
+        assert_eq!(a.lt(&b).unwrap().data, vec![true, false, false, false]);
+        assert_eq!(a.le(&b).unwrap().data, vec![true, true, true, false]);
+        assert!(a.gt(&Tensor::new(vec![1, 2, 3], vec![3]).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_equality_and_scalar_comparisons() {
+        let a = Tensor::new(vec![1.0, f64::NAN, 3.0], vec![3]).unwrap();
+
+        assert_eq!(a.eq_elem(&a).unwrap().data, vec![true, false, true]);
+        assert_eq!(a.ne_elem(&a).unwrap().data, vec![false, true, false]);
+        assert_eq!(a.gt_scalar(2.0).data, vec![false, false, true]);
+        assert_eq!(a.le_scalar(1.0).data, vec![true, false, false]);
+        assert_eq!(a.eq_scalar(3.0).data, vec![false, false, true]);
+        assert_eq!(a.ne_scalar(3.0).shape, vec![3]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Thread-local configuration for behavior that is not passed explicitly.
+//!
+//! The active [`Config`] is read by operations that have no argument for the
+//! setting in question, such as `Display` for tensors. Each thread starts with
+//! [`Config::default`].
+
+use std::cell::Cell;
+
+use crate::display::PrintOptions;
+
+/// Library-wide behavioral settings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Options used when printing tensors with `Display`.
+    pub print_options: PrintOptions,
+}
+
+thread_local! {
+    static CURRENT: Cell<Config> = Cell::new(Config::default());
+}
+
+impl Config {
+    /// Returns the configuration active on the current thread.
+    pub fn current() -> Config {
+        CURRENT.with(Cell::get)
+    }
+
+    /// Replaces the configuration of the current thread.
+    pub fn set_current(config: Config) {
+        CURRENT.with(|current| current.set(config));
+    }
+
+    /// Returns a copy of `self` with different print options.
+    pub fn with_print_options(mut self, print_options: PrintOptions) -> Config {
+        self.print_options = print_options;
+        self
+    }
+
+    /// Runs `body` with `self` as the current thread's configuration, restoring
+    /// the previous configuration afterwards, even if `body` panics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::config::Config;
+    /// use tiny_tensor::display::PrintOptions;
+    /// use tiny_tensor::tensor;
+    ///
+    /// let t = tensor![1, 2, 3, 4, 5];
+    /// let options = PrintOptions { threshold: 2, edge_items: 1 };
+    /// let text = Config::current()
+    ///     .with_print_options(options)
+    ///     .scoped(|| t.to_string());
+    /// assert_eq!(text, "[1, ..., 5]");
+    /// ```
+    pub fn scoped<R>(self, body: impl FnOnce() -> R) -> R {
+        struct Restore(Config);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                Config::set_current(self.0);
+            }
+        }
+
+        let _restore = Restore(Config::current());
+        Config::set_current(self);
+        body()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_restores_previous_config() {
+        let options = PrintOptions {
+            threshold: 1,
+            edge_items: 1,
+        };
+        let scoped = Config::default().with_print_options(options);
+
+        let inner = scoped.scoped(Config::current);
+
+        assert_eq!(inner, scoped);
+        assert_eq!(Config::current(), Config::default());
+    }
+
+    #[test]
+    fn test_scoped_restores_after_panic() {
+        let options = PrintOptions {
+            threshold: 7,
+            edge_items: 2,
+        };
+
+        let result = std::panic::catch_unwind(|| {
+            Config::default()
+                .with_print_options(options)
+                .scoped(|| panic!("boom"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(Config::current(), Config::default());
+    }
+}
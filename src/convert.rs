@@ -0,0 +1,103 @@
+//! Conversions between `Tensor` and standard library collections.
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+impl<T: Copy> TryFrom<Vec<T>> for Tensor<T> {
+    type Error = TensorError;
+
+    /// Creates a 1D tensor from a flat vector.
+    fn try_from(data: Vec<T>) -> Result<Self, Self::Error> {
+        let len = data.len();
+        Tensor::new(data, vec![len])
+    }
+}
+
+impl<T: Copy> TryFrom<Vec<Vec<T>>> for Tensor<T> {
+    type Error = TensorError;
+
+    /// Creates a 2D tensor from a vector of rows.
+    ///
+    /// Returns `TensorError::ShapeError` if the rows have different lengths.
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Self, Self::Error> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().position(|r| r.len() != cols) {
+            return Err(TensorError::ShapeError(format!(
+                "ragged input: row {} has length {}, expected {}",
+                row,
+                rows[row].len(),
+                cols
+            )));
+        }
+
+        let shape = vec![rows.len(), cols];
+        Tensor::new(rows.into_iter().flatten().collect(), shape)
+    }
+}
+
+impl<T: Copy> TryFrom<Vec<Vec<Vec<T>>>> for Tensor<T> {
+    type Error = TensorError;
+
+    /// Creates a 3D tensor from a vector of matrices.
+    ///
+    /// Returns `TensorError::ShapeError` if the matrices or their rows have
+    /// different lengths.
+    fn try_from(layers: Vec<Vec<Vec<T>>>) -> Result<Self, Self::Error> {
+        let rows = layers.first().map_or(0, Vec::len);
+        let cols = layers.first().and_then(|m| m.first()).map_or(0, Vec::len);
+        for (i, matrix) in layers.iter().enumerate() {
+            if matrix.len() != rows {
+                return Err(TensorError::ShapeError(format!(
+                    "ragged input: matrix {} has {} rows, expected {}",
+                    i,
+                    matrix.len(),
+                    rows
+                )));
+            }
+            if let Some(j) = matrix.iter().position(|r| r.len() != cols) {
+                return Err(TensorError::ShapeError(format!(
+                    "ragged input: row {} of matrix {} has length {}, expected {}",
+                    j,
+                    i,
+                    matrix[j].len(),
+                    cols
+                )));
+            }
+        }
+
+        let shape = vec![layers.len(), rows, cols];
+        Tensor::new(layers.into_iter().flatten().flatten().collect(), shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_vec() {
+        let result = Tensor::try_from(vec![1, 2, 3]).unwrap();
+
+        assert_eq!(result.shape(), &[3]);
+    }
+
+    #[test]
+    fn test_try_from_nested_vec() {
+        let matrix = Tensor::try_from(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        let cube = Tensor::try_from(vec![vec![vec![1, 2]], vec![vec![3, 4]]]).unwrap();
+
+        assert_eq!(matrix.shape(), &[2, 3]);
+        assert_eq!(matrix.as_slice(), Some(&[1, 2, 3, 4, 5, 6][..]));
+        assert_eq!(cube.shape(), &[2, 1, 2]);
+        assert_eq!(cube.as_slice(), Some(&[1, 2, 3, 4][..]));
+    }
+
+    #[test]
+    fn test_try_from_ragged() {
+        let rows = Tensor::try_from(vec![vec![1, 2], vec![3]]);
+        let layers = Tensor::try_from(vec![vec![vec![1, 2]], vec![vec![3, 4], vec![5, 6]]]);
+
+        assert!(matches!(rows, Err(TensorError::ShapeError(_))));
+        assert!(matches!(layers, Err(TensorError::ShapeError(_))));
+    }
+}
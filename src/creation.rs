@@ -1,19 +1,295 @@
+use std::cmp::Ordering;
+
+use crate::error::TensorError;
+use crate::fft::{Complex, fft};
+use crate::num::{Float, One, Progression, Zero};
 use crate::tensor::Tensor;
 
+/// Returns the number of elements described by `shape`, panicking on overflow.
+fn num_elements(shape: &[usize]) -> usize {
+    shape
+        .iter()
+        .try_fold(1usize, |acc, &dim| acc.checked_mul(dim))
+        .expect("the number of elements in the shape overflows usize")
+}
+
 /// Creates a `Tensor` of a given shape filled with zeros.
 ///
 /// # Panics
 ///
 /// Panics if the total number of elements overflows `usize`.
 pub fn zeros<T: Default + Copy>(shape: &[usize]) -> Tensor<T> {
-    let num_elements = shape.iter().product();
-    let data = vec![T::default(); num_elements];
+    full(shape, T::default())
+}
+
+/// Creates a `Tensor` of a given shape filled with ones.
+///
+/// # Panics
+///
+/// Panics if the total number of elements overflows `usize`.
+pub fn ones<T: One + Copy>(shape: &[usize]) -> Tensor<T> {
+    full(shape, T::one())
+}
+
+/// Creates a `Tensor` of a given shape filled with `value`.
+///
+/// # Panics
+///
+/// Panics if the total number of elements overflows `usize`.
+pub fn full<T: Copy>(shape: &[usize], value: T) -> Tensor<T> {
+    let data = vec![value; num_elements(shape)];
 
     Tensor::new(data, shape.to_vec()).unwrap()
 }
 
+/// Creates a `Tensor` filled with zeros with the same shape as `other`.
+pub fn zeros_like<T: Default + Copy>(other: &Tensor<T>) -> Tensor<T> {
+    zeros(other.shape())
+}
+
+/// Creates a `Tensor` filled with ones with the same shape as `other`.
+pub fn ones_like<T: One + Copy>(other: &Tensor<T>) -> Tensor<T> {
+    ones(other.shape())
+}
+
+/// Creates a `Tensor` filled with `value` with the same shape as `other`.
+pub fn full_like<T: Copy>(other: &Tensor<T>, value: T) -> Tensor<T> {
+    full(other.shape(), value)
+}
+
+/// Creates an `n x n` identity matrix.
+///
+/// # Panics
+///
+/// Panics if the total number of elements overflows `usize`.
+pub fn eye<T: Zero + One + Copy>(n: usize) -> Tensor<T> {
+    eye_rect(n, n, 0)
+}
+
+/// Creates a `rows x cols` matrix with ones on the `k`-th diagonal and zeros elsewhere.
+///
+/// `k = 0` is the main diagonal, positive values refer to diagonals above it and
+/// negative values to diagonals below it. Diagonals lying entirely outside the
+/// matrix produce an all-zero result.
+///
+/// # Panics
+///
+/// Panics if the total number of elements overflows `usize`.
+pub fn eye_rect<T: Zero + One + Copy>(rows: usize, cols: usize, k: isize) -> Tensor<T> {
+    let mut data = vec![T::zero(); num_elements(&[rows, cols])];
+    let (row_start, col_start) = if k >= 0 {
+        (0, k.unsigned_abs())
+    } else {
+        (k.unsigned_abs(), 0)
+    };
+
+    let mut row = row_start;
+    let mut col = col_start;
+    while row < rows && col < cols {
+        data[row * cols + col] = T::one();
+        row += 1;
+        col += 1;
+    }
+
+    Tensor::new(data, vec![rows, cols]).unwrap()
+}
+
+/// Collects the elements of a 1D tensor, or returns a `ShapeError` naming `what`.
+pub(crate) fn vector_elements<T: Copy>(
+    tensor: &Tensor<T>,
+    what: &str,
+) -> Result<Vec<T>, TensorError> {
+    if tensor.ndim() != 1 {
+        return Err(TensorError::ShapeError(format!(
+            "{} must be 1-dimensional, got shape {:?}",
+            what, tensor.shape
+        )));
+    }
+    Ok(tensor.iter().copied().collect())
+}
+
+/// Creates the square matrix with the elements of `vector` on its main
+/// diagonal and zeros elsewhere.
+///
+/// Returns a `ShapeError` if `vector` is not 1-dimensional.
+pub fn diag<T: Zero + Copy>(vector: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let values = vector_elements(vector, "diag vector")?;
+    let n = values.len();
+    Ok(Tensor::from_fn(vec![n, n], |index| {
+        if index[0] == index[1] {
+            values[index[0]]
+        } else {
+            T::zero()
+        }
+    }))
+}
+
+/// Creates a Toeplitz matrix with first column `col` and first row `row`.
+///
+/// The result has shape `[col.len(), row.len()]` and every diagonal is constant:
+/// element `[i, j]` is `col[i - j]` below the main diagonal and `row[j - i]` on
+/// or above it. As in SciPy, `row[0]` is ignored in favour of `col[0]`.
+///
+/// Returns a `ShapeError` if either input is not 1-dimensional.
+pub fn toeplitz<T: Copy>(col: &Tensor<T>, row: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let col = vector_elements(col, "toeplitz column")?;
+    let row = vector_elements(row, "toeplitz row")?;
+    Ok(Tensor::from_fn(vec![col.len(), row.len()], |index| {
+        let (i, j) = (index[0], index[1]);
+        if i >= j { col[i - j] } else { row[j - i] }
+    }))
+}
+
+/// Creates the `n x n` circulant matrix whose first column is `c`.
+///
+/// Each column is the previous one rotated down by one, so element `[i, j]` is
+/// `c[(i - j) mod n]`.
+///
+/// Returns a `ShapeError` if `c` is not 1-dimensional.
+pub fn circulant<T: Copy>(c: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let c = vector_elements(c, "circulant vector")?;
+    let n = c.len();
+    Ok(Tensor::from_fn(vec![n, n], |index| {
+        c[(index[0] + n - index[1]) % n]
+    }))
+}
+
+/// Multiplies the circulant matrix with first column `c` by the vector `x`,
+/// without materializing the matrix.
+///
+/// This is the circular convolution of `c` and `x`, computed through the fast
+/// Fourier transform in `O(n log n)` time for any length `n`.
+///
+/// Returns a `ShapeError` if either input is not 1-dimensional or their lengths differ.
+pub fn circulant_matvec<T: Float>(c: &Tensor<T>, x: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let c = vector_elements(c, "circulant vector")?;
+    let x = vector_elements(x, "circulant_matvec operand")?;
+    if c.len() != x.len() {
+        return Err(TensorError::ShapeError(format!(
+            "circulant of size {} cannot multiply a vector of length {}",
+            c.len(),
+            x.len()
+        )));
+    }
+
+    let spectrum = |values: Vec<T>| {
+        let mut values: Vec<Complex<T>> = values
+            .into_iter()
+            .map(|re| Complex::new(re, T::zero()))
+            .collect();
+        fft(&mut values, false);
+        values
+    };
+    let mut product: Vec<Complex<T>> = spectrum(c)
+        .into_iter()
+        .zip(spectrum(x))
+        .map(|(c, x)| c.mul(x))
+        .collect();
+    fft(&mut product, true);
+
+    let n = product.len();
+    Tensor::new(product.into_iter().map(|z| z.re).collect(), vec![n])
+}
+
+/// Creates a 1D `Tensor` of evenly spaced values in the half-open interval `[start, stop)`.
+///
+/// Each element is computed as `start + i * step` rather than by repeated addition,
+/// so floating-point ranges do not accumulate rounding error. A negative `step`
+/// produces a descending range. Integer ranges may end next to the type's limits;
+/// the range stops at the first term that is not representable.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `step` is zero (or NaN).
+pub fn arange<T>(start: T, stop: T, step: T) -> Result<Tensor<T>, TensorError>
+where
+    T: Copy + PartialOrd + Zero + Progression,
+{
+    let ascending = match step.partial_cmp(&T::zero()) {
+        Some(Ordering::Greater) => true,
+        Some(Ordering::Less) => false,
+        _ => {
+            return Err(TensorError::ValueError(
+                "arange step must be non-zero".to_string(),
+            ));
+        }
+    };
+
+    let mut data = Vec::new();
+    while let Some(value) = T::nth_term(start, step, data.len()) {
+        let in_range = if ascending {
+            value < stop
+        } else {
+            value > stop
+        };
+        if !in_range {
+            break;
+        }
+        data.push(value);
+    }
+
+    let len = data.len();
+    Tensor::new(data, vec![len])
+}
+
+/// Creates a 1D `Tensor` of `num` evenly spaced values over the closed interval
+/// `[start, end]`.
+///
+/// As in NumPy, both endpoints are included, and the last element is exactly `end`.
+/// `num = 1` yields just `start`, and `num = 0` yields an empty tensor.
+pub fn linspace<T: Float>(start: T, end: T, num: usize) -> Tensor<T> {
+    let mut data = Vec::with_capacity(num);
+    if num == 1 {
+        data.push(start);
+    } else if num > 1 {
+        let step = (end - start) / T::from_usize(num - 1);
+        data.extend((0..num - 1).map(|i| start + step * T::from_usize(i)));
+        data.push(end);
+    }
+
+    Tensor::new(data, vec![num]).unwrap()
+}
+
+/// Creates a 1D `Tensor` of `num` values spaced evenly on a log scale, from
+/// `base^start` to `base^end` inclusive.
+pub fn logspace<T: Float>(base: T, start: T, end: T, num: usize) -> Tensor<T> {
+    let exponents = linspace(start, end, num);
+    let data = exponents.data.into_iter().map(|e| base.powf(e)).collect();
+
+    Tensor::new(data, vec![num]).unwrap()
+}
+
+/// Stacks equally shaped tensors along a new leading axis.
+///
+/// Used by the `tensor!` macro to assemble nested literals; not part of the
+/// public API.
+#[doc(hidden)]
+pub fn __stack_rows<T: Copy>(rows: Vec<Tensor<T>>) -> Tensor<T> {
+    let inner_shape = rows[0].shape().to_vec();
+    if !rows.iter().all(|row| row.shape() == inner_shape.as_slice()) {
+        panic!("tensor!: all nested rows must have the same shape");
+    }
+
+    let mut shape = vec![rows.len()];
+    shape.extend(inner_shape);
+    let data: Vec<T> = rows.into_iter().flat_map(|row| row.data).collect();
+
+    Tensor::new(data, shape).unwrap()
+}
+
 /// A macro for creating `Tensor`s with a convenient, literal-like syntax.
 ///
+/// Nested brackets may be used to any depth; each level of nesting adds a
+/// dimension. Like `vec![x; n]`, `tensor![x; [d0, d1, ...]]` creates a tensor of
+/// the given shape filled with `x`.
+///
+/// Either form accepts a trailing `; T` to cast every element to the numeric
+/// type `T`, which avoids awkward literal suffixes such as `1.0f32`.
+///
+/// # Panics
+///
+/// Panics if the nested rows are ragged, i.e. do not all have the same shape.
+///
 /// # Examples
 ///
 /// ```
@@ -27,64 +303,45 @@ pub fn zeros<T: Default + Copy>(shape: &[usize]) -> Tensor<T> {
 ///
 /// // 3D tensor
 /// let t = tensor![[[1, 2], [3, 4]], [[5, 6], [7, 8]]];
+///
+/// // 4D tensor, e.g. a batch of images shaped [N, C, H, W]
+/// let b = tensor![[[[1, 2], [3, 4]]]];
+/// assert_eq!(b.shape(), &[1, 1, 2, 2]);
+///
+/// // Constant-filled tensor of shape [2, 3, 4]
+/// let z = tensor![0.0; [2, 3, 4]];
+///
+/// // Integer literals cast to f32
+/// let f = tensor![[1, 2], [3, 4]; f32];
+/// assert_eq!(f.as_slice(), Some(&[1.0f32, 2.0, 3.0, 4.0][..]));
 /// ```
 #[macro_export]
 macro_rules! tensor {
-    // 3D: tensor![[[a, b], [c, d]], [[e, f], [g, h]]]
-    (
-        [
-            $(
-                [
-                    $(
-                        [ $( $val:expr ),+ $(,)? ]
-                    ),+ $(,)?
-                ]
-            ),+ $(,)?
-        ]
-    ) => {{
-        let layers = vec![
-            $(
-                vec![
-                    $(
-                        vec![ $( $val ),+ ]
-                    ),+
-                ]
-            ),+
-        ];
-        let d1 = layers.len();
-        let d2 = layers[0].len();
-        let d3 = layers[0][0].len();
-        if !layers.iter().all(|m| m.len() == d2 && m.iter().all(|r| r.len() == d3)) {
-            panic!("tensor!: all inner 2D matrices must have equal sizes");
-        }
-        let data: Vec<_> = layers.into_iter().flatten().flatten().collect();
-        $crate::tensor::Tensor::new(data, vec![d1, d2, d3]).unwrap()
-    }};
-    // 2D: tensor![[a, b], [c, d]]
-    (
-        [
-            $(
-                [ $( $val:expr ),+ $(,)? ]
-            ),+ $(,)?
-        ]
-    ) => {{
-        let rows = vec![
-            $(
-                vec![ $( $val ),+ ]
-            ),+
-        ];
-        let d1 = rows.len();
-        let d2 = rows[0].len();
-        if !rows.iter().all(|r| r.len() == d2) {
-            panic!("tensor!: all rows must have the same length");
-        }
-        let data: Vec<_> = rows.into_iter().flatten().collect();
-        $crate::tensor::Tensor::new(data, vec![d1, d2]).unwrap()
-    }};
+    // Fill with a cast: tensor![x; [d0, d1]; T]
+    ( $val:expr ; [ $( $dim:expr ),* $(,)? ] ; $t:ty ) => {
+        $crate::creation::full(&[ $( $dim ),* ], $val as $t)
+    };
+    // Fill: tensor![x; [d0, d1]]
+    ( $val:expr ; [ $( $dim:expr ),* $(,)? ] ) => {
+        $crate::creation::full(&[ $( $dim ),* ], $val)
+    };
+    // N-D with a cast: tensor![[...], [...]; T]
+    ( $( [ $( $inner:tt )* ] ),+ $(,)? ; $t:ty ) => {
+        $crate::creation::__stack_rows(vec![ $( $crate::tensor![ $( $inner )* ; $t ] ),+ ])
+    };
+    // N-D: tensor![[...], [...]], where each row is itself a tensor! literal.
+    ( $( [ $( $inner:tt )* ] ),+ $(,)? ) => {
+        $crate::creation::__stack_rows(vec![ $( $crate::tensor![ $( $inner )* ] ),+ ])
+    };
+    // 1D with a cast: tensor![a, b, c; T]
+    ( $( $val:expr ),+ $(,)? ; $t:ty ) => {
+        $crate::tensor![ $( $val as $t ),+ ]
+    };
     // 1D: tensor![a, b, c]
     ( $( $val:expr ),+ $(,)? ) => {{
         let data = vec![ $( $val ),+ ];
-        $crate::tensor::Tensor::new(data, vec![data.len()]).unwrap()
+        let len = data.len();
+        $crate::tensor::Tensor::new(data, vec![len]).unwrap()
     }};
 }
 
@@ -99,4 +356,200 @@ mod tests {
         assert_eq!(result.shape, &[2, 3]);
         assert_eq!(result.data, vec![0, 0, 0, 0, 0, 0])
     }
+
+    #[test]
+    fn test_ones_and_full() {
+        let ones: Tensor<f64> = ones(&[3]);
+        let full = full(&[2, 2], 7u8);
+
+        assert_eq!(ones.data, vec![1.0, 1.0, 1.0]);
+        assert_eq!(full.shape, &[2, 2]);
+        assert_eq!(full.data, vec![7, 7, 7, 7]);
+    }
+
+    #[test]
+    fn test_like_constructors() {
+        let other = Tensor::new(vec![5, 6, 7, 8, 9, 10], vec![3, 2]).unwrap();
+
+        assert_eq!(zeros_like(&other).data, vec![0; 6]);
+        assert_eq!(ones_like(&other).data, vec![1; 6]);
+        assert_eq!(full_like(&other, 4).shape, &[3, 2]);
+    }
+
+    #[test]
+    fn test_toeplitz_and_circulant() {
+        let col = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+        let row = Tensor::new(vec![9, 4, 5, 6], vec![4]).unwrap();
+
+        let result = toeplitz(&col, &row).unwrap();
+        assert_eq!(result.shape, &[3, 4]);
+        assert_eq!(result.data, vec![1, 4, 5, 6, 2, 1, 4, 5, 3, 2, 1, 4]);
+
+        let result = circulant(&col).unwrap();
+        assert_eq!(result.data, vec![1, 3, 2, 2, 1, 3, 3, 2, 1]);
+
+        let matrix = Tensor::new(vec![1, 2], vec![1, 2]).unwrap();
+        assert!(matches!(
+            circulant(&matrix),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_circulant_matvec_matches_dense_product() {
+        let c = Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap();
+        let x = Tensor::new(vec![4.0, 5.0, 6.0], vec![3]).unwrap();
+
+        let result = circulant_matvec(&c, &x).unwrap();
+
+        // Rows of circulant(c) are [1, 3, 2], [2, 1, 3], [3, 2, 1].
+        let expected = Tensor::new(vec![31.0, 31.0, 28.0], vec![3]).unwrap();
+        assert!(result.max_abs_diff(&expected).unwrap() < 1e-12);
+        let short = Tensor::new(vec![1.0, 2.0], vec![2]).unwrap();
+        assert!(circulant_matvec(&c, &short).is_err());
+
+        // Power-of-two and other lengths agree with the dense matrix.
+        for n in [1, 7, 8] {
+            let c = Tensor::from_fn(vec![n], |i| (i[0] * 3 % 5) as f64 - 1.5);
+            let x = Tensor::from_fn(vec![n], |i| 1.0 / (i[0] + 1) as f64);
+            let dense = circulant(&c).unwrap();
+            let expected = Tensor::from_fn(vec![n], |i| {
+                (0..n)
+                    .map(|j| dense.get(&[i[0], j]).unwrap() * x.get(&[j]).unwrap())
+                    .sum::<f64>()
+            });
+            let result = circulant_matvec(&c, &x).unwrap();
+            assert!(result.max_abs_diff(&expected).unwrap() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_eye() {
+        let result: Tensor<i32> = eye(3);
+
+        assert_eq!(result.shape, &[3, 3]);
+        assert_eq!(result.data, vec![1, 0, 0, 0, 1, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_eye_rect_offsets() {
+        let above: Tensor<f32> = eye_rect(2, 3, 1);
+        let below: Tensor<u8> = eye_rect(3, 2, -1);
+        let outside: Tensor<i64> = eye_rect(2, 2, 5);
+
+        assert_eq!(above.data, vec![0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(below.data, vec![0, 0, 1, 0, 0, 1]);
+        assert_eq!(outside.data, vec![0; 4]);
+    }
+
+    #[test]
+    fn test_arange() {
+        let ints = arange(0, 5, 2).unwrap();
+        let descending = arange(3, -3, -2).unwrap();
+        let floats = arange(0.0, 0.5, 0.1).unwrap();
+        let empty = arange(5, 0, 1).unwrap();
+
+        assert_eq!(ints.data, vec![0, 2, 4]);
+        assert_eq!(descending.data, vec![3, 1, -1]);
+        assert_eq!(floats.shape, &[5]);
+        assert_eq!(floats.data[3], 3.0 * 0.1);
+        assert_eq!(empty.shape, &[0]);
+    }
+
+    #[test]
+    fn test_arange_near_integer_limits() {
+        assert_eq!(arange(250u8, 255, 3).unwrap().data, vec![250, 253]);
+        assert_eq!(arange(0u8, 255, 100).unwrap().data, vec![0, 100, 200]);
+        assert_eq!(arange(-120i8, 127, 100).unwrap().data, vec![-120, -20, 80]);
+        assert_eq!(arange(127i8, -128, -127).unwrap().data, vec![127, 0, -127]);
+        assert_eq!(arange(-128i8, 127, 1).unwrap().len(), 255);
+        assert_eq!(
+            arange(u128::MAX - 2, u128::MAX, 1).unwrap().data,
+            vec![u128::MAX - 2, u128::MAX - 1]
+        );
+    }
+
+    #[test]
+    fn test_arange_zero_step() {
+        assert!(matches!(arange(0, 5, 0), Err(TensorError::ValueError(_))));
+        assert!(matches!(
+            arange(0.0, 1.0, f64::NAN),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+
+    #[test]
+    fn test_linspace() {
+        let result = linspace(0.0, 1.0, 5);
+        let single = linspace(2.0f32, 3.0, 1);
+        let empty = linspace(2.0f32, 3.0, 0);
+
+        assert_eq!(result.data, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(single.data, vec![2.0]);
+        assert_eq!(empty.shape, &[0]);
+    }
+
+    #[test]
+    fn test_logspace() {
+        let result = logspace(10.0, 0.0, 3.0, 4);
+
+        assert_eq!(result.data, vec![1.0, 10.0, 100.0, 1000.0]);
+    }
+
+    #[test]
+    fn test_tensor_macro() {
+        let vector = tensor![1, 2, 3];
+        let matrix = tensor![[1, 2, 3], [4, 5, 6]];
+        let batch = tensor![[[[1, 2], [3, 4]]], [[[5, 6], [7, 8]]]];
+
+        assert_eq!(vector.shape, &[3]);
+        assert_eq!(matrix.shape, &[2, 3]);
+        assert_eq!(matrix.data, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(batch.shape, &[2, 1, 2, 2]);
+        assert_eq!(batch.data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_tensor_macro_fill_and_cast() {
+        let filled = tensor![1.5; [2, 3, 4]];
+        let cast_fill = tensor![2; [3]; u8];
+        let cast_vector = tensor![1, 2, 3; f64];
+        let cast_matrix = tensor![[1, 2], [3, 4]; i64];
+
+        assert_eq!(filled.shape, &[2, 3, 4]);
+        assert!(filled.data.iter().all(|&x| x == 1.5));
+        assert_eq!(cast_fill.data, vec![2u8, 2, 2]);
+        assert_eq!(cast_vector.data, vec![1.0, 2.0, 3.0]);
+        assert_eq!(cast_matrix.shape, &[2, 2]);
+        assert_eq!(cast_matrix.data, vec![1i64, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "same shape")]
+    fn test_tensor_macro_ragged() {
+        tensor![[[1, 2], [3, 4]], [[5, 6]]];
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows usize")]
+    fn test_full_overflow_panics() {
+        full(&[usize::MAX, 2], 0u8);
+    }
+
+    #[test]
+    fn test_diag_round_trips_with_diagonal() {
+        let v = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+
+        let m = diag(&v).unwrap();
+
+        assert_eq!(m.as_slice(), Some(&[1, 0, 0, 0, 2, 0, 0, 0, 3][..]));
+        assert_eq!(m.diagonal(0).unwrap(), v);
+        assert_eq!(
+            diag(&Tensor::<f64>::new(vec![], vec![0]).unwrap())
+                .unwrap()
+                .shape(),
+            &[0, 0]
+        );
+        assert!(diag(&m).is_err());
+    }
 }
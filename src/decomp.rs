@@ -0,0 +1,571 @@
+//! Decompositions of higher-order tensors: CP by alternating least squares
+//! and Tucker by higher-order SVD.
+
+use crate::cancel;
+use crate::error::TensorError;
+use crate::linalg::{SvdMode, solve, svd};
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Options for [`cp_als`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CpOptions {
+    /// The maximum number of ALS sweeps over all modes.
+    pub max_iter: usize,
+    /// Iteration stops once the relative fit improves by less than this.
+    pub tol: f64,
+    /// Seed for the pseudo-random initial factors.
+    pub seed: u64,
+}
+
+impl Default for CpOptions {
+    fn default() -> Self {
+        Self {
+            max_iter: 200,
+            tol: 1e-10,
+            seed: 0,
+        }
+    }
+}
+
+/// A rank-`R` CP (CANDECOMP/PARAFAC) decomposition: a weighted sum of `R`
+/// outer products of factor-matrix columns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpDecomposition<T> {
+    /// The weight of each rank-one component, shape `[R]`.
+    pub weights: Tensor<T>,
+    /// One factor matrix per mode, of shape `[shape[n], R]`, with unit-norm columns.
+    pub factors: Vec<Tensor<T>>,
+    /// The relative fit `1 - |X - X_hat| / |X|` reached.
+    pub fit: T,
+    /// The number of ALS sweeps performed.
+    pub iterations: usize,
+}
+
+impl<T: Float> CpDecomposition<T> {
+    /// Rebuilds the full tensor from the weights and factors.
+    pub fn reconstruct(&self) -> Tensor<T> {
+        let shape: Vec<usize> = self.factors.iter().map(|f| f.shape()[0]).collect();
+        let factors: Vec<Vec<T>> = self
+            .factors
+            .iter()
+            .map(|f| f.iter().copied().collect())
+            .collect();
+        let weights: Vec<T> = self.weights.iter().copied().collect();
+        reconstruct(&shape, &weights, &factors)
+    }
+}
+
+/// Computes a rank-`rank` CP decomposition of `tensor` by alternating least
+/// squares.
+///
+/// Each sweep solves, mode by mode, for the factor matrix that best fits the
+/// tensor with every other factor held fixed. Factors start from a seeded
+/// pseudo-random draw, so results are reproducible for a given
+/// [`CpOptions::seed`].
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `tensor` has fewer than two dimensions or
+/// a zero-length axis, `TensorError::ValueError` if `rank` is zero or the
+/// normal equations of a mode become singular, and `TensorError::Cancelled` if
+/// the current [`CancellationToken`](crate::cancel::CancellationToken) is
+/// cancelled before a sweep.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::decomp::{cp_als, CpOptions};
+/// use tiny_tensor::tensor::Tensor;
+///
+/// // The outer product of [1, 2] and [1, 0, 3] has rank one.
+/// let x = Tensor::new(vec![1.0, 0.0, 3.0, 2.0, 0.0, 6.0], vec![2, 3]).unwrap();
+/// let cp = cp_als(&x, 1, CpOptions::default()).unwrap();
+/// assert!(cp.reconstruct().max_abs_diff(&x).unwrap() < 1e-8);
+/// ```
+pub fn cp_als<T: Float>(
+    tensor: &Tensor<T>,
+    rank: usize,
+    options: CpOptions,
+) -> Result<CpDecomposition<T>, TensorError> {
+    let shape = tensor.shape().to_vec();
+    if shape.len() < 2 || shape.contains(&0) {
+        return Err(TensorError::ShapeError(format!(
+            "cp_als needs a tensor with at least two non-empty axes, got shape {:?}",
+            shape
+        )));
+    }
+    if rank == 0 {
+        return Err(TensorError::ValueError(
+            "cp_als rank must be at least 1".to_string(),
+        ));
+    }
+
+    let data: Vec<T> = tensor.iter().copied().collect();
+    let norm = data.iter().fold(T::zero(), |acc, &x| acc + x * x).sqrt();
+    let mut state = options.seed;
+    let mut factors: Vec<Vec<T>> = shape
+        .iter()
+        .map(|&len| {
+            (0..len * rank)
+                .map(|_| T::from_f64(next_unit(&mut state)))
+                .collect()
+        })
+        .collect();
+    let mut weights = vec![T::one(); rank];
+    if norm == T::zero() {
+        // Every factor update would vanish, leaving singular normal equations;
+        // an all-zero tensor is exactly the weighted sum with zero weights.
+        for factor in &mut factors {
+            normalize_columns(factor, rank);
+        }
+        weights = vec![T::zero(); rank];
+        return decomposition(weights, factors, &shape, T::one(), 0);
+    }
+
+    let tol = T::from_f64(options.tol);
+    let mut fit = T::zero();
+    let mut iterations = 0;
+    while iterations < options.max_iter {
+        cancel::check("cp_als")?;
+        iterations += 1;
+        for mode in 0..shape.len() {
+            let gram = hadamard_of_grams(&factors, mode, rank);
+            let mut updated = mttkrp(&data, &shape, &factors, mode, rank);
+            solve_rows(gram, &mut updated, rank)?;
+            weights = normalize_columns(&mut updated, rank);
+            factors[mode] = updated;
+        }
+
+        let estimate = reconstruct(&shape, &weights, &factors);
+        let residual = data
+            .iter()
+            .zip(estimate.iter())
+            .fold(T::zero(), |acc, (&x, &y)| acc + (x - y) * (x - y))
+            .sqrt();
+        let new_fit = T::one() - residual / norm;
+        let improvement = (new_fit - fit).abs();
+        fit = new_fit;
+        if improvement < tol {
+            break;
+        }
+    }
+
+    decomposition(weights, factors, &shape, fit, iterations)
+}
+
+/// Wraps row-major weights and `[shape[n], R]` factors into a
+/// [`CpDecomposition`].
+fn decomposition<T: Float>(
+    weights: Vec<T>,
+    factors: Vec<Vec<T>>,
+    shape: &[usize],
+    fit: T,
+    iterations: usize,
+) -> Result<CpDecomposition<T>, TensorError> {
+    let rank = weights.len();
+    Ok(CpDecomposition {
+        weights: Tensor::new(weights, vec![rank])?,
+        factors: factors
+            .into_iter()
+            .zip(shape)
+            .map(|(f, &len)| Tensor::new(f, vec![len, rank]))
+            .collect::<Result<_, _>>()?,
+        fit,
+        iterations,
+    })
+}
+
+/// A Tucker decomposition: a small core tensor multiplied along every mode by
+/// a factor matrix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TuckerDecomposition<T> {
+    /// The core tensor, of shape `ranks`.
+    pub core: Tensor<T>,
+    /// One factor matrix per mode, of shape `[shape[n], ranks[n]]`, with
+    /// orthonormal columns.
+    pub factors: Vec<Tensor<T>>,
+}
+
+impl<T: Float> TuckerDecomposition<T> {
+    /// Rebuilds the full tensor by multiplying the core by every factor.
+    pub fn reconstruct(&self) -> Tensor<T> {
+        self.factors
+            .iter()
+            .enumerate()
+            .fold(self.core.clone(), |acc, (mode, factor)| {
+                mode_product(&acc, factor, mode, false)
+            })
+    }
+}
+
+/// Computes a Tucker decomposition of `tensor` with the multilinear ranks
+/// `ranks` by truncated higher-order SVD (HOSVD).
+///
+/// The factor for mode `n` holds the leading `ranks[n]` left singular vectors
+/// of the mode-`n` unfolding, and the core is the tensor projected onto every
+/// factor. With `ranks` equal to the shape, the decomposition is exact.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `tensor` has fewer than two dimensions,
+/// a zero-length axis, or `ranks` does not give one rank per axis, and
+/// `TensorError::ValueError` if a rank is zero or exceeds the length of its
+/// axis.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::decomp::tucker;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// // The outer product of [1, 2] and [1, 0, 3] has multilinear rank (1, 1).
+/// let x = Tensor::new(vec![1.0, 0.0, 3.0, 2.0, 0.0, 6.0], vec![2, 3]).unwrap();
+/// let tucker = tucker(&x, &[1, 1]).unwrap();
+/// assert_eq!(tucker.core.shape(), &[1, 1]);
+/// assert!(tucker.reconstruct().max_abs_diff(&x).unwrap() < 1e-12);
+/// ```
+pub fn tucker<T: Float>(
+    tensor: &Tensor<T>,
+    ranks: &[usize],
+) -> Result<TuckerDecomposition<T>, TensorError> {
+    let shape = tensor.shape();
+    if shape.len() < 2 || shape.contains(&0) || ranks.len() != shape.len() {
+        return Err(TensorError::ShapeError(format!(
+            "tucker needs a tensor with at least two non-empty axes and one rank per axis, \
+             got shape {:?} and ranks {:?}",
+            shape, ranks
+        )));
+    }
+    if let Some((&rank, &len)) = ranks
+        .iter()
+        .zip(shape)
+        .find(|&(&rank, &len)| rank == 0 || rank > len)
+    {
+        return Err(TensorError::ValueError(format!(
+            "tucker rank {} must be between 1 and the axis length {}",
+            rank, len
+        )));
+    }
+
+    let mut factors = Vec::with_capacity(shape.len());
+    for (mode, &rank) in ranks.iter().enumerate() {
+        let unfolded = unfold(tensor, mode);
+        let (rows, columns) = (unfolded.shape()[0], unfolded.shape()[1]);
+        // A thin SVD has only min(rows, columns) left vectors.
+        let svd_mode = if rank > columns {
+            SvdMode::Full
+        } else {
+            SvdMode::Thin
+        };
+        let (u, _, _) = svd(&unfolded, svd_mode)?;
+        let width = u.shape()[1];
+        let data: Vec<T> = u
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i % width < rank)
+            .map(|(_, &x)| x)
+            .collect();
+        factors.push(Tensor::new(data, vec![rows, rank])?);
+    }
+    let core = factors
+        .iter()
+        .enumerate()
+        .fold(tensor.to_contiguous(), |acc, (mode, factor)| {
+            mode_product(&acc, factor, mode, true)
+        });
+    Ok(TuckerDecomposition { core, factors })
+}
+
+/// Returns the mode-`mode` unfolding of `tensor`: the `[shape[mode], rest]`
+/// matrix whose rows are the slices along that axis.
+fn unfold<T: Float>(tensor: &Tensor<T>, mode: usize) -> Tensor<T> {
+    let len = tensor.shape()[mode];
+    let moved = tensor.moveaxis(mode as isize, 0).unwrap();
+    let rest = tensor.len() / len;
+    Tensor::new(moved.iter().copied().collect(), vec![len, rest]).unwrap()
+}
+
+/// Multiplies `tensor` along `mode` by the `[I, R]` matrix `factor`, or by its
+/// transpose if `transpose` is set, replacing that axis's length `R` with `I`
+/// (or `I` with `R`).
+fn mode_product<T: Float>(
+    tensor: &Tensor<T>,
+    factor: &Tensor<T>,
+    mode: usize,
+    transpose: bool,
+) -> Tensor<T> {
+    let unfolded = unfold(tensor, mode);
+    let (inner, rest) = (unfolded.shape()[0], unfolded.shape()[1]);
+    let (rows, cols) = (factor.shape()[0], factor.shape()[1]);
+    let out = if transpose { cols } else { rows };
+    let matrix: Vec<T> = factor.iter().copied().collect();
+    let weight = |j: usize, i: usize| {
+        if transpose {
+            matrix[i * cols + j]
+        } else {
+            matrix[j * cols + i]
+        }
+    };
+    let source: Vec<T> = unfolded.iter().copied().collect();
+    let data = (0..out * rest)
+        .map(|k| {
+            let (j, c) = (k / rest, k % rest);
+            (0..inner).fold(T::zero(), |acc, i| {
+                acc + weight(j, i) * source[i * rest + c]
+            })
+        })
+        .collect();
+    let mut shape = tensor.shape().to_vec();
+    shape.remove(mode);
+    shape.insert(0, out);
+    Tensor::new(data, shape)
+        .unwrap()
+        .moveaxis(0, mode as isize)
+        .unwrap()
+        .to_contiguous()
+}
+
+/// Returns the next value in `[0, 1)` from a SplitMix64 stream.
+fn next_unit(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Returns the elementwise product of the `R x R` Gram matrices `A^T A` of every
+/// factor except the one for `skip`.
+fn hadamard_of_grams<T: Float>(factors: &[Vec<T>], skip: usize, rank: usize) -> Vec<T> {
+    let mut result = vec![T::one(); rank * rank];
+    for (mode, factor) in factors.iter().enumerate() {
+        if mode == skip {
+            continue;
+        }
+        for r in 0..rank {
+            for s in 0..rank {
+                let dot = factor
+                    .chunks(rank)
+                    .fold(T::zero(), |acc, row| acc + row[r] * row[s]);
+                result[r * rank + s] = result[r * rank + s] * dot;
+            }
+        }
+    }
+    result
+}
+
+/// Multiplies the mode-`mode` unfolding of the tensor by the Khatri-Rao product
+/// of the other factors, without forming either, giving a `[shape[mode], R]`
+/// matrix.
+fn mttkrp<T: Float>(
+    data: &[T],
+    shape: &[usize],
+    factors: &[Vec<T>],
+    mode: usize,
+    rank: usize,
+) -> Vec<T> {
+    let mut result = vec![T::zero(); shape[mode] * rank];
+    let mut index = vec![0; shape.len()];
+    let mut product = vec![T::zero(); rank];
+    for &value in data {
+        product.iter_mut().for_each(|p| *p = value);
+        for (m, factor) in factors.iter().enumerate() {
+            if m != mode {
+                let row = &factor[index[m] * rank..(index[m] + 1) * rank];
+                for (p, &a) in product.iter_mut().zip(row) {
+                    *p = *p * a;
+                }
+            }
+        }
+        let row = &mut result[index[mode] * rank..(index[mode] + 1) * rank];
+        for (r, &p) in row.iter_mut().zip(&product) {
+            *r = *r + p;
+        }
+        advance(&mut index, shape);
+    }
+    result
+}
+
+/// Rebuilds the row-major tensor `sum_r w_r a_r ∘ b_r ∘ ...`.
+fn reconstruct<T: Float>(shape: &[usize], weights: &[T], factors: &[Vec<T>]) -> Tensor<T> {
+    let rank = weights.len();
+    let len = shape.iter().product();
+    let mut index = vec![0; shape.len()];
+    let mut data = Vec::with_capacity(len);
+    for _ in 0..len {
+        let value = (0..rank).fold(T::zero(), |acc, r| {
+            let term = factors
+                .iter()
+                .zip(&index)
+                .fold(weights[r], |p, (factor, &i)| p * factor[i * rank + r]);
+            acc + term
+        });
+        data.push(value);
+        advance(&mut index, shape);
+    }
+    Tensor::new(data, shape.to_vec()).unwrap()
+}
+
+/// Steps a row-major multi-index forward by one element.
+fn advance(index: &mut [usize], shape: &[usize]) {
+    for axis in (0..shape.len()).rev() {
+        index[axis] += 1;
+        if index[axis] < shape[axis] {
+            return;
+        }
+        index[axis] = 0;
+    }
+}
+
+/// Replaces every row `m` of the row-major `[n, R]` matrix `rows` with the
+/// solution `x` of `gram * x = m`. As `gram` is symmetric, this solves
+/// `gram * X^T = M^T` for all rows at once.
+fn solve_rows<T: Float>(gram: Vec<T>, rows: &mut [T], rank: usize) -> Result<(), TensorError> {
+    let n = rows.len() / rank;
+    let gram = Tensor::new(gram, vec![rank, rank])?;
+    let rhs = Tensor::new(rows.to_vec(), vec![n, rank])?.permute(&[1, 0])?;
+    let solution = solve(&gram, &rhs).map_err(|err| match err {
+        TensorError::SingularMatrix(_) => TensorError::ValueError(
+            "cp_als normal equations are singular; try a lower rank".to_string(),
+        ),
+        err => err,
+    })?;
+    for (x, &value) in rows.iter_mut().zip(solution.permute(&[1, 0])?.iter()) {
+        *x = value;
+    }
+    Ok(())
+}
+
+/// Scales every column of the row-major `[n, R]` matrix to unit norm and
+/// returns the original norms. All-zero columns are left unchanged.
+fn normalize_columns<T: Float>(matrix: &mut [T], rank: usize) -> Vec<T> {
+    let mut norms = vec![T::zero(); rank];
+    for row in matrix.chunks(rank) {
+        for (n, &x) in norms.iter_mut().zip(row) {
+            *n = *n + x * x;
+        }
+    }
+    norms.iter_mut().for_each(|n| *n = n.sqrt());
+    for row in matrix.chunks_mut(rank) {
+        for (x, &n) in row.iter_mut().zip(&norms) {
+            if n != T::zero() {
+                *x = *x / n;
+            }
+        }
+    }
+    norms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cp_als_recovers_low_rank_tensor() {
+        let a = [1.0, 2.0, 0.5, -1.0, 3.0, 1.0];
+        let b = [1.0, 0.0, 2.0, 1.0, -1.0, 2.0, 0.5, 1.5];
+        let c = [1.0, 1.0, 2.0, -1.0, 0.0, 3.0, 1.0, 0.5, -2.0, 1.0];
+        let x = Tensor::from_fn(vec![3, 4, 5], |i| {
+            (0..2)
+                .map(|r| a[i[0] * 2 + r] * b[i[1] * 2 + r] * c[i[2] * 2 + r])
+                .sum::<f64>()
+        });
+        let options = CpOptions {
+            max_iter: 1000,
+            tol: 1e-14,
+            seed: 7,
+        };
+
+        let cp = cp_als(&x, 2, options).unwrap();
+
+        assert_eq!(cp.factors.len(), 3);
+        assert_eq!(cp.factors[1].shape(), &[4, 2]);
+        assert!(cp.fit > 1.0 - 1e-6);
+        assert!(cp.reconstruct().max_abs_diff(&x).unwrap() < 1e-5);
+    }
+
+    #[test]
+    fn test_cp_als_validates_arguments() {
+        let vector = Tensor::new(vec![1.0, 2.0], vec![2]).unwrap();
+        let matrix = Tensor::new(vec![1.0; 4], vec![2, 2]).unwrap();
+
+        assert!(matches!(
+            cp_als(&vector, 1, CpOptions::default()),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            cp_als(&matrix, 0, CpOptions::default()),
+            Err(TensorError::ValueError(_))
+        ));
+
+        let token = crate::cancel::CancellationToken::new();
+        token.cancel();
+        assert!(matches!(
+            token.scoped(|| cp_als(&matrix, 1, CpOptions::default())),
+            Err(TensorError::Cancelled(_))
+        ));
+    }
+
+    #[test]
+    fn test_tucker_truncates_and_reconstructs() {
+        let x = Tensor::from_fn(vec![3, 4, 2], |i| {
+            ((i[0] + 1) * (i[1] + 2)) as f64 + (i[2] * i[0]) as f64 - 0.5 * i[1] as f64
+        });
+
+        let full = tucker(&x, &[3, 4, 2]).unwrap();
+        assert_eq!(full.core.shape(), &[3, 4, 2]);
+        assert!(full.reconstruct().max_abs_diff(&x).unwrap() < 1e-10);
+
+        // Ranks above the other axes' product need the full set of left vectors.
+        let tall = Tensor::from_fn(vec![4, 2], |i| (i[0] * 2 + i[1] * i[1]) as f64);
+        let tall_full = tucker(&tall, &[4, 2]).unwrap();
+        assert_eq!(tall_full.factors[0].shape(), &[4, 4]);
+        assert!(tall_full.reconstruct().max_abs_diff(&tall).unwrap() < 1e-10);
+
+        // A rank-(1, 1, 1) tensor is recovered exactly from a 1x1x1 core.
+        let a = [1.0, -2.0, 0.5];
+        let b = [2.0, 1.0, 0.0, 3.0];
+        let c = [1.0, 4.0];
+        let rank_one = Tensor::from_fn(vec![3, 4, 2], |i| a[i[0]] * b[i[1]] * c[i[2]]);
+        let truncated = tucker(&rank_one, &[1, 1, 1]).unwrap();
+        assert_eq!(truncated.factors[1].shape(), &[4, 1]);
+        assert!(truncated.reconstruct().max_abs_diff(&rank_one).unwrap() < 1e-10);
+
+        // Factor columns are orthonormal.
+        let u = &full.factors[1];
+        for (r, s) in [(0, 0), (0, 1), (2, 3), (3, 3)] {
+            let dot: f64 = (0..4)
+                .map(|i| u.get(&[i, r]).unwrap() * u.get(&[i, s]).unwrap())
+                .sum();
+            assert!((dot - if r == s { 1.0 } else { 0.0 }).abs() < 1e-10);
+        }
+
+        assert!(matches!(
+            tucker(&x, &[3, 4]),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            tucker(&x, &[3, 5, 2]),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            tucker(&x, &[0, 1, 1]),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+
+    #[test]
+    fn test_cp_als_zero_tensor() {
+        let zeros = Tensor::new(vec![0.0; 12], vec![2, 3, 2]).unwrap();
+
+        let cp = cp_als(&zeros, 2, CpOptions::default()).unwrap();
+
+        assert_eq!(cp.weights.as_slice(), Some(&[0.0, 0.0][..]));
+        assert_eq!(cp.factors[1].shape(), &[3, 2]);
+        assert_eq!(cp.fit, 1.0);
+        assert_eq!(cp.iterations, 0);
+        assert_eq!(cp.reconstruct(), zeros);
+    }
+}
@@ -0,0 +1,161 @@
+//! Finite differences along an axis.
+
+use std::ops::Sub;
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+impl<T: Copy> Tensor<T> {
+    /// Builds a tensor with `axis` resized to `len` by filling every lane with
+    /// `f`, which receives the input lane and the output lane.
+    fn map_lanes<F>(&self, axis: usize, len: usize, mut f: F) -> Result<Tensor<T>, TensorError>
+    where
+        F: FnMut(&[T], &mut [T]),
+    {
+        let (_, starts) = self.lanes(axis)?;
+        let in_len = self.shape[axis];
+        let stride = self.strides[axis];
+
+        let mut shape = self.shape.clone();
+        shape[axis] = len;
+        let outer: usize = starts.len();
+        let mut result = match self.data.first() {
+            Some(&fill) if outer * len > 0 => Tensor::new(vec![fill; outer * len], shape)?,
+            _ => Tensor::new(Vec::new(), shape)?,
+        };
+        let (_, targets) = result.lanes(axis)?;
+        let target_stride = result.strides[axis];
+
+        let mut input = Vec::with_capacity(in_len);
+        let mut output = Vec::with_capacity(len);
+        for (start, target) in starts.into_iter().zip(targets) {
+            input.clear();
+            input.extend((0..in_len).map(|k| self.data[start + k * stride]));
+            // Any `len > 0` output comes from a non-empty input lane.
+            output.clear();
+            if let Some(&first) = input.first() {
+                output.resize(len, first);
+            }
+            f(&input, &mut output);
+            for (k, &value) in output.iter().enumerate() {
+                result.data[target + k * target_stride] = value;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> Tensor<T> {
+    /// Returns the `n`-th discrete difference along `axis`.
+    ///
+    /// The first difference is `out[k] = x[k + 1] - x[k]`; higher orders apply it
+    /// repeatedly. Each application shortens `axis` by one, and once it reaches
+    /// length zero further differences stay empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let x = Tensor::new(vec![1, 4, 9, 16], vec![4]).unwrap();
+    /// assert_eq!(x.diff(0, 1).unwrap().as_slice(), Some(&[3, 5, 7][..]));
+    /// assert_eq!(x.diff(0, 2).unwrap().as_slice(), Some(&[2, 2][..]));
+    /// ```
+    pub fn diff(&self, axis: usize, n: usize) -> Result<Tensor<T>, TensorError> {
+        self.check_axis(axis)?;
+        let len = self.shape[axis].saturating_sub(n);
+        self.map_lanes(axis, len, |input, output| {
+            let mut lane = input.to_vec();
+            for _ in 0..n.min(input.len()) {
+                lane = lane.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            }
+            output.copy_from_slice(&lane);
+        })
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns the numerical derivative along `axis` for samples `spacing` apart.
+    ///
+    /// Interior points use second-order central differences
+    /// `(x[k + 1] - x[k - 1]) / (2 * spacing)` and the two ends use first-order
+    /// one-sided differences, matching `numpy.gradient` with its default
+    /// `edge_order=1`. The result has the shape of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds and
+    /// `TensorError::ValueError` if `axis` has fewer than two samples or
+    /// `spacing` is zero.
+    pub fn gradient(&self, axis: usize, spacing: T) -> Result<Tensor<T>, TensorError> {
+        self.check_axis(axis)?;
+        let len = self.shape[axis];
+        if len < 2 {
+            return Err(TensorError::ValueError(format!(
+                "gradient needs at least two samples along axis {}, got {}",
+                axis, len
+            )));
+        }
+        if spacing == T::zero() {
+            return Err(TensorError::ValueError(
+                "gradient spacing must be non-zero".to_string(),
+            ));
+        }
+
+        let two = T::from_usize(2);
+        self.map_lanes(axis, len, |x, out| {
+            out[0] = (x[1] - x[0]) / spacing;
+            for k in 1..len - 1 {
+                out[k] = (x[k + 1] - x[k - 1]) / (two * spacing);
+            }
+            out[len - 1] = (x[len - 1] - x[len - 2]) / spacing;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_along_axes_and_orders() {
+        let tensor = Tensor::new(vec![1, 2, 4, 7, 0, 10, 20, 30], vec![2, 4]).unwrap();
+
+        assert_eq!(tensor.diff(1, 1).unwrap().data, vec![1, 2, 3, 10, 10, 10]);
+        assert_eq!(tensor.diff(1, 2).unwrap().data, vec![1, 1, 0, 0]);
+        assert_eq!(tensor.diff(0, 1).unwrap().data, vec![-1, 8, 16, 23]);
+        assert_eq!(tensor.diff(1, 0).unwrap(), tensor);
+        assert_eq!(tensor.diff(1, 5).unwrap().shape(), &[2, 0]);
+        assert!(matches!(tensor.diff(2, 1), Err(TensorError::AxisError(_))));
+    }
+
+    #[test]
+    fn test_gradient_matches_numpy() {
+        let x = Tensor::new(vec![1.0, 2.0, 4.0, 7.0, 11.0], vec![5]).unwrap();
+
+        let result = x.gradient(0, 1.0).unwrap();
+        assert_eq!(result.as_slice(), Some(&[1.0, 1.5, 2.5, 3.5, 4.0][..]));
+
+        let result = x.gradient(0, 0.5).unwrap();
+        assert_eq!(result.as_slice(), Some(&[2.0, 3.0, 5.0, 7.0, 8.0][..]));
+
+        let columns = Tensor::new(vec![0.0, 1.0, 2.0, 4.0], vec![2, 2]).unwrap();
+        assert_eq!(
+            columns.gradient(0, 1.0).unwrap().data,
+            vec![2.0, 3.0, 2.0, 3.0]
+        );
+        assert!(
+            Tensor::new(vec![1.0], vec![1])
+                .unwrap()
+                .gradient(0, 1.0)
+                .is_err()
+        );
+        assert!(x.gradient(0, 0.0).is_err());
+    }
+}
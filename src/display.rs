@@ -0,0 +1,363 @@
+//! Text formatting of tensors.
+
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::config::Config;
+use crate::tensor::Tensor;
+
+/// Options controlling how tensors are printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// Tensors with more elements than this are summarized, printing only the
+    /// leading and trailing `edge_items` entries of each long axis.
+    pub threshold: usize,
+    /// The number of entries printed at each end of a summarized axis.
+    pub edge_items: usize,
+}
+
+impl Default for PrintOptions {
+    /// Matches NumPy: summarize above 1000 elements, keeping 3 items per edge.
+    fn default() -> Self {
+        Self {
+            threshold: 1000,
+            edge_items: 3,
+        }
+    }
+}
+
+/// How individual elements are rendered, derived from a `Formatter`'s flags.
+struct ElementFormat {
+    precision: Option<usize>,
+    sign_plus: bool,
+    /// Every element is padded to this width, so columns line up.
+    width: usize,
+    align: fmt::Alignment,
+    fill: char,
+}
+
+impl ElementFormat {
+    /// Plain `Debug` rendering, right-aligned and padded with spaces.
+    fn plain() -> Self {
+        Self {
+            precision: None,
+            sign_plus: false,
+            width: 0,
+            align: fmt::Alignment::Right,
+            fill: ' ',
+        }
+    }
+
+    /// Captures the precision, sign, width, alignment and fill of `f`.
+    fn from_formatter(f: &Formatter<'_>) -> Self {
+        Self {
+            precision: f.precision(),
+            sign_plus: f.sign_plus(),
+            width: f.width().unwrap_or(0),
+            align: f.align().unwrap_or(fmt::Alignment::Right),
+            fill: f.fill(),
+        }
+    }
+
+    /// Widens the column width to fit the longest element that will be printed.
+    fn fit<T: Debug>(mut self, tensor: &Tensor<T>, edge_items: Option<usize>) -> Self {
+        let mut width = self.width;
+        for_each_visible(
+            &tensor.data,
+            &tensor.shape,
+            &tensor.strides,
+            edge_items,
+            &mut |value| {
+                width = width.max(self.render(value).chars().count());
+            },
+        );
+        self.width = width;
+        self
+    }
+
+    /// Renders `value` with the captured precision and sign flags, unpadded.
+    fn render<T: Debug>(&self, value: &T) -> String {
+        match (self.precision, self.sign_plus) {
+            (Some(precision), true) => format!("{:+.*?}", precision, value),
+            (Some(precision), false) => format!("{:.*?}", precision, value),
+            (None, true) => format!("{:+?}", value),
+            (None, false) => format!("{:?}", value),
+        }
+    }
+
+    /// Writes `value` padded to the column width.
+    fn write<T: Debug>(&self, f: &mut Formatter<'_>, value: &T) -> fmt::Result {
+        let text = self.render(value);
+        let padding = self.width.saturating_sub(text.chars().count());
+        let (before, after) = match self.align {
+            fmt::Alignment::Left => (0, padding),
+            fmt::Alignment::Center => (padding / 2, padding - padding / 2),
+            fmt::Alignment::Right => (padding, 0),
+        };
+
+        for _ in 0..before {
+            write!(f, "{}", self.fill)?;
+        }
+        write!(f, "{}", text)?;
+        for _ in 0..after {
+            write!(f, "{}", self.fill)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the indices printed along an axis of length `len`; `None` marks
+/// the `...` that replaces the middle of a summarized axis.
+fn visible_indices(len: usize, edge_items: Option<usize>) -> Vec<Option<usize>> {
+    match edge_items {
+        Some(edge) if len > 2 * edge => (0..edge)
+            .map(Some)
+            .chain(std::iter::once(None))
+            .chain((len - edge..len).map(Some))
+            .collect(),
+        _ => (0..len).map(Some).collect(),
+    }
+}
+
+/// Calls `visit` with every element that will be printed.
+fn for_each_visible<T>(
+    data: &[T],
+    shape: &[usize],
+    strides: &[usize],
+    edge_items: Option<usize>,
+    visit: &mut impl FnMut(&T),
+) {
+    if shape.is_empty() {
+        return visit(&data[0]);
+    }
+
+    for i in visible_indices(shape[0], edge_items).into_iter().flatten() {
+        let offset = i * strides[0];
+        for_each_visible(
+            &data[offset..],
+            &shape[1..],
+            &strides[1..],
+            edge_items,
+            visit,
+        );
+    }
+}
+
+/// Helper function for pretty-printing tensors.
+///
+/// The innermost axis is printed on a single line, with every element padded
+/// to a common width so that matrices line up as a grid. When `edge_items` is
+/// set, axes longer than twice its value print only their leading and trailing
+/// entries, separated by `...`.
+fn format_recursive<T: Debug>(
+    f: &mut Formatter<'_>,
+    data: &[T],
+    shape: &[usize],
+    strides: &[usize],
+    level: usize,
+    edge_items: Option<usize>,
+    format: &ElementFormat,
+) -> fmt::Result {
+    if shape.is_empty() {
+        return format.write(f, &data[0]);
+    }
+
+    let indices = visible_indices(shape[0], edge_items);
+    if shape.len() == 1 {
+        write!(f, "[")?;
+        for (n, index) in indices.iter().enumerate() {
+            if n > 0 {
+                write!(f, ", ")?;
+            }
+            match index {
+                Some(i) => format.write(f, &data[i * strides[0]])?,
+                None => write!(f, "...")?,
+            }
+        }
+        return write!(f, "]");
+    }
+
+    let indent = " ".repeat(level * 2);
+    writeln!(f, "[")?;
+
+    for (n, index) in indices.iter().enumerate() {
+        write!(f, "{}  ", indent)?;
+        match index {
+            Some(i) => {
+                let offset = i * strides[0];
+                format_recursive(
+                    f,
+                    &data[offset..],
+                    &shape[1..],
+                    &strides[1..],
+                    level + 1,
+                    edge_items,
+                    format,
+                )?;
+            }
+            None => write!(f, "...")?,
+        }
+        if n < indices.len() - 1 {
+            writeln!(f, ",")?;
+        } else {
+            writeln!(f)?;
+        }
+    }
+
+    write!(f, "{}]", indent)
+}
+
+/// A tensor paired with the options used to print it; see [`Tensor::display_with`].
+pub struct TensorDisplay<'a, T> {
+    tensor: &'a Tensor<T>,
+    options: PrintOptions,
+}
+
+impl<T: Debug> Display for TensorDisplay<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let tensor = self.tensor;
+        if tensor.shape.is_empty() {
+            return writeln!(f, "[]");
+        }
+
+        if tensor.shape.contains(&0) {
+            return writeln!(f, "[]");
+        }
+
+        let edge_items = (tensor.len() > self.options.threshold).then_some(self.options.edge_items);
+        let format = ElementFormat::from_formatter(f).fit(tensor, edge_items);
+        format_recursive(
+            f,
+            &tensor.data,
+            &tensor.shape,
+            &tensor.strides,
+            0,
+            edge_items,
+            &format,
+        )
+    }
+}
+
+impl<T: Debug> Display for Tensor<T> {
+    /// Prints the tensor with the print options of the current [`Config`],
+    /// summarizing large tensors.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.display_with(Config::current().print_options), f)
+    }
+}
+
+impl<T: Debug> Tensor<T> {
+    /// Returns a value that prints the tensor using `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::creation::arange;
+    /// use tiny_tensor::display::PrintOptions;
+    ///
+    /// let t = arange(0, 10, 1).unwrap();
+    /// let options = PrintOptions { threshold: 5, edge_items: 1 };
+    /// assert_eq!(t.display_with(options).to_string(), "[0, ..., 9]");
+    /// ```
+    pub fn display_with(&self, options: PrintOptions) -> TensorDisplay<'_, T> {
+        TensorDisplay {
+            tensor: self,
+            options,
+        }
+    }
+
+    /// Formats the tensor in the bracketed `Display` layout, always printing every
+    /// element with its `Debug` representation.
+    ///
+    /// For `f32` and `f64` this is the shortest string that parses back to the
+    /// same value, so the output round-trips exactly through [`Tensor::parse`].
+    /// Unlike `Display`, a zero-dimensional tensor prints its single value, and
+    /// large tensors are never summarized. Tensors with a zero-length axis print
+    /// as `[]`.
+    pub fn to_string_precise(&self) -> String {
+        struct Precise<'a, T>(&'a Tensor<T>);
+
+        impl<T: Debug> Display for Precise<'_, T> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                let tensor = self.0;
+                if tensor.shape.contains(&0) {
+                    return write!(f, "[]");
+                }
+
+                let format = ElementFormat::plain().fit(tensor, None);
+                format_recursive(
+                    f,
+                    &tensor.data,
+                    &tensor.shape,
+                    &tensor.strides,
+                    0,
+                    None,
+                    &format,
+                )
+            }
+        }
+
+        Precise(self).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_small_tensor() {
+        let tensor = Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap();
+
+        assert_eq!(tensor.to_string(), "[\n  [1, 2],\n  [3, 4]\n]");
+    }
+
+    #[test]
+    fn test_display_summarizes_large_tensor() {
+        let tensor = Tensor::new((0..2000).collect(), vec![2000]).unwrap();
+
+        let text = tensor.to_string();
+
+        assert_eq!(text, "[   0,    1,    2, ..., 1997, 1998, 1999]");
+        assert!(!tensor.to_string_precise().contains("..."));
+    }
+
+    #[test]
+    fn test_display_with_summarizes_every_long_axis() {
+        let tensor = Tensor::new((0..30).collect(), vec![5, 6]).unwrap();
+        let options = PrintOptions {
+            threshold: 10,
+            edge_items: 1,
+        };
+
+        let text = tensor.display_with(options).to_string();
+
+        assert_eq!(text, "[\n  [ 0, ...,  5],\n  ...,\n  [24, ..., 29]\n]");
+    }
+
+    #[test]
+    fn test_display_honors_precision_and_width() {
+        let tensor = Tensor::new(vec![1.0, -2.5, 3.3, 10.0], vec![2, 2]).unwrap();
+
+        assert_eq!(
+            format!("{:.2}", tensor),
+            "[\n  [ 1.00, -2.50],\n  [ 3.30, 10.00]\n]"
+        );
+        assert_eq!(
+            format!("{:7.1}", tensor),
+            "[\n  [    1.0,    -2.5],\n  [    3.3,    10.0]\n]"
+        );
+        assert_eq!(
+            format!("{:<+.1}", tensor),
+            "[\n  [+1.0 , -2.5 ],\n  [+3.3 , +10.0]\n]"
+        );
+    }
+
+    #[test]
+    fn test_display_aligns_columns() {
+        let tensor = Tensor::new(vec![1, 200, -30, 4], vec![2, 2]).unwrap();
+
+        assert_eq!(tensor.to_string(), "[\n  [  1, 200],\n  [-30,   4]\n]");
+        assert_eq!(tensor.to_string_precise(), tensor.to_string());
+    }
+}
@@ -0,0 +1,266 @@
+//! Pairwise distances between the rows of two matrices.
+//!
+//! The full `[n, m]` result of a pairwise operation can be far larger than its
+//! inputs, so the evaluators here work through the rows of the first matrix a
+//! chunk at a time and hand each `[chunk, m]` block to a callback, keeping peak
+//! memory proportional to the chunk size.
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// A distance between two vectors, used by [`cdist`] and [`cdist_chunked`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// The L2 distance `sqrt(sum((x - y)^2))`.
+    Euclidean,
+    /// The squared L2 distance `sum((x - y)^2)`.
+    SquaredEuclidean,
+    /// The L1 distance `sum(|x - y|)`.
+    Manhattan,
+    /// The L-infinity distance `max(|x - y|)`.
+    Chebyshev,
+    /// One minus the cosine similarity; zero vectors are at distance 1 from everything.
+    Cosine,
+}
+
+impl Metric {
+    /// Returns the distance between `x` and `y`, which have equal lengths.
+    fn distance<T: Float>(self, x: &[T], y: &[T]) -> T {
+        let pairs = x.iter().zip(y);
+        match self {
+            Metric::Euclidean => Metric::SquaredEuclidean.distance(x, y).sqrt(),
+            Metric::SquaredEuclidean => pairs.fold(T::zero(), |acc, (&a, &b)| {
+                let d = a - b;
+                acc + d * d
+            }),
+            Metric::Manhattan => pairs.fold(T::zero(), |acc, (&a, &b)| acc + (a - b).abs()),
+            Metric::Chebyshev => pairs.fold(T::zero(), |acc, (&a, &b)| {
+                let d = (a - b).abs();
+                if d > acc { d } else { acc }
+            }),
+            Metric::Cosine => {
+                let (dot, xx, yy) = pairs.fold(
+                    (T::zero(), T::zero(), T::zero()),
+                    |(dot, xx, yy), (&a, &b)| (dot + a * b, xx + a * a, yy + b * b),
+                );
+                let norm = (xx * yy).sqrt();
+                if norm == T::zero() {
+                    T::one()
+                } else {
+                    T::one() - dot / norm
+                }
+            }
+        }
+    }
+}
+
+/// Returns the rows of the 2D tensor `matrix` as one row-major buffer and the
+/// number of columns.
+fn rows<T: Copy>(matrix: &Tensor<T>, what: &str) -> Result<(Vec<T>, usize), TensorError> {
+    match matrix.shape() {
+        [_, cols] => Ok((matrix.iter().copied().collect(), *cols)),
+        shape => Err(TensorError::ShapeError(format!(
+            "{} must be 2-dimensional, got shape {:?}",
+            what, shape
+        ))),
+    }
+}
+
+/// Evaluates `op` on every pair of rows of `a` (`[n, d]`) and `b` (`[m, d]`),
+/// `chunk_rows` rows of `a` at a time.
+///
+/// For each chunk, `sink` receives the index of the chunk's first row of `a`
+/// and a `[rows, m]` block whose element `[i, j]` is `op(a[start + i], b[j])`.
+/// Chunks arrive in order and only one block is alive at a time. An error
+/// returned by `sink` stops the evaluation and is passed on.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` or `b` is not 2D or their column
+/// counts differ, and `TensorError::ValueError` if `chunk_rows` is zero.
+pub fn pairwise_chunked<T, U, F, S>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    chunk_rows: usize,
+    mut op: F,
+    mut sink: S,
+) -> Result<(), TensorError>
+where
+    T: Copy,
+    U: Copy,
+    F: FnMut(&[T], &[T]) -> U,
+    S: FnMut(usize, Tensor<U>) -> Result<(), TensorError>,
+{
+    let (a_rows, a_cols) = rows(a, "first operand")?;
+    let (b_rows, b_cols) = rows(b, "second operand")?;
+    if a_cols != b_cols {
+        return Err(TensorError::ShapeError(format!(
+            "rows of length {} cannot be paired with rows of length {}",
+            a_cols, b_cols
+        )));
+    }
+    if chunk_rows == 0 {
+        return Err(TensorError::ValueError(
+            "chunk_rows must be at least 1".to_string(),
+        ));
+    }
+
+    let m = b.shape()[0];
+    for start in (0..a.shape()[0]).step_by(chunk_rows) {
+        let end = (start + chunk_rows).min(a.shape()[0]);
+        let mut block = Vec::with_capacity((end - start) * m);
+        for i in start..end {
+            let x = &a_rows[i * a_cols..(i + 1) * a_cols];
+            for j in 0..m {
+                block.push(op(x, &b_rows[j * b_cols..(j + 1) * b_cols]));
+            }
+        }
+        sink(start, Tensor::new(block, vec![end - start, m])?)?;
+    }
+
+    Ok(())
+}
+
+/// Computes the distances between every row of `a` and every row of `b`,
+/// streaming the result to `sink` in blocks of `chunk_rows` rows of `a`.
+///
+/// See [`pairwise_chunked`] for how blocks are delivered.
+///
+/// # Errors
+///
+/// See [`pairwise_chunked`].
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::distance::{cdist_chunked, Metric};
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let points = Tensor::new(vec![0.0, 0.0, 3.0, 4.0, 6.0, 8.0], vec![3, 2]).unwrap();
+/// let mut nearest = Vec::new();
+/// cdist_chunked(&points, &points, Metric::Euclidean, 2, |_, block| {
+///     for row in block.as_slice().unwrap().chunks(3) {
+///         nearest.push(row.iter().copied().filter(|&d| d > 0.0).fold(f64::MAX, f64::min));
+///     }
+///     Ok(())
+/// })
+/// .unwrap();
+/// assert_eq!(nearest, vec![5.0, 5.0, 5.0]);
+/// ```
+pub fn cdist_chunked<T, S>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    metric: Metric,
+    chunk_rows: usize,
+    sink: S,
+) -> Result<(), TensorError>
+where
+    T: Float,
+    S: FnMut(usize, Tensor<T>) -> Result<(), TensorError>,
+{
+    pairwise_chunked(a, b, chunk_rows, |x, y| metric.distance(x, y), sink)
+}
+
+/// Computes the full `[n, m]` matrix of distances between the rows of `a`
+/// (`[n, d]`) and `b` (`[m, d]`).
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` or `b` is not 2D or their column
+/// counts differ.
+pub fn cdist<T: Float>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    metric: Metric,
+) -> Result<Tensor<T>, TensorError> {
+    let mut data = Vec::new();
+    cdist_chunked(
+        a,
+        b,
+        metric,
+        a.shape().first().copied().unwrap_or(1).max(1),
+        |_, block| {
+            data.extend(block.iter().copied());
+            Ok(())
+        },
+    )?;
+    Tensor::new(data, vec![a.shape()[0], b.shape()[0]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cdist_metrics() {
+        let a = Tensor::new(vec![0.0, 0.0, 1.0, 1.0], vec![2, 2]).unwrap();
+        let b = Tensor::new(vec![3.0, 4.0, 1.0, 0.0, 0.0, 0.0], vec![3, 2]).unwrap();
+
+        let euclidean = cdist(&a, &b, Metric::Euclidean).unwrap();
+        assert_eq!(euclidean.shape(), &[2, 3]);
+        assert_eq!(euclidean.get(&[0, 0]), Some(&5.0));
+        assert_eq!(
+            cdist(&a, &b, Metric::SquaredEuclidean)
+                .unwrap()
+                .get(&[1, 0]),
+            Some(&13.0)
+        );
+        assert_eq!(
+            cdist(&a, &b, Metric::Manhattan).unwrap().get(&[1, 0]),
+            Some(&5.0)
+        );
+        assert_eq!(
+            cdist(&a, &b, Metric::Chebyshev).unwrap().get(&[1, 0]),
+            Some(&3.0)
+        );
+
+        let cosine = cdist(&a, &b, Metric::Cosine).unwrap();
+        assert_eq!(cosine.get(&[0, 1]), Some(&1.0));
+        assert!((cosine.get(&[1, 1]).unwrap() - (1.0 - 1.0 / 2f64.sqrt())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cdist_chunked_matches_full_result() {
+        let a = Tensor::from_fn(vec![7, 3], |i| (i[0] * 3 + i[1]) as f64);
+        let b = Tensor::from_fn(vec![4, 3], |i| (i[0] as f64) - (i[1] as f64));
+        let full = cdist(&a, &b, Metric::Manhattan).unwrap();
+
+        let mut starts = Vec::new();
+        let mut data = Vec::new();
+        cdist_chunked(&a, &b, Metric::Manhattan, 3, |start, block| {
+            assert!(block.shape()[0] <= 3);
+            starts.push(start);
+            data.extend(block.iter().copied());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(starts, vec![0, 3, 6]);
+        assert_eq!(data, full.as_slice().unwrap());
+    }
+
+    #[test]
+    fn test_pairwise_chunked_errors() {
+        let a = Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap();
+        let wide = Tensor::new(vec![1, 2, 3], vec![1, 3]).unwrap();
+        let op = |x: &[i32], y: &[i32]| x[0] * y[0];
+
+        assert!(matches!(
+            pairwise_chunked(&a, &wide, 1, op, |_, _| Ok(())),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            pairwise_chunked(&a, &a, 0, op, |_, _| Ok(())),
+            Err(TensorError::ValueError(_))
+        ));
+
+        let mut calls = 0;
+        let stopped = pairwise_chunked(&a, &a, 1, op, |_, _| {
+            calls += 1;
+            Err(TensorError::IoError("disk full".to_string()))
+        });
+        assert!(stopped.is_err());
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,414 @@
+//! Einops-style axis rearrangement and reduction described by patterns.
+//!
+//! A pattern such as `"b (h w) c -> b c h w"` names the axes of the input on
+//! the left and of the output on the right. Parentheses group several named
+//! axes into one tensor axis, whose length is the product of theirs; `1` or
+//! `()` stands for an axis of length 1. Lengths that cannot be read off the
+//! input, such as `h` and `w` above, are passed in `sizes`, and at most one
+//! name per group may be left for inference.
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::{Offsets, Tensor};
+
+/// The axes of one side of a pattern, each a group of named axes.
+type Groups = Vec<Vec<String>>;
+
+/// How [`reduce`] combines the elements of the axes it removes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reduction {
+    /// The sum of the elements.
+    Sum,
+    /// The arithmetic mean of the elements.
+    Mean,
+    /// The largest element.
+    Max,
+    /// The smallest element.
+    Min,
+    /// The product of the elements.
+    Prod,
+}
+
+/// Returns a `ValueError` describing a problem with `pattern`.
+fn pattern_error(pattern: &str, problem: &str) -> TensorError {
+    TensorError::ValueError(format!("invalid pattern '{}': {}", pattern, problem))
+}
+
+/// Parses one side of `pattern` into groups of axis names.
+fn parse_side(side: &str, pattern: &str) -> Result<Groups, TensorError> {
+    let mut groups = Vec::new();
+    let mut open: Option<Vec<String>> = None;
+    let spaced = side.replace('(', " ( ").replace(')', " ) ");
+    for token in spaced.split_whitespace() {
+        match token {
+            "(" if open.is_some() => return Err(pattern_error(pattern, "nested parentheses")),
+            "(" => open = Some(Vec::new()),
+            ")" => match open.take() {
+                Some(group) => groups.push(group),
+                None => return Err(pattern_error(pattern, "unbalanced parentheses")),
+            },
+            // A unit axis adds nothing to a group.
+            "1" if open.is_some() => {}
+            "1" => groups.push(Vec::new()),
+            name => {
+                let valid = name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    && !name.starts_with(|c: char| c.is_ascii_digit());
+                if !valid {
+                    return Err(pattern_error(
+                        pattern,
+                        &format!("'{}' is not a valid axis name", name),
+                    ));
+                }
+                match open.as_mut() {
+                    Some(group) => group.push(name.to_string()),
+                    None => groups.push(vec![name.to_string()]),
+                }
+            }
+        }
+    }
+    if open.is_some() {
+        return Err(pattern_error(pattern, "unbalanced parentheses"));
+    }
+
+    let names: Vec<&String> = groups.iter().flatten().collect();
+    if let Some(name) = names
+        .iter()
+        .enumerate()
+        .find_map(|(i, name)| names[..i].contains(name).then_some(name))
+    {
+        return Err(pattern_error(
+            pattern,
+            &format!("axis '{}' appears twice on one side", name),
+        ));
+    }
+    Ok(groups)
+}
+
+/// A parsed pattern applied to a particular input.
+struct Plan {
+    /// The named axes of the input, in order.
+    names: Vec<String>,
+    /// The length of each named axis.
+    lengths: Vec<usize>,
+    /// The output axes.
+    right: Groups,
+}
+
+impl Plan {
+    /// Parses `pattern` and resolves the length of every named axis of `shape`.
+    fn new(shape: &[usize], pattern: &str, sizes: &[(&str, usize)]) -> Result<Self, TensorError> {
+        let (left, right) = pattern
+            .split_once("->")
+            .ok_or_else(|| pattern_error(pattern, "expected exactly one '->'"))?;
+        if right.contains("->") {
+            return Err(pattern_error(pattern, "expected exactly one '->'"));
+        }
+        let left = parse_side(left, pattern)?;
+        let right = parse_side(right, pattern)?;
+        if left.len() != shape.len() {
+            return Err(TensorError::ShapeError(format!(
+                "pattern '{}' describes {} axes, but the tensor has shape {:?}",
+                pattern,
+                left.len(),
+                shape
+            )));
+        }
+
+        let names: Vec<String> = left.iter().flatten().cloned().collect();
+        for (name, _) in sizes {
+            if !names.iter().any(|n| n == name) {
+                return Err(pattern_error(
+                    pattern,
+                    &format!("a size is given for '{}', which is not an input axis", name),
+                ));
+            }
+        }
+        if let Some(name) = right.iter().flatten().find(|name| !names.contains(name)) {
+            return Err(pattern_error(
+                pattern,
+                &format!("output axis '{}' does not appear in the input", name),
+            ));
+        }
+
+        let mut lengths = Vec::with_capacity(names.len());
+        for (group, &dim) in left.iter().zip(shape) {
+            let given: Vec<Option<usize>> = group
+                .iter()
+                .map(|name| sizes.iter().find(|(n, _)| n == name).map(|&(_, size)| size))
+                .collect();
+            let known: usize = given.iter().flatten().product();
+            let unknown = given.iter().filter(|size| size.is_none()).count();
+            let inferred = match unknown {
+                0 if known == dim => None,
+                1 if known != 0 && dim % known == 0 => Some(dim / known),
+                0 | 1 => {
+                    return Err(TensorError::ShapeError(format!(
+                        "axis ({}) of length {} cannot be split into the given sizes in pattern '{}'",
+                        group.join(" "),
+                        dim,
+                        pattern
+                    )));
+                }
+                _ => {
+                    return Err(pattern_error(
+                        pattern,
+                        &format!(
+                            "cannot infer more than one of the axes ({})",
+                            group.join(" ")
+                        ),
+                    ));
+                }
+            };
+            lengths.extend(given.iter().map(|size| size.or(inferred).unwrap_or(1)));
+        }
+
+        Ok(Plan {
+            names,
+            lengths,
+            right,
+        })
+    }
+
+    /// Returns the position of the input axis `name`.
+    fn position(&self, name: &str) -> usize {
+        self.names.iter().position(|n| n == name).unwrap_or(0)
+    }
+
+    /// Returns the elements of `x` with its named axes reordered to the output
+    /// order, followed by the input axes missing from the output.
+    fn reorder<T: Copy>(&self, x: &Tensor<T>) -> Result<Vec<T>, TensorError> {
+        let kept = self.right.iter().flatten().map(|name| self.position(name));
+        let dropped = (0..self.names.len())
+            .filter(|&i| !self.right.iter().flatten().any(|n| *n == self.names[i]));
+        let axes: Vec<usize> = kept.chain(dropped).collect();
+        // Read the elements straight out of a row-major buffer through the
+        // permuted strides, copying each element once.
+        let contiguous;
+        let data = match x.as_slice() {
+            Some(data) => data,
+            None => {
+                contiguous = x.to_contiguous();
+                &contiguous.data[..]
+            }
+        };
+        let strides = Tensor::<T>::calculate_strides(&self.lengths);
+        let shape: Vec<usize> = axes.iter().map(|&axis| self.lengths[axis]).collect();
+        let strides: Vec<usize> = axes.iter().map(|&axis| strides[axis]).collect();
+        Ok(Offsets::new(&shape, &strides)
+            .map(|offset| data[offset])
+            .collect())
+    }
+
+    /// Returns the shape of the output.
+    fn output_shape(&self) -> Vec<usize> {
+        self.right
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|name| self.lengths[self.position(name)])
+                    .product()
+            })
+            .collect()
+    }
+}
+
+/// Reorders, splits and merges the axes of `x` as described by `pattern`.
+///
+/// Every named input axis must appear in the output; use [`reduce`] to remove
+/// axes. `sizes` gives the lengths of named axes that cannot be inferred from
+/// the shape of `x`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if the pattern is malformed, an axis is
+/// missing from or unknown to one side, or a size names an axis that is not in
+/// the input, and `TensorError::ShapeError` if the pattern does not fit the
+/// shape of `x`.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::einops::rearrange;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// // A batch of 2 images of 2 x 3 pixels with 4 channels, stored as [b, (h w), c].
+/// let x = Tensor::from_fn(vec![2, 6, 4], |i| i[0] * 100 + i[1] * 10 + i[2]);
+/// let y = rearrange(&x, "b (h w) c -> b c h w", &[("h", 2)]).unwrap();
+/// assert_eq!(y.shape(), &[2, 4, 2, 3]);
+/// assert_eq!(y.get(&[1, 3, 1, 2]), Some(&153));
+/// ```
+pub fn rearrange<T: Copy>(
+    x: &Tensor<T>,
+    pattern: &str,
+    sizes: &[(&str, usize)],
+) -> Result<Tensor<T>, TensorError> {
+    let plan = Plan::new(x.shape(), pattern, sizes)?;
+    if let Some(name) = plan
+        .names
+        .iter()
+        .find(|name| !plan.right.iter().flatten().any(|n| n == *name))
+    {
+        return Err(pattern_error(
+            pattern,
+            &format!(
+                "input axis '{}' is missing from the output; use reduce to remove axes",
+                name
+            ),
+        ));
+    }
+    Tensor::new(plan.reorder(x)?, plan.output_shape())
+}
+
+/// Rearranges `x` like [`rearrange`], combining the elements of every input
+/// axis that is missing from the output with `op`.
+///
+/// # Errors
+///
+/// Returns the errors of [`rearrange`], except that input axes may be missing
+/// from the output, and `TensorError::ValueError` when taking the maximum or
+/// minimum over reduced axes with no elements.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::einops::{Reduction, reduce};
+/// use tiny_tensor::tensor::Tensor;
+///
+/// // Global average pooling of [b, c, h, w] images.
+/// let x = Tensor::from_fn(vec![1, 2, 2, 2], |i| (i[1] * 4 + i[2] * 2 + i[3]) as f64);
+/// let pooled = reduce(&x, "b c h w -> b c", Reduction::Mean, &[]).unwrap();
+/// assert_eq!(pooled.as_slice(), Some(&[1.5, 5.5][..]));
+/// // 2 x 2 max pooling.
+/// let x = Tensor::from_fn(vec![4, 4], |i| (i[0] * 4 + i[1]) as f64);
+/// let pooled = reduce(&x, "(h h2) (w w2) -> h w", Reduction::Max, &[("h2", 2), ("w2", 2)]).unwrap();
+/// assert_eq!(pooled.as_slice(), Some(&[5.0, 7.0, 13.0, 15.0][..]));
+/// ```
+pub fn reduce<T: Float>(
+    x: &Tensor<T>,
+    pattern: &str,
+    op: Reduction,
+    sizes: &[(&str, usize)],
+) -> Result<Tensor<T>, TensorError> {
+    let plan = Plan::new(x.shape(), pattern, sizes)?;
+    let shape = plan.output_shape();
+    let kept: usize = shape.iter().product();
+    let data = plan.reorder(x)?;
+    let reduced = data.len().checked_div(kept).unwrap_or(0);
+    if reduced == 0 && kept > 0 && matches!(op, Reduction::Max | Reduction::Min) {
+        return Err(TensorError::ValueError(format!(
+            "cannot take the {:?} over empty axes in pattern '{}'",
+            op, pattern
+        )));
+    }
+
+    let combine = |values: &[T]| match op {
+        Reduction::Sum => values.iter().fold(T::zero(), |acc, &v| acc + v),
+        Reduction::Mean => {
+            values.iter().fold(T::zero(), |acc, &v| acc + v) / T::from_usize(values.len())
+        }
+        Reduction::Prod => values.iter().fold(T::one(), |acc, &v| acc * v),
+        Reduction::Max => values[1..]
+            .iter()
+            .fold(values[0], |acc, &v| if v > acc { v } else { acc }),
+        Reduction::Min => values[1..]
+            .iter()
+            .fold(values[0], |acc, &v| if v < acc { v } else { acc }),
+    };
+    let result = if reduced == 0 {
+        (0..kept).map(|_| combine(&[])).collect()
+    } else {
+        data.chunks(reduced).map(combine).collect()
+    };
+    Tensor::new(result, shape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rearrange_splits_merges_and_permutes() {
+        let x = Tensor::from_fn(vec![2, 3, 4], |i| i[0] * 100 + i[1] * 10 + i[2]);
+
+        let t = rearrange(&x, "a b c -> c a b", &[]).unwrap();
+        assert_eq!(t, x.permute(&[2, 0, 1]).unwrap().to_contiguous());
+        // A strided input is read in its logical order.
+        let back = rearrange(&t.permute(&[1, 2, 0]).unwrap(), "a b c -> a b c", &[]).unwrap();
+        assert_eq!(back.as_slice(), x.as_slice());
+
+        let merged = rearrange(&x, "a b c -> (a b) c", &[]).unwrap();
+        assert_eq!(merged.shape(), &[6, 4]);
+        assert_eq!(merged.get(&[4, 1]), Some(&111));
+
+        // Splitting with an inferred length, then merging in another order.
+        let split = rearrange(&x, "a b (c1 c2) -> (c2 a) b c1 1", &[("c1", 2)]).unwrap();
+        assert_eq!(split.shape(), &[4, 3, 2, 1]);
+        assert_eq!(split.get(&[3, 2, 1, 0]), Some(&123));
+        let back = rearrange(&split, "(c2 a) b c1 () -> a b (c1 c2)", &[("a", 2)]).unwrap();
+        assert_eq!(back, x);
+    }
+
+    #[test]
+    fn test_rearrange_reports_pattern_errors() {
+        let x = Tensor::new(vec![0; 6], vec![2, 3]).unwrap();
+        let err =
+            |pattern: &str, sizes: &[(&str, usize)]| rearrange(&x, pattern, sizes).unwrap_err();
+
+        assert!(matches!(
+            err("a b c -> a b c", &[]),
+            TensorError::ShapeError(_)
+        ));
+        assert!(matches!(
+            err("a (b c) -> a b c", &[("b", 2)]),
+            TensorError::ShapeError(_)
+        ));
+        assert!(matches!(
+            err("a (b c) -> a b c", &[]),
+            TensorError::ValueError(_)
+        ));
+        assert!(matches!(err("a b -> a", &[]), TensorError::ValueError(_)));
+        assert!(matches!(
+            err("a b -> a b d", &[]),
+            TensorError::ValueError(_)
+        ));
+        assert!(matches!(err("a a -> a", &[]), TensorError::ValueError(_)));
+        assert!(matches!(
+            err("a (b -> a b", &[]),
+            TensorError::ValueError(_)
+        ));
+        assert!(matches!(err("a b", &[]), TensorError::ValueError(_)));
+        assert!(matches!(
+            err("a b -> b a", &[("z", 1)]),
+            TensorError::ValueError(_)
+        ));
+        let message = err("a b -> a", &[]).to_string();
+        assert!(message.contains("'b'"), "{}", message);
+    }
+
+    #[test]
+    fn test_reduce_operations() {
+        let x = Tensor::from_fn(vec![2, 3], |i| (i[0] * 3 + i[1] + 1) as f64);
+
+        let sum = reduce(&x, "a b -> b", Reduction::Sum, &[]).unwrap();
+        assert_eq!(sum.as_slice(), Some(&[5.0, 7.0, 9.0][..]));
+        let prod = reduce(&x, "a b -> a", Reduction::Prod, &[]).unwrap();
+        assert_eq!(prod.as_slice(), Some(&[6.0, 120.0][..]));
+        let min = reduce(&x, "a b -> b a", Reduction::Min, &[]).unwrap();
+        assert_eq!(min, x.permute(&[1, 0]).unwrap().to_contiguous());
+        let total = reduce(&x, "a b -> ", Reduction::Mean, &[]).unwrap();
+        assert_eq!(total.shape(), &[] as &[usize]);
+        assert_eq!(total.as_slice(), Some(&[3.5][..]));
+        let kept = reduce(&x, "a b -> a 1", Reduction::Max, &[]).unwrap();
+        assert_eq!(kept.as_slice(), Some(&[3.0, 6.0][..]));
+        assert_eq!(kept.shape(), &[2, 1]);
+
+        let empty = Tensor::<f64>::new(vec![], vec![2, 0]).unwrap();
+        let sum = reduce(&empty, "a b -> a", Reduction::Sum, &[]).unwrap();
+        assert_eq!(sum.as_slice(), Some(&[0.0, 0.0][..]));
+        assert!(matches!(
+            reduce(&empty, "a b -> a", Reduction::Max, &[]),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+}
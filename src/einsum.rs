@@ -0,0 +1,261 @@
+//! Einstein summation over labelled tensor axes.
+//!
+//! A specification such as `"bij,bjk->bik"` names every axis of every operand
+//! with a letter. Letters kept in the output are iterated over; every other
+//! letter is summed over after multiplying the operands together. One
+//! specification covers matrix products, transposes, traces, diagonals, outer
+//! products and their batched forms.
+
+use std::collections::BTreeMap;
+use std::ops::{Add, Mul};
+
+use crate::error::TensorError;
+use crate::num::Zero;
+use crate::tensor::{Offsets, Tensor};
+
+/// Returns the labels of one term of a specification, checking that each is
+/// an ASCII letter.
+fn labels(term: &str) -> Result<Vec<char>, TensorError> {
+    term.chars()
+        .map(|label| {
+            if label.is_ascii_alphabetic() {
+                Ok(label)
+            } else {
+                Err(TensorError::ValueError(format!(
+                    "einsum labels must be ASCII letters, got '{}' in '{}'",
+                    label, term
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Evaluates the Einstein summation `spec` over `operands`, as NumPy's
+/// `einsum` does.
+///
+/// `spec` lists the axis labels of each operand, separated by commas, then
+/// optionally `->` and the labels of the output; whitespace is ignored.
+/// Without `->`, the output holds the labels that appear exactly once, in
+/// alphabetical order. A label repeated within one operand selects its
+/// diagonal, a label shared between operands multiplies matching elements, and
+/// a label missing from the output is summed over. Every axis must be
+/// labelled; `...` is not supported.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `spec` is malformed: a non-letter
+/// label, a repeated or unknown output label, or a number of terms other than
+/// the number of operands (which must be at least one). Returns
+/// `TensorError::ShapeError` if a term does not label every axis of its
+/// operand, or one label is given different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::einsum::einsum;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap();
+/// let b = Tensor::new(vec![5, 6, 7, 8], vec![2, 2]).unwrap();
+///
+/// let product = einsum("ij,jk->ik", &[&a, &b]).unwrap();
+/// assert_eq!(product.as_slice(), Some(&[19, 22, 43, 50][..]));
+/// let transposed = einsum("ij->ji", &[&a]).unwrap();
+/// assert_eq!(transposed.as_slice(), Some(&[1, 3, 2, 4][..]));
+/// let trace = einsum("ii", &[&a]).unwrap();
+/// assert_eq!(trace.as_slice(), Some(&[5][..]));
+/// ```
+pub fn einsum<T>(spec: &str, operands: &[&Tensor<T>]) -> Result<Tensor<T>, TensorError>
+where
+    T: Copy + Zero + Add<Output = T> + Mul<Output = T>,
+{
+    let spec: String = spec.chars().filter(|c| !c.is_whitespace()).collect();
+    let (inputs, output) = match spec.split_once("->") {
+        Some((inputs, output)) => (inputs, Some(output)),
+        None => (spec.as_str(), None),
+    };
+    let terms = inputs
+        .split(',')
+        .map(labels)
+        .collect::<Result<Vec<_>, _>>()?;
+    if operands.is_empty() || terms.len() != operands.len() {
+        return Err(TensorError::ValueError(format!(
+            "einsum specification '{}' has {} terms for {} operands",
+            spec,
+            terms.len(),
+            operands.len()
+        )));
+    }
+
+    // The length of every label, and how many times it appears.
+    let mut sizes: BTreeMap<char, (usize, usize)> = BTreeMap::new();
+    for (term, operand) in terms.iter().zip(operands) {
+        if term.len() != operand.ndim() {
+            return Err(TensorError::ShapeError(format!(
+                "einsum term '{}' labels {} axes, but the operand has shape {:?}",
+                term.iter().collect::<String>(),
+                term.len(),
+                operand.shape()
+            )));
+        }
+        for (&label, &dim) in term.iter().zip(operand.shape()) {
+            let (size, count) = sizes.entry(label).or_insert((dim, 0));
+            if *size != dim {
+                return Err(TensorError::ShapeError(format!(
+                    "einsum label '{}' has lengths {} and {}",
+                    label, size, dim
+                )));
+            }
+            *count += 1;
+        }
+    }
+
+    let output = match output {
+        Some(output) => {
+            let output = labels(output)?;
+            for (i, label) in output.iter().enumerate() {
+                if output[..i].contains(label) || !sizes.contains_key(label) {
+                    return Err(TensorError::ValueError(format!(
+                        "einsum output label '{}' is repeated or not among the inputs",
+                        label
+                    )));
+                }
+            }
+            output
+        }
+        None => sizes
+            .iter()
+            .filter(|&(_, &(_, count))| count == 1)
+            .map(|(&label, _)| label)
+            .collect(),
+    };
+
+    // Iterate over the output labels, then the summed ones. An operand's
+    // stride for a label adds up the strides of every axis carrying it, which
+    // walks the diagonal of repeated labels.
+    let order: Vec<char> = output
+        .iter()
+        .copied()
+        .chain(
+            sizes
+                .keys()
+                .copied()
+                .filter(|label| !output.contains(label)),
+        )
+        .collect();
+    let dims: Vec<usize> = order.iter().map(|label| sizes[label].0).collect();
+    let (out_shape, summed_shape) = dims.split_at(output.len());
+    let walks: Vec<(Vec<usize>, Vec<usize>)> = terms
+        .iter()
+        .zip(operands)
+        .map(|(term, operand)| {
+            let strides: Vec<usize> = order
+                .iter()
+                .map(|label| {
+                    term.iter()
+                        .zip(&operand.strides)
+                        .filter(|&(axis_label, _)| axis_label == label)
+                        .map(|(_, &stride)| stride)
+                        .sum()
+                })
+                .collect();
+            let (out_strides, summed_strides) = strides.split_at(output.len());
+            (
+                Offsets::new(out_shape, out_strides).collect(),
+                Offsets::new(summed_shape, summed_strides).collect(),
+            )
+        })
+        .collect();
+
+    let size: usize = out_shape.iter().product();
+    let summed: usize = summed_shape.iter().product();
+    let data = (0..size)
+        .map(|o| {
+            (0..summed).fold(T::zero(), |acc, s| {
+                let mut factors = operands
+                    .iter()
+                    .zip(&walks)
+                    .map(|(operand, (bases, inner))| operand.data[bases[o] + inner[s]]);
+                let first = factors.next();
+                acc + factors.fold(first.unwrap_or_else(T::zero), |product, x| product * x)
+            })
+        })
+        .collect();
+    Tensor::new(data, out_shape.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_einsum_common_operations() {
+        let a = Tensor::from_fn(vec![2, 3], |i| (i[0] * 3 + i[1]) as i64);
+        let b = Tensor::from_fn(vec![3, 2], |i| (i[0] * 2 + i[1]) as i64 - 2);
+        let square = Tensor::from_fn(vec![3, 3], |i| (i[0] * 3 + i[1]) as i64);
+
+        let product = einsum("ij,jk->ik", &[&a, &b]).unwrap();
+        assert_eq!(product.as_slice(), Some(&[4, 7, 4, 16][..]));
+        assert_eq!(einsum("ij,jk", &[&a, &b]).unwrap(), product);
+        assert_eq!(
+            einsum("ij -> ji", &[&a]).unwrap(),
+            a.permute(&[1, 0]).unwrap().to_contiguous()
+        );
+        assert_eq!(
+            einsum("ii->i", &[&square]).unwrap().as_slice(),
+            Some(&[0, 4, 8][..])
+        );
+        assert_eq!(
+            einsum("ii->", &[&square]).unwrap().as_slice(),
+            Some(&[12][..])
+        );
+        assert_eq!(einsum("ij->", &[&a]).unwrap().as_slice(), Some(&[15][..]));
+        assert_eq!(
+            einsum("ij->j", &[&a]).unwrap().as_slice(),
+            Some(&[3, 5, 7][..])
+        );
+        let x = Tensor::new(vec![1, 2], vec![2]).unwrap();
+        assert_eq!(einsum("i,j->ij", &[&x, &x]).unwrap(), x.outer(&x).unwrap());
+        // A transposed operand is read through its strides.
+        let at = a.permute(&[1, 0]).unwrap();
+        assert_eq!(einsum("ji,jk->ik", &[&at, &b]).unwrap(), product);
+    }
+
+    #[test]
+    fn test_einsum_batched_product_and_errors() {
+        let a = Tensor::from_fn(vec![2, 2, 3], |i| (i[0] * 6 + i[1] * 3 + i[2]) as f64);
+        let b = Tensor::from_fn(vec![2, 3, 1], |i| (i[0] + i[1]) as f64);
+
+        let c = einsum("bij,bjk->bik", &[&a, &b]).unwrap();
+
+        assert_eq!(c.shape(), &[2, 2, 1]);
+        assert_eq!(c.as_slice(), Some(&[5.0, 14.0, 44.0, 62.0][..]));
+
+        let err = |result: Result<Tensor<f64>, TensorError>| result.unwrap_err();
+        assert!(matches!(
+            err(einsum("ij,jk->ik", &[&a, &b])),
+            TensorError::ShapeError(_)
+        ));
+        assert!(matches!(
+            err(einsum("bij,bkj->bik", &[&a, &b])),
+            TensorError::ShapeError(_)
+        ));
+        assert!(matches!(
+            err(einsum("bij->bii", &[&a])),
+            TensorError::ValueError(_)
+        ));
+        assert!(matches!(
+            err(einsum("bij->bx", &[&a])),
+            TensorError::ValueError(_)
+        ));
+        assert!(matches!(
+            err(einsum("b1j->b", &[&a])),
+            TensorError::ValueError(_)
+        ));
+        assert!(matches!(
+            err(einsum("bij", &[&a, &b])),
+            TensorError::ValueError(_)
+        ));
+        assert!(einsum::<f64>("", &[]).is_err());
+    }
+}
@@ -6,12 +6,33 @@ use std::fmt::{Display, Formatter, Result};
 pub enum TensorError {
     /// Error indicating a mismatch in shapes for an operation.
     ShapeError(String),
+    /// Error indicating that an axis argument is out of range for the tensor.
+    AxisError(String),
+    /// Error indicating that an argument has an invalid value.
+    ValueError(String),
+    /// Error indicating that text could not be parsed as a tensor.
+    ParseError(String),
+    /// Error indicating that reading or writing a file failed.
+    IoError(String),
+    /// Error indicating that a matrix is singular where an invertible one is required.
+    SingularMatrix(String),
+    /// Error indicating that a matrix is not symmetric positive definite where one is required.
+    NotPositiveDefinite(String),
+    /// Error indicating that a computation was stopped through a `CancellationToken`.
+    Cancelled(String),
 }
 
 impl Display for TensorError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
             TensorError::ShapeError(msg) => write!(f, "ShapeError: {}", msg),
+            TensorError::AxisError(msg) => write!(f, "AxisError: {}", msg),
+            TensorError::ValueError(msg) => write!(f, "ValueError: {}", msg),
+            TensorError::ParseError(msg) => write!(f, "ParseError: {}", msg),
+            TensorError::IoError(msg) => write!(f, "IoError: {}", msg),
+            TensorError::SingularMatrix(msg) => write!(f, "SingularMatrix: {}", msg),
+            TensorError::NotPositiveDefinite(msg) => write!(f, "NotPositiveDefinite: {}", msg),
+            TensorError::Cancelled(msg) => write!(f, "Cancelled: {}", msg),
         }
     }
 }
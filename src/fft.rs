@@ -0,0 +1,186 @@
+//! Fast Fourier transforms of any length, used internally by operations
+//! that are convolutions in disguise, and the complex arithmetic they share
+//! with filter design.
+//!
+//! Power-of-two lengths use the iterative radix-2 Cooley-Tukey algorithm;
+//! every other length is rewritten as a power-of-two convolution with
+//! Bluestein's chirp-z algorithm, so all lengths run in `O(n log n)`.
+
+use crate::num::Float;
+
+/// A complex number over a floating-point type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Complex<T> {
+    pub(crate) re: T,
+    pub(crate) im: T,
+}
+
+impl<T: Float> Complex<T> {
+    pub(crate) fn new(re: T, im: T) -> Self {
+        Complex { re, im }
+    }
+
+    /// Returns `e^(i theta)`.
+    fn from_angle(theta: T) -> Self {
+        Complex::new(theta.cos(), theta.sin())
+    }
+
+    pub(crate) fn add(self, other: Self) -> Self {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+
+    pub(crate) fn sub(self, other: Self) -> Self {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+
+    pub(crate) fn mul(self, other: Self) -> Self {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+
+    pub(crate) fn div(self, other: Self) -> Self {
+        let norm = other.re * other.re + other.im * other.im;
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / norm,
+            (self.im * other.re - self.re * other.im) / norm,
+        )
+    }
+
+    fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    fn scale(self, factor: T) -> Self {
+        Complex::new(self.re * factor, self.im * factor)
+    }
+}
+
+/// Computes the discrete Fourier transform of `data` in place, or its inverse
+/// (including the `1 / n` factor) if `inverse` is set.
+pub(crate) fn fft<T: Float>(data: &mut [Complex<T>], inverse: bool) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+    if n.is_power_of_two() {
+        radix2(data, inverse);
+    } else {
+        bluestein(data, inverse);
+    }
+    if inverse {
+        let factor = T::one() / T::from_usize(n);
+        data.iter_mut().for_each(|x| *x = x.scale(factor));
+    }
+}
+
+/// Returns `-1` for a forward transform and `1` for an inverse one, the sign
+/// of the exponent in the transform's kernel.
+fn sign<T: Float>(inverse: bool) -> T {
+    if inverse { T::one() } else { -T::one() }
+}
+
+/// Unscaled in-place transform of a power-of-two length.
+fn radix2<T: Float>(data: &mut [Complex<T>], inverse: bool) {
+    let n = data.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let tau = sign::<T>(inverse) * T::from_f64(std::f64::consts::TAU);
+    let mut len = 2;
+    while len <= n {
+        // Twiddles are computed directly rather than by repeated
+        // multiplication, so rounding error does not build up.
+        let twiddles: Vec<Complex<T>> = (0..len / 2)
+            .map(|k| Complex::from_angle(tau * T::from_usize(k) / T::from_usize(len)))
+            .collect();
+        for block in data.chunks_exact_mut(len) {
+            let (low, high) = block.split_at_mut(len / 2);
+            for ((a, b), &w) in low.iter_mut().zip(high.iter_mut()).zip(&twiddles) {
+                let t = b.mul(w);
+                *b = a.sub(t);
+                *a = a.add(t);
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Unscaled in-place transform of any length, as a power-of-two circular
+/// convolution with the chirp `w_j = e^(±i pi j^2 / n)`.
+fn bluestein<T: Float>(data: &mut [Complex<T>], inverse: bool) {
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let pi = sign::<T>(inverse) * T::from_f64(std::f64::consts::PI);
+    // j^2 is reduced modulo 2n first; the chirp has that period.
+    let chirp: Vec<Complex<T>> = (0..n)
+        .map(|j| {
+            let square = (j as u128 * j as u128 % (2 * n as u128)) as usize;
+            Complex::from_angle(pi * T::from_usize(square) / T::from_usize(n))
+        })
+        .collect();
+
+    let zero = Complex::new(T::zero(), T::zero());
+    let mut a = vec![zero; m];
+    for ((a, &x), &w) in a.iter_mut().zip(data.iter()).zip(&chirp) {
+        *a = x.mul(w);
+    }
+    let mut b = vec![zero; m];
+    b[0] = chirp[0].conj();
+    for j in 1..n {
+        b[j] = chirp[j].conj();
+        b[m - j] = chirp[j].conj();
+    }
+
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (a, &b) in a.iter_mut().zip(&b) {
+        *a = a.mul(b);
+    }
+    radix2(&mut a, true);
+
+    let factor = T::one() / T::from_usize(m);
+    for ((x, &a), &w) in data.iter_mut().zip(&a).zip(&chirp) {
+        *x = a.mul(w).scale(factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_matches_direct_transform() {
+        for n in 1..=12 {
+            let input: Vec<Complex<f64>> = (0..n)
+                .map(|j| Complex::new((j * j % 7) as f64 - 2.0, j as f64 * 0.5))
+                .collect();
+            let mut output = input.clone();
+            fft(&mut output, false);
+
+            for (k, value) in output.iter().enumerate() {
+                let expected =
+                    input
+                        .iter()
+                        .enumerate()
+                        .fold(Complex::new(0.0, 0.0), |acc, (j, &x)| {
+                            let theta = -std::f64::consts::TAU * (j * k) as f64 / n as f64;
+                            acc.add(x.mul(Complex::from_angle(theta)))
+                        });
+                assert!((value.re - expected.re).abs() < 1e-9, "n = {}", n);
+                assert!((value.im - expected.im).abs() < 1e-9, "n = {}", n);
+            }
+
+            fft(&mut output, true);
+            for (x, y) in output.iter().zip(&input) {
+                assert!((x.re - y.re).abs() < 1e-9 && (x.im - y.im).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Elementwise mathematical functions for floating-point tensors: exponentials,
+//! logarithms, powers, and trigonometric and hyperbolic functions.
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+macro_rules! unary_float_ops {
+    ($($name:ident, $name_mut:ident, $doc:expr;)*) => {
+        impl<T: Float> Tensor<T> {
+            $(
+                #[doc = concat!("Returns a new tensor with the ", $doc, " of every element.")]
+                pub fn $name(&self) -> Tensor<T> {
+                    self.map(T::$name)
+                }
+
+                #[doc = concat!("Replaces every element with its ", $doc, ".")]
+                pub fn $name_mut(&mut self) {
+                    self.map_inplace(T::$name);
+                }
+            )*
+        }
+    };
+}
+
+unary_float_ops! {
+    exp, exp_mut, "exponential";
+    ln, ln_mut, "natural logarithm";
+    log2, log2_mut, "base-2 logarithm";
+    log10, log10_mut, "base-10 logarithm";
+    sqrt, sqrt_mut, "square root";
+    recip, recip_mut, "reciprocal";
+    sin, sin_mut, "sine";
+    cos, cos_mut, "cosine";
+    tan, tan_mut, "tangent";
+    asin, asin_mut, "arcsine";
+    sinh, sinh_mut, "hyperbolic sine";
+    cosh, cosh_mut, "hyperbolic cosine";
+    tanh, tanh_mut, "hyperbolic tangent";
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns a new tensor with every element raised to the power `n`.
+    pub fn powf(&self, n: T) -> Tensor<T> {
+        self.map(|x| x.powf(n))
+    }
+
+    /// Raises every element to the power `n` in place.
+    pub fn powf_mut(&mut self, n: T) {
+        self.map_inplace(|x| x.powf(n));
+    }
+
+    /// Returns a new tensor with every element raised to the integer power `n`.
+    pub fn powi(&self, n: i32) -> Tensor<T> {
+        self.map(|x| x.powi(n))
+    }
+
+    /// Raises every element to the integer power `n` in place.
+    pub fn powi_mut(&mut self, n: i32) {
+        self.map_inplace(|x| x.powi(n));
+    }
+
+    /// Returns the four-quadrant arctangent of `self / other`, treating `self`
+    /// as the y coordinates and `other` as the x coordinates.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the shapes cannot be broadcast together.
+    pub fn atan2(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        self.zip_with(other, T::atan2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exp_and_logs() {
+        let tensor = Tensor::new(vec![1.0, 2.0, 4.0, 8.0], vec![2, 2]).unwrap();
+
+        assert_eq!(tensor.log2().as_slice(), Some(&[0.0, 1.0, 2.0, 3.0][..]));
+        assert!(tensor.ln().exp().max_abs_diff(&tensor).unwrap() < 1e-12);
+        assert_eq!(tensor.log10().shape(), &[2, 2]);
+        assert_eq!(
+            Tensor::new(vec![100.0f32], vec![1])
+                .unwrap()
+                .log10()
+                .as_slice(),
+            Some(&[2.0][..])
+        );
+    }
+
+    #[test]
+    fn test_sqrt_recip_and_powers() {
+        let tensor = Tensor::new(vec![1.0, 4.0, 16.0], vec![3]).unwrap();
+
+        assert_eq!(tensor.sqrt().as_slice(), Some(&[1.0, 2.0, 4.0][..]));
+        assert_eq!(tensor.recip().as_slice(), Some(&[1.0, 0.25, 0.0625][..]));
+        assert_eq!(tensor.powi(2).as_slice(), Some(&[1.0, 16.0, 256.0][..]));
+        assert_eq!(tensor.powf(0.5), tensor.sqrt());
+    }
+
+    #[test]
+    fn test_trigonometric_and_hyperbolic() {
+        let tensor = Tensor::new(vec![0.0, std::f64::consts::FRAC_PI_2], vec![2]).unwrap();
+
+        assert!(
+            tensor
+                .sin()
+                .max_abs_diff(&Tensor::new(vec![0.0, 1.0], vec![2]).unwrap())
+                .unwrap()
+                < 1e-12
+        );
+        assert!(
+            tensor
+                .cos()
+                .max_abs_diff(&Tensor::new(vec![1.0, 0.0], vec![2]).unwrap())
+                .unwrap()
+                < 1e-12
+        );
+        assert!(tensor.sin().asin().max_abs_diff(&tensor).unwrap() < 1e-12);
+        assert_eq!(tensor.tan().as_slice().unwrap()[0], 0.0);
+
+        let zero = Tensor::new(vec![0.0f32], vec![1]).unwrap();
+        assert_eq!(zero.sinh().as_slice(), Some(&[0.0][..]));
+        assert_eq!(zero.cosh().as_slice(), Some(&[1.0][..]));
+        assert_eq!(zero.tanh().as_slice(), Some(&[0.0][..]));
+    }
+
+    #[test]
+    fn test_atan2_broadcasts() {
+        let y = Tensor::new(vec![1.0, -1.0], vec![2, 1]).unwrap();
+        let x = Tensor::new(vec![1.0, -1.0], vec![2]).unwrap();
+
+        let result = y.atan2(&x).unwrap();
+
+        let quarter = std::f64::consts::FRAC_PI_4;
+        let expected = Tensor::new(
+            vec![quarter, 3.0 * quarter, -quarter, -3.0 * quarter],
+            vec![2, 2],
+        )
+        .unwrap();
+        assert!(result.max_abs_diff(&expected).unwrap() < 1e-12);
+        assert!(
+            y.atan2(&Tensor::new(vec![1.0; 3], vec![3]).unwrap())
+                .is_ok()
+        );
+        assert!(
+            x.atan2(&Tensor::new(vec![1.0; 3], vec![3]).unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_in_place_variants() {
+        let mut tensor = Tensor::new(vec![1.0f32, 4.0, 9.0, 16.0], vec![2, 2])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+
+        tensor.sqrt_mut();
+        tensor.powi_mut(3);
+
+        assert_eq!(
+            tensor.iter().copied().collect::<Vec<_>>(),
+            vec![1.0, 27.0, 8.0, 64.0]
+        );
+    }
+}
@@ -0,0 +1,702 @@
+//! Dense graph matrices (adjacency, degree and Laplacian) and algorithms on
+//! them: spectral embedding, PageRank and shortest paths.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::cancel;
+use crate::error::TensorError;
+use crate::linalg::eigh;
+use crate::num::Float;
+use crate::sorting::total_cmp;
+use crate::tensor::Tensor;
+
+/// Returns the number of nodes of the square `[n, n]` matrix `adjacency`.
+fn node_count<T>(adjacency: &Tensor<T>) -> Result<usize, TensorError> {
+    match adjacency.shape() {
+        [n, m] if n == m => Ok(*n),
+        shape => Err(TensorError::ShapeError(format!(
+            "adjacency matrix must be square, got shape {:?}",
+            shape
+        ))),
+    }
+}
+
+/// Builds the dense `[n, n]` adjacency matrix of a graph on nodes `0..n`.
+///
+/// Each `(from, to)` pair adds one to `[from, to]`, and for an undirected
+/// graph also to `[to, from]`, so repeated edges accumulate into weights. A
+/// self-loop is counted once.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if an edge refers to a node `>= n`.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::graph::adjacency_from_edges;
+///
+/// let adjacency = adjacency_from_edges::<f64>(&[(0, 1), (1, 2)], 3, false).unwrap();
+/// assert_eq!(
+///     adjacency.as_slice(),
+///     Some(&[0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0][..])
+/// );
+/// ```
+pub fn adjacency_from_edges<T: Float>(
+    edges: &[(usize, usize)],
+    n: usize,
+    directed: bool,
+) -> Result<Tensor<T>, TensorError> {
+    let mut data = vec![T::zero(); n * n];
+    for &(from, to) in edges {
+        if from >= n || to >= n {
+            return Err(TensorError::ValueError(format!(
+                "edge ({}, {}) refers to a node outside 0..{}",
+                from, to, n
+            )));
+        }
+        data[from * n + to] = data[from * n + to] + T::one();
+        if !directed && from != to {
+            data[to * n + from] = data[to * n + from] + T::one();
+        }
+    }
+    Tensor::new(data, vec![n, n])
+}
+
+/// Returns the out-degree of every node, the row sums of `adjacency`.
+fn degrees<T: Float>(adjacency: &Tensor<T>) -> Result<Vec<T>, TensorError> {
+    let n = node_count(adjacency)?;
+    let data: Vec<T> = adjacency.iter().copied().collect();
+    Ok(data
+        .chunks(n.max(1))
+        .take(n)
+        .map(|row| row.iter().fold(T::zero(), |acc, &w| acc + w))
+        .collect())
+}
+
+/// Returns the diagonal matrix of the (weighted) out-degrees of `adjacency`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `adjacency` is not square.
+pub fn degree_matrix<T: Float>(adjacency: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let degrees = degrees(adjacency)?;
+    let n = degrees.len();
+    let mut data = vec![T::zero(); n * n];
+    for (i, &d) in degrees.iter().enumerate() {
+        data[i * n + i] = d;
+    }
+    Tensor::new(data, vec![n, n])
+}
+
+/// Returns the graph Laplacian of `adjacency`.
+///
+/// The combinatorial Laplacian is `D - A` for the degree matrix `D`. The
+/// normalized one is `I - D^(-1/2) A D^(-1/2)`, where isolated nodes keep an
+/// all-zero row and column, matching SciPy's `csgraph.laplacian`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `adjacency` is not square.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::graph::{adjacency_from_edges, laplacian};
+///
+/// let adjacency = adjacency_from_edges::<f64>(&[(0, 1)], 2, false).unwrap();
+/// let l = laplacian(&adjacency, false).unwrap();
+/// assert_eq!(l.as_slice(), Some(&[1.0, -1.0, -1.0, 1.0][..]));
+/// ```
+pub fn laplacian<T: Float>(
+    adjacency: &Tensor<T>,
+    normalized: bool,
+) -> Result<Tensor<T>, TensorError> {
+    let degrees = degrees(adjacency)?;
+    let n = degrees.len();
+    let weights: Vec<T> = adjacency.iter().copied().collect();
+
+    let scale: Vec<T> = degrees
+        .iter()
+        .map(|&d| {
+            if d > T::zero() {
+                d.sqrt().recip()
+            } else {
+                T::zero()
+            }
+        })
+        .collect();
+
+    let mut data = Vec::with_capacity(n * n);
+    for i in 0..n {
+        for j in 0..n {
+            let w = weights[i * n + j];
+            let value = match (normalized, i == j) {
+                (false, true) => degrees[i] - w,
+                (false, false) => -w,
+                (true, true) if degrees[i] > T::zero() => T::one() - w * scale[i] * scale[i],
+                (true, true) => T::zero(),
+                (true, false) => -w * scale[i] * scale[j],
+            };
+            data.push(value);
+        }
+    }
+    Tensor::new(data, vec![n, n])
+}
+
+/// Embeds the nodes of a graph in `k` dimensions from its Laplacian.
+///
+/// Returns an `[n, k]` matrix whose columns are the eigenvectors of the
+/// symmetric `laplacian` for its second through `(k + 1)`-th smallest
+/// eigenvalues, as computed by [`laplacian`]. The first eigenvector is skipped
+/// because it carries no information about a connected graph. Each column has
+/// unit norm and is signed so its first largest-magnitude entry is positive. Only
+/// the lower triangle of `laplacian` is read.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `laplacian` is not square and
+/// `TensorError::ValueError` if `k` is zero or not less than the number of
+/// nodes.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::graph::{adjacency_from_edges, laplacian, spectral_embedding};
+///
+/// // Two triangles joined by the edge 2 - 3 split by the sign of the embedding.
+/// let edges = [(0, 1), (1, 2), (0, 2), (2, 3), (3, 4), (4, 5), (3, 5)];
+/// let adjacency = adjacency_from_edges::<f64>(&edges, 6, false).unwrap();
+/// let embedding = spectral_embedding(&laplacian(&adjacency, false).unwrap(), 1).unwrap();
+/// let side: Vec<bool> = embedding.iter().map(|&x| x > 0.0).collect();
+/// assert!(side[0] == side[1] && side[1] == side[2] && side[2] != side[3]);
+/// assert!(side[3] == side[4] && side[4] == side[5]);
+/// ```
+pub fn spectral_embedding<T: Float>(
+    laplacian: &Tensor<T>,
+    k: usize,
+) -> Result<Tensor<T>, TensorError> {
+    let n = node_count(laplacian)?;
+    if k == 0 || k >= n {
+        return Err(TensorError::ValueError(format!(
+            "spectral embedding dimension {} must be between 1 and {} for {} nodes",
+            k,
+            n.saturating_sub(1),
+            n
+        )));
+    }
+
+    let (_, vectors) = eigh(laplacian)?;
+    let vectors: Vec<T> = vectors.iter().copied().collect();
+    let mut data = vec![T::zero(); n * k];
+    for column in 0..k {
+        let values: Vec<T> = (0..n).map(|row| vectors[row * n + column + 1]).collect();
+        let largest =
+            values.iter().copied().fold(
+                T::zero(),
+                |best, x| {
+                    if x.abs() > best.abs() { x } else { best }
+                },
+            );
+        let sign = if largest < T::zero() {
+            -T::one()
+        } else {
+            T::one()
+        };
+        for (row, value) in values.into_iter().enumerate() {
+            data[row * k + column] = sign * value;
+        }
+    }
+    Tensor::new(data, vec![n, k])
+}
+
+/// Iteration cap for [`pagerank`].
+const PAGERANK_MAX_ITER: usize = 100;
+
+/// The result of [`pagerank`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageRank<T> {
+    /// The score of every node; the scores sum to one.
+    pub scores: Tensor<T>,
+    /// The number of power iterations performed.
+    pub iterations: usize,
+    /// Whether the scores met the tolerance before the iteration cap.
+    pub converged: bool,
+}
+
+/// Ranks the nodes of the directed graph `adjacency` by the power method.
+///
+/// `adjacency[[i, j]]` is the weight of the edge `i -> j`; each node passes its
+/// score to its successors in proportion to the edge weights, and nodes
+/// without outgoing edges spread theirs evenly over all nodes. With
+/// probability `1 - damping` the walk teleports to a uniformly random node.
+/// Iteration stops once the L1 change of the scores falls below `tol`, or
+/// after 100 iterations, which [`PageRank::converged`] reports.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `adjacency` is not square,
+/// `TensorError::ValueError` if it has a negative or NaN weight, `damping` is
+/// outside `[0, 1]` or `tol` is not positive, and `TensorError::Cancelled` if
+/// the current [`CancellationToken`](crate::cancel::CancellationToken) is
+/// cancelled between iterations.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::graph::{adjacency_from_edges, pagerank};
+///
+/// // A star: every leaf links to the hub 0, which links back to all of them.
+/// let edges = [(1, 0), (2, 0), (3, 0), (0, 1), (0, 2), (0, 3)];
+/// let adjacency = adjacency_from_edges::<f64>(&edges, 4, true).unwrap();
+/// let rank = pagerank(&adjacency, 0.85, 1e-4).unwrap();
+/// let scores = rank.scores.as_slice().unwrap();
+/// assert!(rank.converged);
+/// assert!(scores[0] > scores[1] && (scores[1] - scores[3]).abs() < 1e-12);
+/// ```
+pub fn pagerank<T: Float>(
+    adjacency: &Tensor<T>,
+    damping: T,
+    tol: T,
+) -> Result<PageRank<T>, TensorError> {
+    let out = degrees(adjacency)?;
+    let n = out.len();
+    let weights: Vec<T> = adjacency.iter().copied().collect();
+    if weights
+        .iter()
+        .any(|&w| matches!(w.partial_cmp(&T::zero()), None | Some(Ordering::Less)))
+    {
+        return Err(TensorError::ValueError(
+            "pagerank needs non-negative edge weights".to_string(),
+        ));
+    }
+    if matches!(damping.partial_cmp(&T::zero()), None | Some(Ordering::Less)) || damping > T::one()
+    {
+        return Err(TensorError::ValueError(
+            "pagerank damping must be in [0, 1]".to_string(),
+        ));
+    }
+    if tol.partial_cmp(&T::zero()) != Some(Ordering::Greater) {
+        return Err(TensorError::ValueError(
+            "pagerank tolerance must be positive".to_string(),
+        ));
+    }
+
+    let uniform = T::from_usize(n).recip();
+    let mut scores = vec![uniform; n];
+    let mut next = vec![T::zero(); n];
+    let mut iterations = 0;
+    let mut converged = n == 0;
+    while !converged && iterations < PAGERANK_MAX_ITER {
+        cancel::check("pagerank")?;
+        iterations += 1;
+
+        let dangling = (0..n)
+            .filter(|&i| out[i] == T::zero())
+            .fold(T::zero(), |acc, i| acc + scores[i]);
+        let base = (T::one() - damping + damping * dangling) * uniform;
+        next.iter_mut().for_each(|v| *v = base);
+        for i in (0..n).filter(|&i| out[i] > T::zero()) {
+            let share = damping * scores[i] / out[i];
+            for (j, &w) in weights[i * n..(i + 1) * n].iter().enumerate() {
+                next[j] = next[j] + share * w;
+            }
+        }
+
+        let change = scores
+            .iter()
+            .zip(&next)
+            .fold(T::zero(), |acc, (&a, &b)| acc + (a - b).abs());
+        std::mem::swap(&mut scores, &mut next);
+        converged = change < tol;
+    }
+
+    Ok(PageRank {
+        scores: Tensor::new(scores, vec![n])?,
+        iterations,
+        converged,
+    })
+}
+
+/// Side length of the tiles processed by [`floyd_warshall`].
+const FLOYD_WARSHALL_BLOCK: usize = 64;
+
+/// Returns the edge lengths of the square matrix `weights` as a row-major
+/// buffer, with `T::infinity` for missing edges and zero on the diagonal.
+///
+/// Off-diagonal entries that are zero or infinite mean "no edge", following
+/// SciPy's dense `csgraph` convention, so the output of
+/// [`adjacency_from_edges`] can be used directly.
+fn edge_lengths<T: Float>(weights: &Tensor<T>) -> Result<(Vec<T>, usize), TensorError> {
+    let n = node_count(weights)?;
+    let infinity = T::from_f64(f64::INFINITY);
+    let mut lengths: Vec<T> = weights.iter().copied().collect();
+    for (index, w) in lengths.iter_mut().enumerate() {
+        if w.is_nan() {
+            return Err(TensorError::ValueError(
+                "edge weights must not be NaN".to_string(),
+            ));
+        }
+        if index / n == index % n {
+            *w = T::zero();
+        } else if *w == T::zero() {
+            *w = infinity;
+        }
+    }
+    Ok((lengths, n))
+}
+
+/// Relaxes the tile `(ib, jb)` of `dist` through the intermediate nodes of
+/// tile `kb`, with tiles of side `block`.
+fn relax_tile<T: Float>(dist: &mut [T], n: usize, block: usize, kb: usize, ib: usize, jb: usize) {
+    let tile = |b: usize| b * block..((b + 1) * block).min(n);
+    for k in tile(kb) {
+        for i in tile(ib) {
+            let through = dist[i * n + k];
+            for j in tile(jb) {
+                let candidate = through + dist[k * n + j];
+                if candidate < dist[i * n + j] {
+                    dist[i * n + j] = candidate;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the `[n, n]` matrix of shortest path lengths between all pairs of
+/// nodes of the weighted directed graph `weights`, where `weights[[i, j]]` is
+/// the length of the edge `i -> j`.
+///
+/// Zero or infinite off-diagonal entries mean there is no edge, and
+/// unreachable pairs get an infinite distance. Negative edges are allowed.
+/// The matrix is processed in tiles so that each pass over a tile stays in
+/// cache, which gives the same result as the textbook triple loop.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `weights` is not square and
+/// `TensorError::ValueError` if it has a NaN entry or the graph has a cycle
+/// of negative length.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::graph::floyd_warshall;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let weights = Tensor::new(vec![0.0, 4.0, 1.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0], vec![3, 3]).unwrap();
+/// let dist = floyd_warshall(&weights).unwrap();
+/// assert_eq!(dist.get(&[0, 1]), Some(&3.0));
+/// assert_eq!(dist.get(&[1, 0]), Some(&f64::INFINITY));
+/// ```
+pub fn floyd_warshall<T: Float>(weights: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let (mut dist, n) = edge_lengths(weights)?;
+    let block = FLOYD_WARSHALL_BLOCK;
+    let tiles = n.div_ceil(block);
+
+    // Blocked Floyd-Warshall: for each diagonal tile, first close it, then
+    // the tiles sharing its row or column, then all remaining tiles, which
+    // only depend on those two.
+    for kb in 0..tiles {
+        relax_tile(&mut dist, n, block, kb, kb, kb);
+        for b in (0..tiles).filter(|&b| b != kb) {
+            relax_tile(&mut dist, n, block, kb, kb, b);
+            relax_tile(&mut dist, n, block, kb, b, kb);
+        }
+        for ib in (0..tiles).filter(|&b| b != kb) {
+            for jb in (0..tiles).filter(|&b| b != kb) {
+                relax_tile(&mut dist, n, block, kb, ib, jb);
+            }
+        }
+    }
+
+    if (0..n).any(|i| dist[i * n + i] < T::zero()) {
+        return Err(TensorError::ValueError(
+            "graph has a cycle of negative length".to_string(),
+        ));
+    }
+    Tensor::new(dist, vec![n, n])
+}
+
+/// A node waiting in the [`dijkstra`] queue, ordered so that the closest
+/// node is popped first from a max-heap.
+struct Visit<T> {
+    distance: T,
+    node: usize,
+}
+
+impl<T: PartialOrd> Ord for Visit<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        total_cmp(&other.distance, &self.distance)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Visit<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> PartialEq for Visit<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd> Eq for Visit<T> {}
+
+/// Returns the shortest path length from `source` to every node of the
+/// weighted directed graph `weights`, using the same edge convention as
+/// [`floyd_warshall`].
+///
+/// Unreachable nodes get an infinite distance.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `weights` is not square and
+/// `TensorError::ValueError` if `source` is not a node or a weight is
+/// negative or NaN.
+pub fn dijkstra<T: Float>(weights: &Tensor<T>, source: usize) -> Result<Tensor<T>, TensorError> {
+    let (lengths, n) = edge_lengths(weights)?;
+    if source >= n {
+        return Err(TensorError::ValueError(format!(
+            "source node {} is outside 0..{}",
+            source, n
+        )));
+    }
+    if lengths.iter().any(|&w| w < T::zero()) {
+        return Err(TensorError::ValueError(
+            "dijkstra needs non-negative edge weights".to_string(),
+        ));
+    }
+
+    let infinity = T::from_f64(f64::INFINITY);
+    let mut dist = vec![infinity; n];
+    let mut queue = BinaryHeap::new();
+    dist[source] = T::zero();
+    queue.push(Visit {
+        distance: T::zero(),
+        node: source,
+    });
+    while let Some(Visit { distance, node }) = queue.pop() {
+        if distance > dist[node] {
+            continue;
+        }
+        for (next, &length) in lengths[node * n..(node + 1) * n].iter().enumerate() {
+            let candidate = distance + length;
+            if length < infinity && candidate < dist[next] {
+                dist[next] = candidate;
+                queue.push(Visit {
+                    distance: candidate,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    Tensor::new(dist, vec![n])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacency_and_degrees() {
+        let edges = [(0, 1), (0, 2), (0, 1), (2, 2)];
+
+        let undirected = adjacency_from_edges::<f64>(&edges, 3, false).unwrap();
+        assert_eq!(
+            undirected.as_slice(),
+            Some(&[0.0, 2.0, 1.0, 2.0, 0.0, 0.0, 1.0, 0.0, 1.0][..])
+        );
+        assert_eq!(
+            degree_matrix(&undirected).unwrap().as_slice(),
+            Some(&[3.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0][..])
+        );
+
+        let directed = adjacency_from_edges::<f32>(&edges, 3, true).unwrap();
+        assert_eq!(
+            directed.as_slice(),
+            Some(&[0.0, 2.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0][..])
+        );
+        assert!(matches!(
+            adjacency_from_edges::<f64>(&[(0, 3)], 3, false),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(degree_matrix(&Tensor::new(vec![0.0; 6], vec![2, 3]).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_normalized_laplacian() {
+        // A path 0 - 1 - 2 plus the isolated node 3.
+        let adjacency = adjacency_from_edges::<f64>(&[(0, 1), (1, 2)], 4, false).unwrap();
+
+        let l = laplacian(&adjacency, true).unwrap();
+
+        let c = -1.0 / 2f64.sqrt();
+        let expected = Tensor::new(
+            vec![
+                1.0, c, 0.0, 0.0, //
+                c, 1.0, c, 0.0, //
+                0.0, c, 1.0, 0.0, //
+                0.0, 0.0, 0.0, 0.0,
+            ],
+            vec![4, 4],
+        )
+        .unwrap();
+        assert!(l.max_abs_diff(&expected).unwrap() < 1e-12);
+
+        let combinatorial = laplacian(&adjacency, false).unwrap();
+        assert_eq!(combinatorial.get(&[1, 1]), Some(&2.0));
+        assert_eq!(combinatorial.get(&[1, 2]), Some(&-1.0));
+    }
+
+    #[test]
+    fn test_spectral_embedding_of_a_path() {
+        // The Fiedler vector of a path is cos(pi * (i + 1/2) / n), up to scale.
+        let n = 5;
+        let edges: Vec<(usize, usize)> = (0..n - 1).map(|i| (i, i + 1)).collect();
+        let adjacency = adjacency_from_edges::<f64>(&edges, n, false).unwrap();
+        let l = laplacian(&adjacency, false).unwrap();
+
+        let embedding = spectral_embedding(&l, 2).unwrap();
+
+        assert_eq!(embedding.shape(), &[5, 2]);
+        let fiedler: Vec<f64> = (0..n)
+            .map(|i| (std::f64::consts::PI * (i as f64 + 0.5) / n as f64).cos())
+            .collect();
+        let norm = fiedler.iter().map(|x| x * x).sum::<f64>().sqrt();
+        // The ends tie for the largest magnitude, so the sign is not fixed.
+        let sign = embedding.get(&[0, 0]).unwrap().signum();
+        for (i, &x) in fiedler.iter().enumerate() {
+            assert!((embedding.get(&[i, 0]).unwrap() - sign * x / norm).abs() < 1e-10);
+        }
+        // Columns are orthonormal.
+        let dot: f64 = (0..n)
+            .map(|i| embedding.get(&[i, 0]).unwrap() * embedding.get(&[i, 1]).unwrap())
+            .sum();
+        assert!(dot.abs() < 1e-10);
+
+        assert!(matches!(
+            spectral_embedding(&l, 0),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            spectral_embedding(&l, 5),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            spectral_embedding(&Tensor::new(vec![0.0; 6], vec![2, 3]).unwrap(), 1),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_pagerank_handles_dangling_nodes() {
+        // 0 -> 1 -> 2, and 2 has no outgoing edges.
+        let adjacency = adjacency_from_edges::<f64>(&[(0, 1), (1, 2)], 3, true).unwrap();
+
+        let rank = pagerank(&adjacency, 0.85, 1e-12).unwrap();
+        let scores = rank.scores.as_slice().unwrap();
+
+        assert!(rank.converged);
+        assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(scores[0] < scores[1] && scores[1] < scores[2]);
+
+        // Without damping, the walk only follows edges and teleports from node 2.
+        let rank = pagerank(&adjacency, 0.0, 1e-12).unwrap();
+        assert_eq!(rank.scores.as_slice(), Some(&[1.0 / 3.0; 3][..]));
+        assert_eq!(rank.iterations, 1);
+    }
+
+    #[test]
+    fn test_pagerank_reports_non_convergence_and_errors() {
+        // Without teleportation, the two-cycle is stationary from the uniform
+        // start, but feeding it from node 2 makes the scores oscillate.
+        let cycle = adjacency_from_edges::<f64>(&[(0, 1), (1, 0)], 2, true).unwrap();
+        assert!(pagerank(&cycle, 1.0, 1e-12).unwrap().converged);
+
+        let fed = adjacency_from_edges::<f64>(&[(0, 1), (1, 0), (2, 0)], 3, true).unwrap();
+        let rank = pagerank(&fed, 1.0, 1e-12).unwrap();
+        assert!(!rank.converged);
+        assert_eq!(rank.iterations, 100);
+
+        assert!(pagerank(&cycle, 1.5, 1e-6).is_err());
+        assert!(pagerank(&cycle, 0.5, 0.0).is_err());
+        assert!(pagerank(&cycle.map(|w| -w), 0.5, 1e-6).is_err());
+
+        let token = crate::cancel::CancellationToken::new();
+        token.cancel();
+        assert!(matches!(
+            token.scoped(|| pagerank(&fed, 0.85, 1e-6)),
+            Err(TensorError::Cancelled(_))
+        ));
+    }
+
+    /// Returns the all-pairs distances of the textbook triple loop.
+    fn naive_floyd_warshall(weights: &Tensor<f64>) -> Vec<f64> {
+        let (mut dist, n) = edge_lengths(weights).unwrap();
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    dist[i * n + j] = dist[i * n + j].min(dist[i * n + k] + dist[k * n + j]);
+                }
+            }
+        }
+        dist
+    }
+
+    #[test]
+    fn test_floyd_warshall_matches_naive_across_tiles() {
+        // A graph larger than one tile. Shifting positive odd lengths by the
+        // node potentials `p(i) - p(j)` makes some edges negative while every
+        // cycle keeps its positive length, and keeps the lengths non-zero.
+        let n = 150;
+        let potential = |i: usize| ((i % 7) * 2) as f64;
+        let weights = Tensor::from_fn(vec![n, n], |index| {
+            let (i, j) = (index[0], index[1]);
+            if (1..=3).contains(&((i * 31 + j * 17) % 11)) {
+                (((i + 2 * j) % 9) * 2 + 1) as f64 + potential(i) - potential(j)
+            } else {
+                0.0
+            }
+        });
+
+        let dist = floyd_warshall(&weights).unwrap();
+
+        assert_eq!(
+            dist.as_slice().unwrap(),
+            &naive_floyd_warshall(&weights)[..]
+        );
+        let first = dijkstra(&weights.map(|w| w.abs()), 0).unwrap();
+        let all = floyd_warshall(&weights.map(|w| w.abs())).unwrap();
+        assert_eq!(first.as_slice().unwrap(), &all.as_slice().unwrap()[..n]);
+    }
+
+    #[test]
+    fn test_shortest_path_edge_cases() {
+        let weights = Tensor::new(
+            vec![0.0, 2.0, 0.0, 0.0, 0.0, -3.0, 0.0, 0.0, 0.0],
+            vec![3, 3],
+        )
+        .unwrap();
+        let dist = floyd_warshall(&weights).unwrap();
+        assert_eq!(dist.as_slice().unwrap()[..3], [0.0, 2.0, -1.0]);
+        assert!(matches!(
+            dijkstra(&weights, 0),
+            Err(TensorError::ValueError(_))
+        ));
+
+        let cycle = Tensor::new(vec![0.0, 1.0, -2.0, 0.0], vec![2, 2]).unwrap();
+        assert!(floyd_warshall(&cycle).is_err());
+
+        let path = adjacency_from_edges::<f64>(&[(0, 1), (1, 2)], 4, true).unwrap();
+        assert_eq!(
+            dijkstra(&path, 0).unwrap().as_slice(),
+            Some(&[0.0, 1.0, 2.0, f64::INFINITY][..])
+        );
+        assert!(dijkstra(&path, 4).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Histograms over evenly spaced bins.
+
+use std::cmp::Ordering;
+
+use crate::creation::linspace;
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Returns the `[lo, hi]` range of a dimension: `range` if given, otherwise the
+/// extent of the non-NaN `values`, widened by 0.5 on each side when it is a
+/// single point. Either way, both bounds must be finite.
+fn resolve_range<T: Float>(
+    values: impl Iterator<Item = T>,
+    range: Option<(T, T)>,
+) -> Result<(T, T), TensorError> {
+    let (lo, hi) = match range {
+        Some((lo, hi)) => {
+            if lo.partial_cmp(&hi) != Some(Ordering::Less) {
+                return Err(TensorError::ValueError(
+                    "histogram range must satisfy lo < hi".to_string(),
+                ));
+            }
+            (lo, hi)
+        }
+        None => {
+            let mut extent: Option<(T, T)> = None;
+            for x in values.filter(|x| !x.is_nan()) {
+                extent = Some(match extent {
+                    None => (x, x),
+                    Some((lo, hi)) => (if x < lo { x } else { lo }, if x > hi { x } else { hi }),
+                });
+            }
+            let (lo, hi) = extent.unwrap_or((T::zero(), T::one()));
+            if lo == hi {
+                let half = T::from_f64(0.5);
+                (lo - half, hi + half)
+            } else {
+                (lo, hi)
+            }
+        }
+    };
+    if !lo.is_finite() || !hi.is_finite() {
+        return Err(TensorError::ValueError(
+            "histogram range must be finite; pass an explicit range to skip infinite values"
+                .to_string(),
+        ));
+    }
+    Ok((lo, hi))
+}
+
+/// Returns the bin of `x` among `edges`, with the last bin closed on the right,
+/// or `None` if `x` lies outside the edges or is NaN.
+fn bin_of<T: Float>(edges: &[T], x: T) -> Option<usize> {
+    let bins = edges.len() - 1;
+    if x.is_nan() || x < edges[0] || x > edges[bins] {
+        return None;
+    }
+    Some(
+        edges
+            .partition_point(|&edge| edge <= x)
+            .saturating_sub(1)
+            .min(bins - 1),
+    )
+}
+
+/// Counts the elements of `x`, of any shape, into `bins` evenly spaced bins.
+///
+/// `range` gives the `(lo, hi)` extent of the bins and defaults to the minimum
+/// and maximum of `x`. As in [`histogramdd`], the last bin includes its right
+/// edge and NaN or out-of-range elements are not counted.
+///
+/// Returns the `bins` counts and the `bins + 1` edges.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `bins` is zero, `range` does not
+/// satisfy `lo < hi`, or the range, given or inferred, is not finite.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::histogram::histogram;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let x = Tensor::new(vec![1.0, 2.0, 2.0, 3.0, 5.0], vec![5]).unwrap();
+/// let (counts, edges) = histogram(&x, 4, None).unwrap();
+/// assert_eq!(counts.as_slice(), Some(&[1, 2, 1, 1][..]));
+/// assert_eq!(edges.as_slice(), Some(&[1.0, 2.0, 3.0, 4.0, 5.0][..]));
+/// ```
+pub fn histogram<T: Float>(
+    x: &Tensor<T>,
+    bins: usize,
+    range: Option<(T, T)>,
+) -> Result<(Tensor<usize>, Tensor<T>), TensorError> {
+    if bins == 0 {
+        return Err(TensorError::ValueError(
+            "histogram needs at least one bin".to_string(),
+        ));
+    }
+
+    let (lo, hi) = resolve_range(x.iter().copied(), range)?;
+    let edges = linspace(lo, hi, bins + 1);
+    let edge_values = edges.as_slice().unwrap();
+    let mut counts = vec![0; bins];
+    for &value in x.iter() {
+        if let Some(bin) = bin_of(edge_values, value) {
+            counts[bin] += 1;
+        }
+    }
+
+    Ok((Tensor::new(counts, vec![bins])?, edges))
+}
+
+/// Counts `[n, d]` samples into a `d`-dimensional grid of evenly spaced bins.
+///
+/// `bins[k]` is the number of bins along dimension `k`, and `ranges`, if given,
+/// holds the `(lo, hi)` extent of each dimension; otherwise each dimension
+/// spans the minimum to the maximum of its samples. Bins are half-open except
+/// the last along each dimension, which includes its right edge. Samples
+/// outside the ranges, or with a NaN coordinate, are not counted.
+///
+/// Returns the count tensor, of shape `bins`, and the `bins[k] + 1` edges of
+/// every dimension.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `samples` is not 2D or `bins` or
+/// `ranges` does not have one entry per column, and `TensorError::ValueError`
+/// if a bin count is zero, a range does not satisfy `lo < hi`, or a range,
+/// given or inferred, is not finite.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::histogram::histogramdd;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let samples = Tensor::new(vec![0.1, 0.1, 0.9, 0.2, 0.8, 0.7], vec![3, 2]).unwrap();
+/// let (counts, edges) = histogramdd(&samples, &[2, 2], Some(&[(0.0, 1.0), (0.0, 1.0)])).unwrap();
+/// assert_eq!(counts.as_slice(), Some(&[1, 0, 1, 1][..]));
+/// assert_eq!(edges[0].as_slice(), Some(&[0.0, 0.5, 1.0][..]));
+/// ```
+pub fn histogramdd<T: Float>(
+    samples: &Tensor<T>,
+    bins: &[usize],
+    ranges: Option<&[(T, T)]>,
+) -> Result<(Tensor<usize>, Vec<Tensor<T>>), TensorError> {
+    let (n, d) = match samples.shape() {
+        [n, d] => (*n, *d),
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "histogramdd samples must have shape [n, d], got {:?}",
+                shape
+            )));
+        }
+    };
+    if bins.len() != d || ranges.is_some_and(|r| r.len() != d) {
+        return Err(TensorError::ShapeError(format!(
+            "histogramdd needs one bin count and range per dimension, got {} dimensions",
+            d
+        )));
+    }
+    if bins.contains(&0) {
+        return Err(TensorError::ValueError(
+            "every dimension needs at least one bin".to_string(),
+        ));
+    }
+
+    let data: Vec<T> = samples.iter().copied().collect();
+    let edges = (0..d)
+        .map(|k| {
+            let column = (0..n).map(|i| data[i * d + k]);
+            let (lo, hi) = resolve_range(column, ranges.map(|r| r[k]))?;
+            Ok(linspace(lo, hi, bins[k] + 1))
+        })
+        .collect::<Result<Vec<_>, TensorError>>()?;
+
+    let mut counts = Tensor::new(vec![0; bins.iter().product()], bins.to_vec())?;
+    let edge_values: Vec<&[T]> = edges.iter().map(|e| e.as_slice().unwrap()).collect();
+    'samples: for row in data.chunks(d.max(1)).take(n) {
+        let mut offset = 0;
+        for (k, &x) in row.iter().enumerate() {
+            match bin_of(edge_values[k], x) {
+                Some(bin) => offset += bin * counts.strides[k],
+                None => continue 'samples,
+            }
+        }
+        counts.data[offset] += 1;
+    }
+
+    Ok((counts, edges))
+}
+
+/// Counts paired samples `(x[i], y[i])` into a 2D grid of `bins.0 x bins.1`
+/// evenly spaced bins; a convenience wrapper around [`histogramdd`].
+///
+/// Returns the `[bins.0, bins.1]` counts and the edges along `x` and then `y`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `x` and `y` are not 1D tensors of equal
+/// length, and otherwise the errors of [`histogramdd`].
+pub fn histogram2d<T: Float>(
+    x: &Tensor<T>,
+    y: &Tensor<T>,
+    bins: (usize, usize),
+    ranges: Option<((T, T), (T, T))>,
+) -> Result<(Tensor<usize>, Vec<Tensor<T>>), TensorError> {
+    if x.ndim() != 1 || x.shape() != y.shape() {
+        return Err(TensorError::ShapeError(format!(
+            "histogram2d needs 1D inputs of equal length, got {:?} and {:?}",
+            x.shape(),
+            y.shape()
+        )));
+    }
+
+    let data = x.iter().zip(y.iter()).flat_map(|(&a, &b)| [a, b]).collect();
+    let samples = Tensor::new(data, vec![x.len(), 2])?;
+    let ranges = ranges.map(|(rx, ry)| [rx, ry]);
+    histogramdd(&samples, &[bins.0, bins.1], ranges.as_ref().map(|r| &r[..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogramdd_infers_ranges_and_closes_last_bin() {
+        let samples =
+            Tensor::new(vec![0.0, 10.0, 1.0, 10.0, 2.0, 20.0, 4.0, 30.0], vec![4, 2]).unwrap();
+
+        let (counts, edges) = histogramdd(&samples, &[2, 1], None).unwrap();
+
+        assert_eq!(counts.shape(), &[2, 1]);
+        // The maximum, 4.0, lands in the last bin rather than falling off the end.
+        assert_eq!(counts.as_slice(), Some(&[2, 2][..]));
+        assert_eq!(edges[0].as_slice(), Some(&[0.0, 2.0, 4.0][..]));
+        assert_eq!(edges[1].as_slice(), Some(&[10.0, 30.0][..]));
+    }
+
+    #[test]
+    fn test_histogramdd_skips_outside_and_nan_samples() {
+        let samples = Tensor::new(vec![0.5, -1.0, f64::NAN, 0.25], vec![4, 1]).unwrap();
+
+        let (counts, _) = histogramdd(&samples, &[2], Some(&[(0.0, 1.0)])).unwrap();
+        assert_eq!(counts.as_slice(), Some(&[1, 1][..]));
+
+        let constant = Tensor::new(vec![3.0, 3.0], vec![2, 1]).unwrap();
+        let (counts, edges) = histogramdd(&constant, &[1], None).unwrap();
+        assert_eq!(counts.as_slice(), Some(&[2][..]));
+        assert_eq!(edges[0].as_slice(), Some(&[2.5, 3.5][..]));
+    }
+
+    #[test]
+    fn test_histogramdd_validates_arguments() {
+        let samples = Tensor::new(vec![0.0; 4], vec![2, 2]).unwrap();
+
+        assert!(matches!(
+            histogramdd(&samples, &[2], None),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            histogramdd(&samples, &[2, 0], None),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(histogramdd(&samples, &[1, 1], Some(&[(0.0, 1.0), (1.0, 1.0)])).is_err());
+    }
+
+    #[test]
+    fn test_histogram2d() {
+        let x = Tensor::new(vec![0.1, 0.6, 0.7, 0.9], vec![4]).unwrap();
+        let y = Tensor::new(vec![0.1, 0.1, 0.9, 0.8], vec![4]).unwrap();
+
+        let (counts, edges) = histogram2d(&x, &y, (2, 2), Some(((0.0, 1.0), (0.0, 1.0)))).unwrap();
+
+        assert_eq!(counts.as_slice(), Some(&[1, 0, 1, 2][..]));
+        assert_eq!(edges[0].as_slice(), Some(&[0.0, 0.5, 1.0][..]));
+        assert_eq!(edges[1].len(), 3);
+        assert!(histogram2d(&x, &Tensor::new(vec![0.0], vec![1]).unwrap(), (2, 2), None).is_err());
+    }
+
+    #[test]
+    fn test_histogram_flattens_input() {
+        let x = Tensor::new(
+            vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0, f64::NAN, 2.0],
+            vec![2, 4],
+        )
+        .unwrap();
+
+        let (counts, edges) = histogram(&x, 2, Some((0.0, 1.0))).unwrap();
+        assert_eq!(counts.as_slice(), Some(&[3, 3][..]));
+        assert_eq!(edges.as_slice(), Some(&[0.0, 0.5, 1.0][..]));
+
+        let (counts, _) = histogram(&x, 1, None).unwrap();
+        assert_eq!(counts.as_slice(), Some(&[7][..]));
+        assert!(histogram(&x, 0, None).is_err());
+        assert!(histogram(&x, 2, Some((1.0, 0.0))).is_err());
+    }
+
+    #[test]
+    fn test_histogram_rejects_infinite_ranges() {
+        let x = Tensor::new(vec![0.0, 1.0, f64::INFINITY], vec![3]).unwrap();
+
+        assert!(matches!(
+            histogram(&x, 2, None),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            histogram(&x, 2, Some((0.0, f64::INFINITY))),
+            Err(TensorError::ValueError(_))
+        ));
+        let (counts, _) = histogram(&x, 2, Some((0.0, 1.0))).unwrap();
+        assert_eq!(counts.as_slice(), Some(&[1, 1][..]));
+
+        let samples = Tensor::new(vec![0.0, f64::NEG_INFINITY, 1.0, 2.0], vec![2, 2]).unwrap();
+        assert!(matches!(
+            histogramdd(&samples, &[2, 2], None),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+}
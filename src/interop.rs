@@ -0,0 +1,95 @@
+//! Conversions to and from the `ndarray` crate, available with the `ndarray` feature.
+
+use ndarray::{ArrayD, ArrayViewD, IxDyn, ShapeBuilder};
+
+use crate::tensor::Tensor;
+
+impl<T: Copy> From<ArrayD<T>> for Tensor<T> {
+    /// Converts an owned `ndarray` array into a tensor.
+    ///
+    /// Arrays in standard (row-major) layout hand over their buffer without
+    /// copying; any other layout is copied into row-major order.
+    fn from(array: ArrayD<T>) -> Self {
+        let shape = array.shape().to_vec();
+        let len = array.len();
+        let data = if array.is_standard_layout() {
+            let (mut data, offset) = array.into_raw_vec_and_offset();
+            let start = offset.unwrap_or(0);
+            if start != 0 || data.len() != len {
+                data = data[start..start + len].to_vec();
+            }
+            data
+        } else {
+            array.iter().copied().collect()
+        };
+
+        Tensor::new(data, shape).unwrap()
+    }
+}
+
+impl<T: Copy> Tensor<T> {
+    /// Converts the tensor into an owned `ndarray` array without copying.
+    ///
+    /// The tensor's strides are carried over, so the array describes exactly
+    /// the same elements in the same memory layout.
+    pub fn into_ndarray(self) -> ArrayD<T> {
+        let shape = IxDyn(&self.shape).strides(IxDyn(&self.strides));
+        ArrayD::from_shape_vec(shape, self.data).unwrap()
+    }
+
+    /// Returns a zero-copy `ndarray` view of the tensor.
+    pub fn view_ndarray(&self) -> ArrayViewD<'_, T> {
+        let shape = IxDyn(&self.shape).strides(IxDyn(&self.strides));
+        ArrayViewD::from_shape(shape, &self.data).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_standard_layout_array() {
+        let array = ArrayD::from_shape_vec(IxDyn(&[2, 3]), vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let ptr = array.as_ptr();
+
+        let tensor = Tensor::from(array);
+
+        assert_eq!(tensor.shape(), &[2, 3]);
+        assert_eq!(tensor.as_slice().unwrap().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_from_transposed_array() {
+        let array = ArrayD::from_shape_vec(IxDyn(&[2, 3]), vec![1, 2, 3, 4, 5, 6]).unwrap();
+
+        let tensor = Tensor::from(array.reversed_axes());
+
+        assert_eq!(tensor.shape(), &[3, 2]);
+        assert_eq!(tensor.as_slice(), Some(&[1, 4, 2, 5, 3, 6][..]));
+    }
+
+    #[test]
+    fn test_from_sliced_array() {
+        let array = ArrayD::from_shape_vec(IxDyn(&[3, 2]), vec![1, 2, 3, 4, 5, 6]).unwrap();
+        let middle = array.slice_move(ndarray::s![1..2, ..]).into_dyn();
+
+        let tensor = Tensor::from(middle);
+
+        assert_eq!(tensor.shape(), &[1, 2]);
+        assert_eq!(tensor.as_slice(), Some(&[3, 4][..]));
+    }
+
+    #[test]
+    fn test_into_and_view_ndarray() {
+        let tensor = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2]).unwrap();
+
+        let view = tensor.view_ndarray();
+        assert_eq!(view[[1, 0]], 3.0);
+        assert_eq!(view.as_ptr(), tensor.as_slice().unwrap().as_ptr());
+
+        let array = tensor.clone().into_ndarray();
+        assert_eq!(array.shape(), &[2, 2]);
+        assert_eq!(Tensor::from(array), tensor);
+    }
+}
@@ -0,0 +1,297 @@
+//! Interpolation of sampled data.
+
+use std::cmp::Ordering;
+
+use crate::creation::vector_elements;
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// The condition imposed at both ends of a [`CubicSpline`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary<T> {
+    /// The second derivative is zero at both ends.
+    Natural,
+    /// The first derivative equals `start` at the first knot and `end` at the
+    /// last, for every column of `y`.
+    Clamped { start: T, end: T },
+}
+
+/// A piecewise cubic interpolant with continuous first and second derivatives.
+///
+/// `y` may be 1D, with one value per knot, or 2D with shape `[knots, columns]`,
+/// in which case every column is interpolated independently against the same
+/// knots.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubicSpline<T> {
+    x: Vec<T>,
+    /// Knot values, row-major `[knots, columns]`.
+    y: Vec<T>,
+    /// Second derivatives at the knots, laid out like `y`.
+    second: Vec<T>,
+    columns: usize,
+    /// Whether `y` was 1D, so evaluation returns 1D results.
+    vector: bool,
+}
+
+impl<T: Float> CubicSpline<T> {
+    /// Fits a spline with natural boundary conditions through the points `(x, y)`.
+    ///
+    /// # Errors
+    ///
+    /// See [`CubicSpline::fit_with`].
+    pub fn fit(x: &Tensor<T>, y: &Tensor<T>) -> Result<Self, TensorError> {
+        Self::fit_with(x, y, Boundary::Natural)
+    }
+
+    /// Fits a spline through the points `(x, y)` with the given boundary condition.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `x` is not 1D, `y` is not 1D or 2D,
+    /// or the first axis of `y` does not match the length of `x`. Returns
+    /// `TensorError::ValueError` if there are fewer than two knots or `x` is not
+    /// strictly increasing.
+    pub fn fit_with(
+        x: &Tensor<T>,
+        y: &Tensor<T>,
+        boundary: Boundary<T>,
+    ) -> Result<Self, TensorError> {
+        let x = vector_elements(x, "spline knots")?;
+        let (vector, columns) = match y.shape() {
+            [n] if *n == x.len() => (true, 1),
+            [n, columns] if *n == x.len() => (false, *columns),
+            shape => {
+                return Err(TensorError::ShapeError(format!(
+                    "spline values of shape {:?} do not match {} knots",
+                    shape,
+                    x.len()
+                )));
+            }
+        };
+        if x.len() < 2 {
+            return Err(TensorError::ValueError(
+                "a cubic spline needs at least two knots".to_string(),
+            ));
+        }
+        if x.windows(2)
+            .any(|pair| pair[0].partial_cmp(&pair[1]) != Some(Ordering::Less))
+        {
+            return Err(TensorError::ValueError(
+                "spline knots must be strictly increasing".to_string(),
+            ));
+        }
+
+        let y: Vec<T> = y.iter().copied().collect();
+        let second = second_derivatives(&x, &y, columns, boundary);
+        Ok(Self {
+            x,
+            y,
+            second,
+            columns,
+            vector,
+        })
+    }
+
+    /// Evaluates the spline at every point of the 1D tensor `new_x`.
+    ///
+    /// The result has shape `[new_x.len()]` for a 1D fit and
+    /// `[new_x.len(), columns]` otherwise. Points outside the knots are
+    /// extrapolated with the cubic of the nearest end interval.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `new_x` is not 1-dimensional.
+    pub fn evaluate(&self, new_x: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let points = vector_elements(new_x, "spline evaluation points")?;
+        let six = T::from_usize(6);
+        let last = self.x.len() - 2;
+
+        let mut data = Vec::with_capacity(points.len() * self.columns);
+        for &point in &points {
+            let i = self
+                .x
+                .partition_point(|&knot| knot <= point)
+                .saturating_sub(1)
+                .min(last);
+            let h = self.x[i + 1] - self.x[i];
+            let left = self.x[i + 1] - point;
+            let right = point - self.x[i];
+
+            for column in 0..self.columns {
+                let (y0, y1) = (
+                    self.y[i * self.columns + column],
+                    self.y[(i + 1) * self.columns + column],
+                );
+                let (m0, m1) = (
+                    self.second[i * self.columns + column],
+                    self.second[(i + 1) * self.columns + column],
+                );
+                data.push(
+                    (m0 * left * left * left + m1 * right * right * right) / (six * h)
+                        + (y0 / h - m0 * h / six) * left
+                        + (y1 / h - m1 * h / six) * right,
+                );
+            }
+        }
+
+        let shape = if self.vector {
+            vec![points.len()]
+        } else {
+            vec![points.len(), self.columns]
+        };
+        Tensor::new(data, shape)
+    }
+}
+
+/// Solves the tridiagonal system for the second derivatives at the knots, for
+/// every column of `y` at once.
+fn second_derivatives<T: Float>(x: &[T], y: &[T], columns: usize, boundary: Boundary<T>) -> Vec<T> {
+    let n = x.len();
+    let six = T::from_usize(6);
+    let two = T::from_usize(2);
+    let h: Vec<T> = x.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let slope =
+        |i: usize, column: usize| (y[(i + 1) * columns + column] - y[i * columns + column]) / h[i];
+
+    let mut lower = vec![T::zero(); n];
+    let mut diag = vec![T::one(); n];
+    let mut upper = vec![T::zero(); n];
+    let mut rhs = vec![T::zero(); n * columns];
+
+    for i in 1..n - 1 {
+        lower[i] = h[i - 1];
+        diag[i] = two * (h[i - 1] + h[i]);
+        upper[i] = h[i];
+        for column in 0..columns {
+            rhs[i * columns + column] = six * (slope(i, column) - slope(i - 1, column));
+        }
+    }
+
+    if let Boundary::Clamped { start, end } = boundary {
+        diag[0] = two * h[0];
+        upper[0] = h[0];
+        lower[n - 1] = h[n - 2];
+        diag[n - 1] = two * h[n - 2];
+        for column in 0..columns {
+            rhs[column] = six * (slope(0, column) - start);
+            rhs[(n - 1) * columns + column] = six * (end - slope(n - 2, column));
+        }
+    }
+
+    solve_tridiagonal(&lower, &mut diag, &upper, &mut rhs, columns);
+    rhs
+}
+
+/// Solves a tridiagonal system in place with the Thomas algorithm.
+///
+/// `lower[0]` and `upper[n - 1]` are ignored. `rhs` holds `columns` right-hand
+/// sides, row-major, and is overwritten with the solutions. The matrix must be
+/// diagonally dominant, which spline systems always are.
+fn solve_tridiagonal<T: Float>(
+    lower: &[T],
+    diag: &mut [T],
+    upper: &[T],
+    rhs: &mut [T],
+    columns: usize,
+) {
+    let n = diag.len();
+    for i in 1..n {
+        let factor = lower[i] / diag[i - 1];
+        diag[i] = diag[i] - factor * upper[i - 1];
+        for column in 0..columns {
+            rhs[i * columns + column] =
+                rhs[i * columns + column] - factor * rhs[(i - 1) * columns + column];
+        }
+    }
+
+    for i in (0..n).rev() {
+        for column in 0..columns {
+            let mut value = rhs[i * columns + column];
+            if i + 1 < n {
+                value = value - upper[i] * rhs[(i + 1) * columns + column];
+            }
+            rhs[i * columns + column] = value / diag[i];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_spline_interpolates_knots() {
+        let x = Tensor::new(vec![0.0, 1.0, 2.0, 3.0], vec![4]).unwrap();
+        let y = Tensor::new(vec![0.0, 1.0, 0.0, 1.0], vec![4]).unwrap();
+
+        let spline = CubicSpline::fit(&x, &y).unwrap();
+
+        assert!(spline.evaluate(&x).unwrap().max_abs_diff(&y).unwrap() < 1e-12);
+        let linear = CubicSpline::fit(
+            &Tensor::new(vec![0.0, 2.0], vec![2]).unwrap(),
+            &Tensor::new(vec![1.0, 5.0], vec![2]).unwrap(),
+        )
+        .unwrap();
+        let result = linear
+            .evaluate(&Tensor::new(vec![0.5, 3.0], vec![2]).unwrap())
+            .unwrap();
+        assert!(
+            result
+                .max_abs_diff(&Tensor::new(vec![2.0, 7.0], vec![2]).unwrap())
+                .unwrap()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_clamped_spline_reproduces_cubic() {
+        // A clamped spline with the exact end slopes reproduces any cubic.
+        let f = |x: f64| x * x * x - 2.0 * x;
+        let x = Tensor::new(vec![-1.0, 0.0, 0.5, 2.0], vec![4]).unwrap();
+        let y = x.map(f);
+        let boundary = Boundary::Clamped {
+            start: 1.0,
+            end: 10.0,
+        };
+
+        let spline = CubicSpline::fit_with(&x, &y, boundary).unwrap();
+
+        let points = Tensor::new(vec![-0.7, 0.25, 1.3, 1.9], vec![4]).unwrap();
+        let result = spline.evaluate(&points).unwrap();
+        assert!(result.max_abs_diff(&points.map(f)).unwrap() < 1e-12);
+    }
+
+    #[test]
+    fn test_spline_batched_columns() {
+        let x = Tensor::new(vec![0.0, 1.0, 2.0], vec![3]).unwrap();
+        let y = Tensor::new(vec![0.0, 10.0, 1.0, 11.0, 4.0, 14.0], vec![3, 2]).unwrap();
+
+        let spline = CubicSpline::fit(&x, &y).unwrap();
+        let result = spline
+            .evaluate(&Tensor::new(vec![0.5], vec![1]).unwrap())
+            .unwrap();
+
+        assert_eq!(result.shape(), &[1, 2]);
+        let values = result.as_slice().unwrap();
+        assert!((values[1] - values[0] - 10.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_spline_rejects_bad_knots() {
+        let y = Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap();
+
+        let unsorted = Tensor::new(vec![0.0, 2.0, 1.0], vec![3]).unwrap();
+        assert!(matches!(
+            CubicSpline::fit(&unsorted, &y),
+            Err(TensorError::ValueError(_))
+        ));
+        let short = Tensor::new(vec![0.0, 1.0], vec![2]).unwrap();
+        assert!(matches!(
+            CubicSpline::fit(&short, &y),
+            Err(TensorError::ShapeError(_))
+        ));
+        let single = Tensor::new(vec![0.0], vec![1]).unwrap();
+        assert!(CubicSpline::fit(&single, &single).is_err());
+    }
+}
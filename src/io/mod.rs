@@ -0,0 +1,6 @@
+//! Reading and writing tensors in external file formats.
+
+pub mod npy;
+pub mod npz;
+#[cfg(feature = "safetensors")]
+pub mod safetensors;
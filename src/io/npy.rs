@@ -0,0 +1,389 @@
+//! Support for NumPy's `.npy` file format.
+//!
+//! Versions 1.0, 2.0 and 3.0 of the format are read; files are written as 1.0,
+//! or as 2.0 when the header is too large for 1.0. Fortran-ordered arrays are
+//! loaded without copying by giving the tensor column-major strides.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Element types that can be stored in `.npy` files.
+pub trait NpyElement: Copy {
+    /// The NumPy type character, e.g. `'f'` for floats.
+    const KIND: char;
+    /// The size of one element in bytes.
+    const SIZE: usize;
+
+    /// Decodes one element from exactly `SIZE` bytes.
+    fn from_bytes(bytes: &[u8], little_endian: bool) -> Self;
+    /// Appends the little-endian encoding of `self` to `out`.
+    fn write_le(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_npy_element {
+    ($($t:ty => $kind:expr),*) => {
+        $(
+            impl NpyElement for $t {
+                const KIND: char = $kind;
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn from_bytes(bytes: &[u8], little_endian: bool) -> Self {
+                    let bytes = bytes.try_into().unwrap();
+                    if little_endian {
+                        <$t>::from_le_bytes(bytes)
+                    } else {
+                        <$t>::from_be_bytes(bytes)
+                    }
+                }
+
+                fn write_le(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_npy_element!(f32 => 'f', f64 => 'f', i32 => 'i', i64 => 'i', u8 => 'u');
+
+impl NpyElement for bool {
+    const KIND: char = 'b';
+    const SIZE: usize = 1;
+
+    fn from_bytes(bytes: &[u8], _little_endian: bool) -> Self {
+        bytes[0] != 0
+    }
+
+    fn write_le(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+/// Returns the `descr` string NumPy uses for `T`, e.g. `<f8`.
+fn descr<T: NpyElement>() -> String {
+    let order = if T::SIZE == 1 { '|' } else { '<' };
+    format!("{}{}{}", order, T::KIND, T::SIZE)
+}
+
+impl<T: NpyElement> Tensor<T> {
+    /// Reads a tensor from a `.npy` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::IoError` if the file cannot be read, and
+    /// `TensorError::ParseError` if it is not a valid `.npy` file or its dtype
+    /// does not match `T`.
+    pub fn read_npy<P: AsRef<Path>>(path: P) -> Result<Self, TensorError> {
+        let file = File::open(path).map_err(io_error)?;
+        Self::read_npy_from(BufReader::new(file))
+    }
+
+    /// Reads a tensor in `.npy` format from any reader.
+    ///
+    /// # Errors
+    ///
+    /// See [`Tensor::read_npy`].
+    pub fn read_npy_from<R: Read>(mut reader: R) -> Result<Self, TensorError> {
+        let mut preamble = [0u8; 8];
+        reader.read_exact(&mut preamble).map_err(io_error)?;
+        if &preamble[..6] != MAGIC {
+            return Err(parse_error("missing .npy magic string"));
+        }
+
+        let header_len = match preamble[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len).map_err(io_error)?;
+                u16::from_le_bytes(len) as usize
+            }
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len).map_err(io_error)?;
+                u32::from_le_bytes(len) as usize
+            }
+            version => {
+                return Err(parse_error(&format!(
+                    "unsupported .npy version {}",
+                    version
+                )));
+            }
+        };
+
+        let header = read_bytes(&mut reader, header_len, "header")?;
+        let header = String::from_utf8(header).map_err(|_| parse_error("header is not UTF-8"))?;
+        let header = Header::parse(&header)?;
+
+        let little_endian = match header.descr.chars().next() {
+            Some('<') | Some('|') | Some('=') => true,
+            Some('>') => false,
+            _ => return Err(parse_error("invalid byte order in descr")),
+        };
+        if header.descr[1..] != descr::<T>()[1..] {
+            return Err(parse_error(&format!(
+                "dtype {} does not match the requested element type {}",
+                header.descr,
+                descr::<T>()
+            )));
+        }
+
+        let byte_len = header
+            .shape
+            .iter()
+            .try_fold(T::SIZE, |acc, &dim| acc.checked_mul(dim))
+            .ok_or_else(|| parse_error("array size overflows usize"))?;
+        let bytes = read_bytes(&mut reader, byte_len, "array data")?;
+        let data = bytes
+            .chunks_exact(T::SIZE)
+            .map(|chunk| T::from_bytes(chunk, little_endian))
+            .collect();
+
+        let mut tensor = Tensor::new(data, header.shape)?;
+        if header.fortran_order {
+            // Column-major strides: the first axis varies fastest in the buffer.
+            let mut stride = 1;
+            for (axis, &dim) in tensor.shape.iter().enumerate() {
+                tensor.strides[axis] = stride;
+                stride *= dim;
+            }
+        }
+
+        Ok(tensor)
+    }
+
+    /// Writes the tensor to a `.npy` file in C (row-major) order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::IoError` if the file cannot be written.
+    pub fn write_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), TensorError> {
+        let file = File::create(path).map_err(io_error)?;
+        let mut writer = BufWriter::new(file);
+        self.write_npy_to(&mut writer)?;
+        writer.flush().map_err(io_error)
+    }
+
+    /// Writes the tensor in `.npy` format to any writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::IoError` if writing fails.
+    pub fn write_npy_to<W: Write>(&self, mut writer: W) -> Result<(), TensorError> {
+        let shape = match self.shape.as_slice() {
+            [dim] => format!("({},)", dim),
+            dims => format!(
+                "({})",
+                dims.iter()
+                    .map(|dim| dim.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+            descr::<T>(),
+            shape
+        );
+
+        // The preamble plus header must be a multiple of 64 bytes, ending in '\n'.
+        let (version, preamble_len) = if header.len() + 11 <= u16::MAX as usize {
+            (1u8, 10)
+        } else {
+            (2u8, 12)
+        };
+        let padding = (64 - (preamble_len + header.len() + 1) % 64) % 64;
+        header.extend(std::iter::repeat_n(' ', padding));
+        header.push('\n');
+
+        let mut bytes = Vec::with_capacity(preamble_len + header.len() + self.len() * T::SIZE);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&[version, 0]);
+        if version == 1 {
+            bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        } else {
+            bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        }
+        bytes.extend_from_slice(header.as_bytes());
+        for value in self.iter() {
+            value.write_le(&mut bytes);
+        }
+
+        writer.write_all(&bytes).map_err(io_error)
+    }
+}
+
+/// The fields of a `.npy` header dictionary.
+struct Header {
+    descr: String,
+    fortran_order: bool,
+    shape: Vec<usize>,
+}
+
+impl Header {
+    /// Parses the Python dict literal stored in a `.npy` header.
+    fn parse(text: &str) -> Result<Self, TensorError> {
+        let descr = field(text, "descr")?;
+        let descr = descr
+            .strip_prefix('\'')
+            .and_then(|d| d.split('\'').next())
+            .ok_or_else(|| parse_error("descr is not a string"))?
+            .to_string();
+
+        let fortran_order = match field(text, "fortran_order")? {
+            f if f.starts_with("True") => true,
+            f if f.starts_with("False") => false,
+            _ => return Err(parse_error("fortran_order is not a boolean")),
+        };
+
+        let shape = field(text, "shape")?;
+        let shape = shape
+            .strip_prefix('(')
+            .and_then(|s| s.split(')').next())
+            .ok_or_else(|| parse_error("shape is not a tuple"))?;
+        let shape = shape
+            .split(',')
+            .map(str::trim)
+            .filter(|dim| !dim.is_empty())
+            .map(|dim| dim.parse().map_err(|_| parse_error("invalid shape entry")))
+            .collect::<Result<Vec<usize>, _>>()?;
+
+        Ok(Self {
+            descr,
+            fortran_order,
+            shape,
+        })
+    }
+}
+
+/// Returns the text following `'key':` in a header, with leading whitespace removed.
+fn field<'a>(text: &'a str, key: &str) -> Result<&'a str, TensorError> {
+    let pattern = format!("'{}':", key);
+    let start = text
+        .find(&pattern)
+        .ok_or_else(|| parse_error(&format!("header is missing '{}'", key)))?;
+    Ok(text[start + pattern.len()..].trim_start())
+}
+
+/// Reads exactly `len` bytes. The buffer grows with the data actually read, so
+/// a length taken from a corrupt header cannot force a huge allocation.
+fn read_bytes<R: Read>(reader: &mut R, len: usize, what: &str) -> Result<Vec<u8>, TensorError> {
+    let mut bytes = Vec::new();
+    reader
+        .take(len as u64)
+        .read_to_end(&mut bytes)
+        .map_err(io_error)?;
+    if bytes.len() < len {
+        return Err(parse_error(&format!(
+            "{} ends after {} of {} bytes",
+            what,
+            bytes.len(),
+            len
+        )));
+    }
+    Ok(bytes)
+}
+
+fn io_error(err: std::io::Error) -> TensorError {
+    TensorError::IoError(err.to_string())
+}
+
+fn parse_error(msg: &str) -> TensorError {
+    TensorError::ParseError(format!("invalid .npy data: {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_in_memory() {
+        let tensor = Tensor::new(vec![1.5f32, -2.0, 3.25, 4.0, 5.0, 6.0], vec![2, 3]).unwrap();
+
+        let mut bytes = Vec::new();
+        tensor.write_npy_to(&mut bytes).unwrap();
+        let restored = Tensor::<f32>::read_npy_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        assert_eq!((bytes.len() - 6 * 4) % 64, 0);
+        assert_eq!(restored, tensor);
+    }
+
+    #[test]
+    fn test_round_trip_file() {
+        let path = std::env::temp_dir().join("tiny_tensor_test_round_trip.npy");
+        let tensor = Tensor::new(vec![true, false, true], vec![3]).unwrap();
+
+        tensor.write_npy(&path).unwrap();
+        let restored = Tensor::<bool>::read_npy(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored, tensor);
+    }
+
+    #[test]
+    fn test_read_numpy_header() {
+        // Equivalent to np.save of np.array([[1, 2, 3], [4, 5, 6]], dtype='>i4', order='F').
+        let header = "{'descr': '>i4', 'fortran_order': True, 'shape': (2, 3), }";
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for value in [1i32, 4, 2, 5, 3, 6] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let tensor = Tensor::<i32>::read_npy_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(tensor.shape(), &[2, 3]);
+        assert_eq!(
+            tensor.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_read_rejects_wrong_dtype() {
+        let tensor = Tensor::new(vec![1i64, 2], vec![2]).unwrap();
+        let mut bytes = Vec::new();
+        tensor.write_npy_to(&mut bytes).unwrap();
+
+        let result = Tensor::<f64>::read_npy_from(bytes.as_slice());
+
+        assert!(matches!(result, Err(TensorError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_read_rejects_hostile_sizes() {
+        let npy = |header: &str, data: &[u8]| {
+            let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+            bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(header.as_bytes());
+            bytes.extend_from_slice(data);
+            bytes
+        };
+
+        // The element count overflows usize.
+        let overflow = format!(
+            "{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {}), }}",
+            usize::MAX,
+            2
+        );
+        let result = Tensor::<f64>::read_npy_from(npy(&overflow, &[]).as_slice());
+        assert!(matches!(result, Err(TensorError::ParseError(_))));
+
+        // A huge but representable size is not allocated up front.
+        let huge = "{'descr': '<f8', 'fortran_order': False, 'shape': (1000000000000,), }";
+        let result = Tensor::<f64>::read_npy_from(npy(huge, &[0; 16]).as_slice());
+        assert!(matches!(result, Err(TensorError::ParseError(_))));
+
+        // A header length beyond the end of the data.
+        let mut truncated = b"\x93NUMPY\x02\x00".to_vec();
+        truncated.extend_from_slice(&u32::MAX.to_le_bytes());
+        let result = Tensor::<f64>::read_npy_from(truncated.as_slice());
+        assert!(matches!(result, Err(TensorError::ParseError(_))));
+    }
+}
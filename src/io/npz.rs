@@ -0,0 +1,344 @@
+//! Support for NumPy's `.npz` archives: zip files holding one `.npy` file per array.
+//!
+//! Archives are written uncompressed, as `numpy.savez` does. Reading supports
+//! uncompressed members, including the zip64 records NumPy emits; compressed
+//! archives from `numpy.savez_compressed` are rejected.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::error::TensorError;
+use crate::io::npy::NpyElement;
+use crate::tensor::Tensor;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+/// 1980-01-01, the earliest date a zip file can record.
+const DOS_DATE: u16 = 0x0021;
+
+/// Reads every array in a `.npz` archive, keyed by name without the `.npy` suffix.
+///
+/// All arrays must have element type `T`.
+///
+/// # Errors
+///
+/// Returns `TensorError::IoError` if the file cannot be read, and
+/// `TensorError::ParseError` if the archive or any of its arrays is invalid,
+/// compressed, or of a different dtype.
+pub fn read_npz<T, P>(path: P) -> Result<HashMap<String, Tensor<T>>, TensorError>
+where
+    T: NpyElement,
+    P: AsRef<Path>,
+{
+    let bytes = fs::read(path).map_err(|err| TensorError::IoError(err.to_string()))?;
+    let mut tensors = HashMap::new();
+
+    for entry in central_directory(&bytes)? {
+        if entry.method != 0 {
+            return Err(parse_error(&format!(
+                "member '{}' is compressed, which is not supported",
+                entry.name
+            )));
+        }
+
+        let local = slice(&bytes, entry.offset, 30)?;
+        if read_u32(local, 0) != LOCAL_HEADER_SIGNATURE {
+            return Err(parse_error("invalid local file header"));
+        }
+        let start = checked_add(entry.offset, 30 + read_u16(local, 26) as usize)
+            .and_then(|start| checked_add(start, read_u16(local, 28) as usize))?;
+        let data = slice(&bytes, start, entry.size)?;
+
+        let name = entry.name.strip_suffix(".npy").unwrap_or(&entry.name);
+        tensors.insert(name.to_string(), Tensor::read_npy_from(data)?);
+    }
+
+    Ok(tensors)
+}
+
+/// Writes a collection of named arrays to an uncompressed `.npz` archive.
+///
+/// Each array is stored as `<name>.npy`, so the file can be opened with
+/// `numpy.load`. Members are written in sorted name order.
+///
+/// # Errors
+///
+/// Returns `TensorError::IoError` if the file cannot be written, or if the
+/// archive would exceed the limits of the plain zip format: 4 GiB in total,
+/// 65535 members, and 65535 bytes per member name.
+pub fn write_npz<T, P>(path: P, tensors: &HashMap<String, Tensor<T>>) -> Result<(), TensorError>
+where
+    T: NpyElement,
+    P: AsRef<Path>,
+{
+    let mut names: Vec<&String> = tensors.keys().collect();
+    names.sort();
+    let count = to_u16(names.len(), "members")?;
+
+    let mut archive = Vec::new();
+    let mut central = Vec::new();
+    for name in &names {
+        let mut data = Vec::new();
+        tensors[*name].write_npy_to(&mut data)?;
+        let file_name = format!("{}.npy", name);
+        let offset = to_u32(archive.len())?;
+        let size = to_u32(data.len())?;
+        let crc = crc32(&data);
+        let name_len = to_u16(file_name.len(), "bytes in a member name")?;
+
+        push_u32(&mut archive, LOCAL_HEADER_SIGNATURE);
+        push_common_fields(&mut archive, crc, size, name_len);
+        push_u16(&mut archive, 0); // extra field length
+        archive.extend_from_slice(file_name.as_bytes());
+        archive.extend_from_slice(&data);
+
+        push_u32(&mut central, CENTRAL_HEADER_SIGNATURE);
+        push_u16(&mut central, 20); // version made by
+        push_common_fields(&mut central, crc, size, name_len);
+        for _ in 0..4 {
+            // extra field length, comment length, disk number, internal attributes
+            push_u16(&mut central, 0);
+        }
+        push_u32(&mut central, 0); // external attributes
+        push_u32(&mut central, offset);
+        central.extend_from_slice(file_name.as_bytes());
+    }
+
+    let central_offset = to_u32(archive.len())?;
+    let central_size = to_u32(central.len())?;
+    archive.extend_from_slice(&central);
+    push_u32(&mut archive, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+    push_u16(&mut archive, 0); // this disk
+    push_u16(&mut archive, 0); // disk with the central directory
+    push_u16(&mut archive, count);
+    push_u16(&mut archive, count);
+    push_u32(&mut archive, central_size);
+    push_u32(&mut archive, central_offset);
+    push_u16(&mut archive, 0); // comment length
+
+    fs::write(path, archive).map_err(|err| TensorError::IoError(err.to_string()))
+}
+
+/// A member of a zip archive, as described by its central directory entry.
+struct Entry {
+    name: String,
+    method: u16,
+    size: usize,
+    offset: usize,
+}
+
+/// Locates the end-of-central-directory record and parses every entry.
+fn central_directory(bytes: &[u8]) -> Result<Vec<Entry>, TensorError> {
+    let end = (0..=bytes.len().saturating_sub(22))
+        .rev()
+        .find(|&pos| read_u32(bytes, pos) == END_OF_CENTRAL_DIRECTORY_SIGNATURE)
+        .ok_or_else(|| parse_error("missing end of central directory record"))?;
+    let count = read_u16(bytes, end + 10) as usize;
+    let mut pos = read_u32(bytes, end + 16) as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        let header = slice(bytes, pos, 46)?;
+        if read_u32(header, 0) != CENTRAL_HEADER_SIGNATURE {
+            return Err(parse_error("invalid central directory entry"));
+        }
+        let name_len = read_u16(header, 28) as usize;
+        let extra_len = read_u16(header, 30) as usize;
+        let comment_len = read_u16(header, 32) as usize;
+        let name = slice(bytes, checked_add(pos, 46)?, name_len)?;
+        let name = String::from_utf8(name.to_vec())
+            .map_err(|_| parse_error("member name is not UTF-8"))?;
+        let extra = slice(bytes, checked_add(pos, 46 + name_len)?, extra_len)?;
+
+        // Fields saturated at 0xFFFFFFFF are stored in the zip64 extra field,
+        // in the order uncompressed size, compressed size, local header offset.
+        let mut zip64 = zip64_values(extra);
+        let uncompressed = read_u32(header, 24);
+        let mut size = read_u32(header, 20) as u64;
+        let mut offset = read_u32(header, 42) as u64;
+        if uncompressed == u32::MAX {
+            zip64.next();
+        }
+        if size == u32::MAX as u64 {
+            size = zip64
+                .next()
+                .ok_or_else(|| parse_error("missing zip64 size"))?;
+        }
+        if offset == u32::MAX as u64 {
+            offset = zip64
+                .next()
+                .ok_or_else(|| parse_error("missing zip64 offset"))?;
+        }
+
+        entries.push(Entry {
+            name,
+            method: read_u16(header, 10),
+            size: usize::try_from(size).map_err(|_| parse_error("member size overflows usize"))?,
+            offset: usize::try_from(offset)
+                .map_err(|_| parse_error("member offset overflows usize"))?,
+        });
+        pos = checked_add(pos, 46 + name_len + extra_len + comment_len)?;
+    }
+
+    Ok(entries)
+}
+
+/// Returns the 8-byte values of the zip64 extra field, if present.
+fn zip64_values(extra: &[u8]) -> impl Iterator<Item = u64> + '_ {
+    let mut pos = 0;
+    let mut field: &[u8] = &[];
+    while pos + 4 <= extra.len() {
+        let id = read_u16(extra, pos);
+        let len = read_u16(extra, pos + 2) as usize;
+        let end = (pos + 4 + len).min(extra.len());
+        if id == ZIP64_EXTRA_FIELD_ID {
+            field = &extra[pos + 4..end];
+            break;
+        }
+        pos = end;
+    }
+
+    field
+        .chunks_exact(8)
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+}
+
+/// Appends the header fields shared by local and central directory entries,
+/// from "version needed" through "file name length".
+fn push_common_fields(out: &mut Vec<u8>, crc: u32, size: u32, name_len: u16) {
+    push_u16(out, 20); // version needed to extract
+    push_u16(out, 0); // flags
+    push_u16(out, 0); // compression method: stored
+    push_u16(out, 0); // modification time
+    push_u16(out, DOS_DATE);
+    push_u32(out, crc);
+    push_u32(out, size); // compressed size
+    push_u32(out, size); // uncompressed size
+    push_u16(out, name_len);
+}
+
+/// Computes the CRC-32 (IEEE) checksum used by the zip format.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+fn slice(bytes: &[u8], start: usize, len: usize) -> Result<&[u8], TensorError> {
+    bytes
+        .get(start..checked_add(start, len)?)
+        .ok_or_else(|| parse_error("unexpected end of archive"))
+}
+
+/// Adds two archive positions, treating overflow as a corrupt archive.
+fn checked_add(a: usize, b: usize) -> Result<usize, TensorError> {
+    a.checked_add(b)
+        .ok_or_else(|| parse_error("offset overflows usize"))
+}
+
+fn read_u16(bytes: &[u8], pos: usize) -> u16 {
+    bytes
+        .get(pos..pos + 2)
+        .map_or(0, |b| u16::from_le_bytes(b.try_into().unwrap()))
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    bytes
+        .get(pos..pos + 4)
+        .map_or(0, |b| u32::from_le_bytes(b.try_into().unwrap()))
+}
+
+fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn to_u16(value: usize, what: &str) -> Result<u16, TensorError> {
+    u16::try_from(value).map_err(|_| {
+        TensorError::IoError(format!(
+            "{} {} exceed the plain zip limit of 65535",
+            value, what
+        ))
+    })
+}
+
+fn to_u32(value: usize) -> Result<u32, TensorError> {
+    u32::try_from(value)
+        .map_err(|_| TensorError::IoError("archive exceeds the 4 GiB zip limit".to_string()))
+}
+
+fn parse_error(msg: &str) -> TensorError {
+    TensorError::ParseError(format!("invalid .npz archive: {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_npz_round_trip() {
+        let path = std::env::temp_dir().join("tiny_tensor_test_round_trip.npz");
+        let mut tensors = HashMap::new();
+        tensors.insert(
+            "weights".to_string(),
+            Tensor::new(vec![0.5f64, -1.0, 2.0, 4.0], vec![2, 2]).unwrap(),
+        );
+        tensors.insert(
+            "bias".to_string(),
+            Tensor::new(vec![0.1, 0.2], vec![2]).unwrap(),
+        );
+
+        write_npz(&path, &tensors).unwrap();
+        let restored = read_npz::<f64, _>(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored, tensors);
+    }
+
+    #[test]
+    fn test_read_npz_rejects_garbage() {
+        let path = std::env::temp_dir().join("tiny_tensor_test_garbage.npz");
+        std::fs::write(&path, b"not a zip file at all, just some bytes").unwrap();
+
+        let result = read_npz::<f32, _>(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(TensorError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_npz_rejects_out_of_range_counts_and_offsets() {
+        let path = std::env::temp_dir().join("tiny_tensor_test_too_many_members.npz");
+        let scalar = Tensor::new(vec![0u8], vec![1]).unwrap();
+        let tensors: HashMap<String, Tensor<u8>> = (0..=u16::MAX as usize)
+            .map(|i| (i.to_string(), scalar.clone()))
+            .collect();
+
+        let result = write_npz(&path, &tensors);
+
+        assert!(matches!(result, Err(TensorError::IoError(_))));
+        assert!(!path.exists());
+        assert!(matches!(
+            slice(b"abc", usize::MAX, 2),
+            Err(TensorError::ParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,556 @@
+//! Support for the safetensors format, available with the `safetensors` feature.
+//!
+//! A safetensors file is an 8-byte little-endian header length, a JSON header
+//! mapping tensor names to their dtype, shape and byte range, and a raw
+//! little-endian data buffer. Half-precision tensors use the `half` crate.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use half::{bf16, f16};
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+/// Element types that can be stored in safetensors files.
+pub trait SafetensorsElement: Copy {
+    /// The dtype name used in the header, e.g. `"F32"`.
+    const DTYPE: &'static str;
+    /// The size of one element in bytes.
+    const SIZE: usize;
+
+    /// Decodes one element from exactly `SIZE` little-endian bytes.
+    fn from_le(bytes: &[u8]) -> Self;
+    /// Appends the little-endian encoding of `self` to `out`.
+    fn write_le(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_safetensors_element {
+    ($($t:ty => $dtype:expr),*) => {
+        $(
+            impl SafetensorsElement for $t {
+                const DTYPE: &'static str = $dtype;
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn from_le(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+
+                fn write_le(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_safetensors_element!(
+    f64 => "F64", f32 => "F32", f16 => "F16", bf16 => "BF16",
+    i64 => "I64", i32 => "I32", i16 => "I16", i8 => "I8", u8 => "U8"
+);
+
+impl SafetensorsElement for bool {
+    const DTYPE: &'static str = "BOOL";
+    const SIZE: usize = 1;
+
+    fn from_le(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+
+    fn write_le(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+/// Reads every tensor in a safetensors file, keyed by name.
+///
+/// All tensors must have element type `T`; the `__metadata__` entry is ignored.
+///
+/// # Errors
+///
+/// Returns `TensorError::IoError` if the file cannot be read, and
+/// `TensorError::ParseError` if the file is malformed or a tensor has a
+/// different dtype.
+pub fn read_safetensors<T, P>(path: P) -> Result<HashMap<String, Tensor<T>>, TensorError>
+where
+    T: SafetensorsElement,
+    P: AsRef<Path>,
+{
+    let bytes = fs::read(path).map_err(|err| TensorError::IoError(err.to_string()))?;
+    from_safetensors_bytes(&bytes)
+}
+
+/// Parses every tensor from an in-memory safetensors buffer.
+///
+/// # Errors
+///
+/// See [`read_safetensors`].
+pub fn from_safetensors_bytes<T>(bytes: &[u8]) -> Result<HashMap<String, Tensor<T>>, TensorError>
+where
+    T: SafetensorsElement,
+{
+    let header_len = bytes
+        .get(..8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()) as usize)
+        .ok_or_else(|| parse_error("missing header length"))?;
+    let header = bytes
+        .get(8..8usize.saturating_add(header_len))
+        .ok_or_else(|| parse_error("header extends past the end of the file"))?;
+    let header = std::str::from_utf8(header).map_err(|_| parse_error("header is not UTF-8"))?;
+    let buffer = &bytes[8 + header_len..];
+
+    let Json::Object(entries) = Json::parse(header)? else {
+        return Err(parse_error("header is not a JSON object"));
+    };
+
+    let mut tensors = HashMap::new();
+    for (name, info) in entries {
+        if name == "__metadata__" {
+            continue;
+        }
+
+        let dtype = match info.get("dtype") {
+            Some(Json::String(dtype)) => dtype.as_str(),
+            _ => return Err(parse_error(&format!("'{}' has no dtype", name))),
+        };
+        if dtype != T::DTYPE {
+            return Err(parse_error(&format!(
+                "'{}' has dtype {}, expected {}",
+                name,
+                dtype,
+                T::DTYPE
+            )));
+        }
+        let shape = info
+            .get("shape")
+            .and_then(Json::as_usizes)
+            .ok_or_else(|| parse_error(&format!("'{}' has an invalid shape", name)))?;
+        let (begin, end) = match info
+            .get("data_offsets")
+            .and_then(Json::as_usizes)
+            .as_deref()
+        {
+            Some(&[begin, end]) if begin <= end => (begin, end),
+            _ => return Err(parse_error(&format!("'{}' has invalid data offsets", name))),
+        };
+        let data = buffer
+            .get(begin..end)
+            .ok_or_else(|| parse_error(&format!("'{}' lies outside the data buffer", name)))?;
+        let byte_len = shape
+            .iter()
+            .try_fold(T::SIZE, |acc, &dim| acc.checked_mul(dim))
+            .ok_or_else(|| parse_error(&format!("'{}' has a size that overflows usize", name)))?;
+        if data.len() != byte_len {
+            return Err(parse_error(&format!(
+                "'{}' has a byte length that does not match its shape",
+                name
+            )));
+        }
+
+        let data = data.chunks_exact(T::SIZE).map(T::from_le).collect();
+        tensors.insert(name, Tensor::new(data, shape)?);
+    }
+
+    Ok(tensors)
+}
+
+/// Writes a collection of named tensors to a safetensors file.
+///
+/// Tensors are laid out in sorted name order.
+///
+/// # Errors
+///
+/// Returns `TensorError::IoError` if the file cannot be written.
+pub fn write_safetensors<T, P>(
+    path: P,
+    tensors: &HashMap<String, Tensor<T>>,
+) -> Result<(), TensorError>
+where
+    T: SafetensorsElement,
+    P: AsRef<Path>,
+{
+    fs::write(path, to_safetensors_bytes(tensors))
+        .map_err(|err| TensorError::IoError(err.to_string()))
+}
+
+/// Serializes a collection of named tensors into an in-memory safetensors buffer.
+pub fn to_safetensors_bytes<T: SafetensorsElement>(
+    tensors: &HashMap<String, Tensor<T>>,
+) -> Vec<u8> {
+    let mut names: Vec<&String> = tensors.keys().collect();
+    names.sort();
+
+    let mut buffer = Vec::new();
+    let mut fields = Vec::with_capacity(names.len());
+    for name in names {
+        let tensor = &tensors[name];
+        let begin = buffer.len();
+        for value in tensor.iter() {
+            value.write_le(&mut buffer);
+        }
+        let shape: Vec<String> = tensor.shape().iter().map(|dim| dim.to_string()).collect();
+        fields.push(format!(
+            "{}:{{\"dtype\":\"{}\",\"shape\":[{}],\"data_offsets\":[{},{}]}}",
+            json_string(name),
+            T::DTYPE,
+            shape.join(","),
+            begin,
+            buffer.len()
+        ));
+    }
+
+    // The data buffer must start at an 8-byte aligned offset.
+    let mut header = format!("{{{}}}", fields.join(","));
+    header.extend(std::iter::repeat_n(' ', (8 - header.len() % 8) % 8));
+
+    let mut bytes = Vec::with_capacity(8 + header.len() + buffer.len());
+    bytes.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(&buffer);
+    bytes
+}
+
+/// Encodes `value` as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A parsed JSON value; just enough of JSON to read safetensors headers.
+#[derive(Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(text: &str) -> Result<Self, TensorError> {
+        let mut parser = JsonParser {
+            text,
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != text.len() {
+            return Err(parse_error("trailing characters after the JSON header"));
+        }
+        Ok(value)
+    }
+
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Interprets an array of non-negative integers as `usize`s.
+    fn as_usizes(&self) -> Option<Vec<usize>> {
+        match self {
+            Json::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Json::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// The deepest nesting of arrays and objects accepted in a header. Real
+/// headers nest three levels deep; the cap keeps a hostile header from
+/// exhausting the stack.
+const MAX_JSON_DEPTH: usize = 64;
+
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), TensorError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(parse_error(&format!(
+                "expected '{}' at byte {} of the header",
+                c, self.pos
+            )))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, TensorError> {
+        self.skip_whitespace();
+        let rest = &self.text[self.pos..];
+        match self.peek() {
+            Some(open @ ('{' | '[')) => {
+                if self.depth == MAX_JSON_DEPTH {
+                    return Err(parse_error(&format!(
+                        "header nests deeper than {} levels",
+                        MAX_JSON_DEPTH
+                    )));
+                }
+                self.depth += 1;
+                let value = if open == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => self.string().map(Json::String),
+            Some('t') if rest.starts_with("true") => {
+                self.pos += 4;
+                Ok(Json::Bool(true))
+            }
+            Some('f') if rest.starts_with("false") => {
+                self.pos += 5;
+                Ok(Json::Bool(false))
+            }
+            Some('n') if rest.starts_with("null") => {
+                self.pos += 4;
+                Ok(Json::Null)
+            }
+            _ => self.number(),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, TensorError> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                _ => break,
+            }
+        }
+        self.expect('}')?;
+        Ok(Json::Object(entries))
+    }
+
+    fn array(&mut self) -> Result<Json, TensorError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                _ => break,
+            }
+        }
+        self.expect(']')?;
+        Ok(Json::Array(items))
+    }
+
+    fn string(&mut self) -> Result<String, TensorError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, 'n')) => '\n',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'u')) => {
+                            let hex: String = (0..4)
+                                .filter_map(|_| chars.next().map(|(_, h)| h))
+                                .collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        Some((_, other)) => other,
+                        None => break,
+                    };
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+        Err(parse_error("unterminated string in the header"))
+    }
+
+    fn number(&mut self) -> Result<Json, TensorError> {
+        let rest = &self.text[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(rest.len());
+        let value = rest[..len].parse().map_err(|_| {
+            parse_error(&format!(
+                "invalid JSON value at byte {} of the header",
+                self.pos
+            ))
+        })?;
+        self.pos += len;
+        Ok(Json::Number(value))
+    }
+}
+
+fn parse_error(msg: &str) -> TensorError {
+    TensorError::ParseError(format!("invalid safetensors data: {}", msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_f16() {
+        let mut tensors = HashMap::new();
+        let values = [1.0, -0.5, 2.25, 1024.0].map(f16::from_f32).to_vec();
+        tensors.insert(
+            "layer.weight".to_string(),
+            Tensor::new(values, vec![2, 2]).unwrap(),
+        );
+        tensors.insert(
+            "layer.bias".to_string(),
+            Tensor::new(vec![f16::ZERO], vec![1]).unwrap(),
+        );
+
+        let bytes = to_safetensors_bytes(&tensors);
+        let restored = from_safetensors_bytes::<f16>(&bytes).unwrap();
+
+        assert_eq!(u64::from_le_bytes(bytes[..8].try_into().unwrap()) % 8, 0);
+        assert_eq!(restored, tensors);
+    }
+
+    #[test]
+    fn test_read_handwritten_header() {
+        let header = r#"{"__metadata__": {"format": "pt"}, "w": {"dtype": "F32", "shape": [2], "data_offsets": [0, 8]}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&1.5f32.to_le_bytes());
+        bytes.extend_from_slice(&(-2.0f32).to_le_bytes());
+
+        let tensors = from_safetensors_bytes::<f32>(&bytes).unwrap();
+
+        assert_eq!(tensors["w"].as_slice(), Some(&[1.5, -2.0][..]));
+    }
+
+    #[test]
+    fn test_read_rejects_bad_files() {
+        let mut tensors = HashMap::new();
+        tensors.insert(
+            "x".to_string(),
+            Tensor::new(vec![1i32, 2], vec![2]).unwrap(),
+        );
+        let bytes = to_safetensors_bytes(&tensors);
+
+        assert!(matches!(
+            from_safetensors_bytes::<f32>(&bytes),
+            Err(TensorError::ParseError(_))
+        ));
+        assert!(matches!(
+            from_safetensors_bytes::<i32>(&bytes[..bytes.len() - 1]),
+            Err(TensorError::ParseError(_))
+        ));
+        assert!(matches!(
+            from_safetensors_bytes::<i32>(&[1, 2, 3]),
+            Err(TensorError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_rejects_hostile_headers() {
+        let file = |header: &str| {
+            let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+            bytes.extend_from_slice(header.as_bytes());
+            bytes
+        };
+
+        // The shape's byte length overflows usize.
+        let overflow = format!(
+            r#"{{"w": {{"dtype": "F32", "shape": [{}, 2], "data_offsets": [0, 0]}}}}"#,
+            usize::MAX / 2
+        );
+        assert!(matches!(
+            from_safetensors_bytes::<f32>(&file(&overflow)),
+            Err(TensorError::ParseError(_))
+        ));
+
+        // Deep nesting is rejected instead of overflowing the stack.
+        let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(matches!(
+            from_safetensors_bytes::<f32>(&file(&nested)),
+            Err(TensorError::ParseError(_))
+        ));
+        let shallow = format!(
+            r#"{{"__metadata__": {}1{}}}"#,
+            "[".repeat(10),
+            "]".repeat(10)
+        );
+        assert!(from_safetensors_bytes::<f32>(&file(&shallow)).is_ok());
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let path = std::env::temp_dir().join("tiny_tensor_test_round_trip.safetensors");
+        let mut tensors = HashMap::new();
+        tensors.insert(
+            "quote\"name".to_string(),
+            Tensor::new(vec![true, false], vec![1, 2]).unwrap(),
+        );
+
+        write_safetensors(&path, &tensors).unwrap();
+        let restored = read_safetensors::<bool, _>(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(restored, tensors);
+    }
+}
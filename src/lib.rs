@@ -1,3 +1,46 @@
+#[cfg(feature = "bytemuck")]
+mod bytes;
+pub mod cancel;
+pub mod compare;
+pub mod config;
+pub mod convert;
 pub mod creation;
+pub mod decomp;
+pub mod difference;
+pub mod display;
+pub mod distance;
+pub mod einops;
+pub mod einsum;
 pub mod error;
+mod fft;
+pub mod float;
+pub mod graph;
+pub mod histogram;
+#[cfg(feature = "ndarray")]
+mod interop;
+pub mod interp;
+pub mod io;
+pub mod linalg;
+pub mod manipulation;
+pub mod nn;
+pub mod num;
+pub mod ops;
+pub mod optimize;
+pub mod parse;
+pub mod polynomial;
+pub mod promotion;
+pub mod ragged;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod reduction;
+pub mod select;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod signal;
+pub mod sorting;
+mod special;
+pub mod stats;
 pub mod tensor;
+pub mod testing;
+pub mod text;
+pub mod timeseries;
@@ -0,0 +1,1644 @@
+//! Dense linear algebra: LU, Cholesky, QR, singular value and symmetric
+//! eigenvalue decompositions, determinants, inverses and linear solvers, plus
+//! randomized sketches for large matrices with the `rand` feature.
+
+use std::cmp::Ordering;
+use std::ops::{Mul, Range};
+
+use crate::cancel;
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::sorting::total_cmp;
+use crate::tensor::Tensor;
+
+/// Returns the order `n` of the square `[n, n]` matrix `a`.
+fn square_order<T>(a: &Tensor<T>, what: &str) -> Result<usize, TensorError> {
+    match a.shape() {
+        [n, m] if n == m => Ok(*n),
+        shape => Err(TensorError::ShapeError(format!(
+            "{} needs a square 2D matrix, got shape {:?}",
+            what, shape
+        ))),
+    }
+}
+
+/// Factors the row-major `n x n` matrix in `lu` in place as `P A = L U` with
+/// partial pivoting, storing the unit lower factor `L` below the diagonal and
+/// `U` on and above it.
+///
+/// Returns the row permutation, where `perm[i]` is the row of `A` that ends up
+/// in row `i`, its sign, and whether a zero pivot was met, i.e. whether `A` is
+/// singular.
+fn lu_in_place<T: Float>(lu: &mut [T], n: usize) -> (Vec<usize>, T, bool) {
+    let mut perm: Vec<usize> = (0..n).collect();
+    let mut sign = T::one();
+    let mut singular = false;
+    for k in 0..n {
+        let pivot = (k..n)
+            .max_by(|&i, &j| {
+                lu[i * n + k]
+                    .abs()
+                    .partial_cmp(&lu[j * n + k].abs())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(k);
+        if pivot != k {
+            for j in 0..n {
+                lu.swap(k * n + j, pivot * n + j);
+            }
+            perm.swap(k, pivot);
+            sign = -sign;
+        }
+
+        let diagonal = lu[k * n + k];
+        if diagonal == T::zero() {
+            singular = true;
+            continue;
+        }
+        for i in k + 1..n {
+            let factor = lu[i * n + k] / diagonal;
+            lu[i * n + k] = factor;
+            for j in k + 1..n {
+                lu[i * n + j] = lu[i * n + j] - factor * lu[k * n + j];
+            }
+        }
+    }
+    (perm, sign, singular)
+}
+
+/// Solves `A X = B` in place for the `k` columns of the row-major `[n, k]`
+/// right-hand side `rhs`, given the factors and permutation from
+/// [`lu_in_place`] of a non-singular `A`.
+fn lu_solve<T: Float>(lu: &[T], perm: &[usize], n: usize, rhs: &mut [T], k: usize) {
+    let permuted: Vec<T> = perm
+        .iter()
+        .flat_map(|&row| rhs[row * k..(row + 1) * k].to_vec())
+        .collect();
+    rhs.copy_from_slice(&permuted);
+    for i in 0..n {
+        for p in 0..i {
+            let factor = lu[i * n + p];
+            for c in 0..k {
+                rhs[i * k + c] = rhs[i * k + c] - factor * rhs[p * k + c];
+            }
+        }
+    }
+    for i in (0..n).rev() {
+        for p in i + 1..n {
+            let factor = lu[i * n + p];
+            for c in 0..k {
+                rhs[i * k + c] = rhs[i * k + c] - factor * rhs[p * k + c];
+            }
+        }
+        for c in 0..k {
+            rhs[i * k + c] = rhs[i * k + c] / lu[i * n + i];
+        }
+    }
+}
+
+/// Returns the right-hand side `b` of a system with `rows` equations as a
+/// row-major `[rows, k]` buffer and `k`; a 1D `b` is a single column.
+fn right_hand_side<T: Float>(b: &Tensor<T>, rows: usize) -> Result<(Vec<T>, usize), TensorError> {
+    let k = match b.shape() {
+        [n] if *n == rows => 1,
+        [n, k] if *n == rows => *k,
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "right-hand side of shape {:?} does not match {} equations",
+                shape, rows
+            )));
+        }
+    };
+    Ok((b.iter().copied().collect(), k))
+}
+
+/// Returns the spacing between one and the next larger representable value.
+fn machine_epsilon<T: Float>() -> T {
+    let two = T::from_usize(2);
+    let mut eps = T::one();
+    while T::one() + eps / two > T::one() {
+        eps = eps / two;
+    }
+    eps
+}
+
+/// A Householder reflection `I - scale v v^T` acting on rows `start..` of a
+/// row-major matrix.
+struct Reflector<T> {
+    start: usize,
+    v: Vec<T>,
+    scale: T,
+}
+
+impl<T: Float> Reflector<T> {
+    /// Reflects the given columns of the row-major matrix `data` with `width`
+    /// columns.
+    fn apply(&self, data: &mut [T], width: usize, columns: Range<usize>) {
+        for c in columns {
+            let dot = self.v.iter().enumerate().fold(T::zero(), |acc, (p, &vp)| {
+                acc + vp * data[(self.start + p) * width + c]
+            });
+            let factor = dot * self.scale;
+            for (p, &vp) in self.v.iter().enumerate() {
+                let index = (self.start + p) * width + c;
+                data[index] = data[index] - factor * vp;
+            }
+        }
+    }
+}
+
+/// Reduces the row-major `[m, n]` matrix `r` in place to upper trapezoidal
+/// form with Householder reflections, returning them in the order applied.
+///
+/// Columns that are already zero below the diagonal need no reflection, so
+/// fewer than `min(m, n)` reflections may be returned.
+fn householder<T: Float>(r: &mut [T], m: usize, n: usize) -> Vec<Reflector<T>> {
+    let mut reflectors = Vec::with_capacity(m.min(n));
+    for col in 0..m.min(n) {
+        let norm = (col..m)
+            .fold(T::zero(), |acc, i| acc + r[i * n + col] * r[i * n + col])
+            .sqrt();
+        if norm == T::zero() {
+            continue;
+        }
+        // Reflect onto the sign opposite the diagonal entry to avoid cancellation.
+        let alpha = if r[col * n + col] > T::zero() {
+            -norm
+        } else {
+            norm
+        };
+        let mut v: Vec<T> = (col..m).map(|i| r[i * n + col]).collect();
+        v[0] = v[0] - alpha;
+        let v_norm_sq = v.iter().fold(T::zero(), |acc, &x| acc + x * x);
+        if v_norm_sq == T::zero() {
+            continue;
+        }
+        let reflector = Reflector {
+            start: col,
+            v,
+            scale: T::from_usize(2) / v_norm_sq,
+        };
+        reflector.apply(r, n, col..n);
+        // Clear the round-off left below the diagonal.
+        for i in col + 1..m {
+            r[i * n + col] = T::zero();
+        }
+        reflectors.push(reflector);
+    }
+    reflectors
+}
+
+/// An LU factorization `P A = L U` with partial pivoting of a square matrix
+/// `A`, as returned by [`lu`].
+///
+/// `L` is unit lower triangular, `U` is upper triangular and `P` is a
+/// permutation matrix. The factorization can be reused to solve any number of
+/// systems with the same matrix without refactorizing it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LuFactorization<T> {
+    n: usize,
+    /// `L` below the diagonal, without its unit diagonal, and `U` on and above.
+    lu: Vec<T>,
+    /// `perm[i]` is the row of `A` that ends up in row `i` of `P A`.
+    perm: Vec<usize>,
+    sign: T,
+    singular: bool,
+}
+
+/// Computes the LU factorization with partial pivoting of a square matrix.
+///
+/// Factorization succeeds for singular matrices too; [`LuFactorization::solve`]
+/// and [`LuFactorization::inverse`] then report the singularity.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not a square 2D matrix.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::lu;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![1.0f64, 2.0, 3.0, 4.0], vec![2, 2]).unwrap();
+/// let factors = lu(&a).unwrap();
+/// assert_eq!(factors.permutation(), &[1, 0]);
+/// // Both right-hand sides reuse the same factorization.
+/// for b in [vec![5.0, 11.0], vec![3.0, 7.0]] {
+///     let x = factors.solve(&Tensor::new(b, vec![2]).unwrap()).unwrap();
+///     assert!((x.as_slice().unwrap()[0] - 1.0).abs() < 1e-12);
+/// }
+/// ```
+pub fn lu<T: Float>(a: &Tensor<T>) -> Result<LuFactorization<T>, TensorError> {
+    let n = square_order(a, "lu")?;
+    let mut lu: Vec<T> = a.iter().copied().collect();
+    let (perm, sign, singular) = lu_in_place(&mut lu, n);
+    Ok(LuFactorization {
+        n,
+        lu,
+        perm,
+        sign,
+        singular,
+    })
+}
+
+impl<T: Float> LuFactorization<T> {
+    /// Returns the permutation matrix `P`.
+    pub fn p(&self) -> Tensor<T> {
+        let perm = &self.perm;
+        Tensor::from_fn(vec![self.n, self.n], |index| {
+            if perm[index[0]] == index[1] {
+                T::one()
+            } else {
+                T::zero()
+            }
+        })
+    }
+
+    /// Returns the unit lower triangular factor `L`.
+    pub fn l(&self) -> Tensor<T> {
+        let (n, lu) = (self.n, &self.lu);
+        Tensor::from_fn(vec![n, n], |index| match index[0].cmp(&index[1]) {
+            Ordering::Greater => lu[index[0] * n + index[1]],
+            Ordering::Equal => T::one(),
+            Ordering::Less => T::zero(),
+        })
+    }
+
+    /// Returns the upper triangular factor `U`.
+    pub fn u(&self) -> Tensor<T> {
+        let (n, lu) = (self.n, &self.lu);
+        Tensor::from_fn(vec![n, n], |index| {
+            if index[0] <= index[1] {
+                lu[index[0] * n + index[1]]
+            } else {
+                T::zero()
+            }
+        })
+    }
+
+    /// Returns the row permutation: row `i` of `P A` is row `permutation()[i]` of `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Returns whether the factored matrix is singular, i.e. `U` has a zero on
+    /// its diagonal.
+    pub fn is_singular(&self) -> bool {
+        self.singular
+    }
+
+    /// Returns the determinant of the factored matrix.
+    pub fn det(&self) -> T {
+        (0..self.n).fold(self.sign, |acc, k| acc * self.lu[k * self.n + k])
+    }
+
+    /// Solves `A x = b` for a right-hand side of shape `[n]` or `[n, k]`; the
+    /// solution has the shape of `b`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `b` does not have `n` rows and
+    /// `TensorError::SingularMatrix` if the factored matrix is singular.
+    pub fn solve(&self, b: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let (mut x, k) = right_hand_side(b, self.n)?;
+        self.check_invertible("cannot solve a system with a singular matrix")?;
+        lu_solve(&self.lu, &self.perm, self.n, &mut x, k);
+        Tensor::new(x, b.shape().to_vec())
+    }
+
+    /// Returns the inverse of the factored matrix.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::SingularMatrix` if the factored matrix is singular.
+    pub fn inverse(&self) -> Result<Tensor<T>, TensorError> {
+        self.check_invertible("matrix is singular and cannot be inverted")?;
+        let n = self.n;
+        let mut inverse = vec![T::zero(); n * n];
+        for i in 0..n {
+            inverse[i * n + i] = T::one();
+        }
+        lu_solve(&self.lu, &self.perm, n, &mut inverse, n);
+        Tensor::new(inverse, vec![n, n])
+    }
+
+    fn check_invertible(&self, message: &str) -> Result<(), TensorError> {
+        if self.singular {
+            Err(TensorError::SingularMatrix(message.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Solves the square linear system `a x = b`.
+///
+/// `b` is either a vector of shape `[n]` or a matrix of shape `[n, k]` whose
+/// columns are solved for at once, and the solution has the shape of `b`. The
+/// system is solved by LU factorization with partial pivoting; use [`lu`]
+/// directly to solve several systems with the same matrix.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not square or `b` does not have
+/// `n` rows, and `TensorError::SingularMatrix` if `a` is singular.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::solve;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![3.0, 1.0, 1.0, 2.0], vec![2, 2]).unwrap();
+/// let b = Tensor::new(vec![9.0, 8.0], vec![2]).unwrap();
+/// let x = solve(&a, &b).unwrap();
+/// assert!(x.max_abs_diff(&Tensor::new(vec![2.0, 3.0], vec![2]).unwrap()).unwrap() < 1e-12);
+/// ```
+pub fn solve<T: Float>(a: &Tensor<T>, b: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    lu(a)?.solve(b)
+}
+
+/// Computes the Cholesky factorization `A = L L^T` of a symmetric positive
+/// definite matrix, returning the lower triangular factor `L`, whose diagonal
+/// is positive.
+///
+/// Only the lower triangle of `a` is read; the upper triangle is assumed to
+/// mirror it.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not a square 2D matrix and
+/// `TensorError::NotPositiveDefinite` if it is not positive definite.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::cholesky;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![4.0, 2.0, 2.0, 5.0], vec![2, 2]).unwrap();
+/// let l = cholesky(&a).unwrap();
+/// assert_eq!(l.as_slice(), Some(&[2.0, 0.0, 1.0, 2.0][..]));
+/// ```
+pub fn cholesky<T: Float>(a: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let n = square_order(a, "cholesky")?;
+    let mut l: Vec<T> = a.iter().copied().collect();
+    for j in 0..n {
+        let pivot = (0..j).fold(l[j * n + j], |acc, k| acc - l[j * n + k] * l[j * n + k]);
+        // Also rejects a NaN pivot.
+        if !matches!(pivot.partial_cmp(&T::zero()), Some(Ordering::Greater)) {
+            return Err(TensorError::NotPositiveDefinite(format!(
+                "leading minor of order {} is not positive definite",
+                j + 1
+            )));
+        }
+        let diagonal = pivot.sqrt();
+        l[j * n + j] = diagonal;
+        for i in j + 1..n {
+            let sum = (0..j).fold(l[i * n + j], |acc, k| acc - l[i * n + k] * l[j * n + k]);
+            l[i * n + j] = sum / diagonal;
+        }
+        for k in j + 1..n {
+            l[j * n + k] = T::zero();
+        }
+    }
+    Tensor::new(l, vec![n, n])
+}
+
+/// The shape of the factors returned by [`qr`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QrMode {
+    /// `Q` is `[m, k]` with orthonormal columns and `R` is `[k, n]`, where
+    /// `k = min(m, n)`.
+    Reduced,
+    /// `Q` is an orthogonal `[m, m]` matrix and `R` is `[m, n]`.
+    Full,
+}
+
+/// Computes the QR decomposition `A = Q R` of a 2D matrix with Householder
+/// reflections.
+///
+/// `R` is upper triangular (trapezoidal when `A` is not square); its diagonal
+/// entries may be negative. `mode` selects between the reduced and full
+/// factors.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not 2D.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::{QrMode, qr};
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![3.0f64, 1.0, 4.0, 2.0, 0.0, 5.0], vec![3, 2]).unwrap();
+/// let (q, r) = qr(&a, QrMode::Reduced).unwrap();
+/// assert_eq!((q.shape(), r.shape()), (&[3, 2][..], &[2, 2][..]));
+/// assert!((r.get(&[0, 0]).unwrap().abs() - 5.0).abs() < 1e-12);
+///
+/// let (q, r) = qr(&a, QrMode::Full).unwrap();
+/// assert_eq!((q.shape(), r.shape()), (&[3, 3][..], &[3, 2][..]));
+/// ```
+pub fn qr<T: Float>(a: &Tensor<T>, mode: QrMode) -> Result<(Tensor<T>, Tensor<T>), TensorError> {
+    let (m, n) = match a.shape() {
+        [m, n] => (*m, *n),
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "qr needs a 2D matrix, got shape {:?}",
+                shape
+            )));
+        }
+    };
+    let mut r: Vec<T> = a.iter().copied().collect();
+    let reflectors = householder(&mut r, m, n);
+
+    // Q = H_0 H_1 ... applied to the leading columns of the identity.
+    let columns = match mode {
+        QrMode::Reduced => m.min(n),
+        QrMode::Full => m,
+    };
+    let mut q = vec![T::zero(); m * columns];
+    for i in 0..columns {
+        q[i * columns + i] = T::one();
+    }
+    for reflector in reflectors.iter().rev() {
+        reflector.apply(&mut q, columns, 0..columns);
+    }
+
+    r.truncate(columns * n);
+    Ok((
+        Tensor::new(q, vec![m, columns])?,
+        Tensor::new(r, vec![columns, n])?,
+    ))
+}
+
+/// Returns the least-squares solution of the over-determined system `a x = b`,
+/// the `x` minimizing `||a x - b||` for each column of `b`.
+///
+/// `a` has shape `[m, n]` with `m >= n`, and `b` has shape `[m]` or `[m, k]`;
+/// the solution has shape `[n]` or `[n, k]`. The system is reduced with
+/// Householder reflections (a QR factorization), which avoids squaring the
+/// condition number as the normal equations would.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not 2D with at least as many
+/// rows as columns or `b` does not have `m` rows, and
+/// `TensorError::SingularMatrix` if the columns of `a` are linearly dependent.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::lstsq;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// // Fit y = c0 + c1 * t through (0, 1), (1, 3) and (2, 5).
+/// let a = Tensor::new(vec![1.0, 0.0, 1.0, 1.0, 1.0, 2.0], vec![3, 2]).unwrap();
+/// let y = Tensor::new(vec![1.0, 3.0, 5.0], vec![3]).unwrap();
+/// let c = lstsq(&a, &y).unwrap();
+/// assert!(c.max_abs_diff(&Tensor::new(vec![1.0, 2.0], vec![2]).unwrap()).unwrap() < 1e-12);
+/// ```
+pub fn lstsq<T: Float>(a: &Tensor<T>, b: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+    let (m, n) = match a.shape() {
+        [m, n] if m >= n => (*m, *n),
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "lstsq needs a 2D matrix with at least as many rows as columns, got {:?}",
+                shape
+            )));
+        }
+    };
+    let (mut rhs, k) = right_hand_side(b, m)?;
+    let mut r: Vec<T> = a.iter().copied().collect();
+
+    // Reduce `a` to upper triangular form, applying the same reflections to `b`.
+    for reflector in householder(&mut r, m, n) {
+        reflector.apply(&mut rhs, k, 0..k);
+    }
+
+    let largest = (0..n).fold(T::zero(), |acc, i| {
+        let d = r[i * n + i].abs();
+        if d > acc { d } else { acc }
+    });
+    let tolerance = machine_epsilon::<T>() * T::from_usize(m) * largest;
+    if (0..n).any(|i| r[i * n + i].abs() <= tolerance) {
+        return Err(TensorError::SingularMatrix(
+            "lstsq needs linearly independent columns".to_string(),
+        ));
+    }
+
+    // Back-substitute R x = (Q^T b)[..n].
+    let mut x = vec![T::zero(); n * k];
+    for i in (0..n).rev() {
+        for c in 0..k {
+            let tail = (i + 1..n).fold(T::zero(), |acc, p| acc + r[i * n + p] * x[p * k + c]);
+            x[i * k + c] = (rhs[i * k + c] - tail) / r[i * n + i];
+        }
+    }
+    let shape = if b.ndim() == 1 { vec![n] } else { vec![n, k] };
+    Tensor::new(x, shape)
+}
+
+/// Sweep cap for the Jacobi iterations behind [`svd`] and [`eigh`].
+const JACOBI_MAX_SWEEPS: usize = 100;
+
+/// Returns the dot product of two equally long vectors.
+fn dot<T: Float>(a: &[T], b: &[T]) -> T {
+    a.iter().zip(b).fold(T::zero(), |acc, (&x, &y)| acc + x * y)
+}
+
+/// Extends the orthonormal vectors of length `len` in `basis` to `count`
+/// vectors, each time adding the standard basis vector with the largest
+/// component orthogonal to the current basis.
+fn complete_basis<T: Float>(basis: &mut Vec<Vec<T>>, len: usize, count: usize) {
+    while basis.len() < count {
+        let best = (0..len)
+            .map(|e| {
+                let mut v = vec![T::zero(); len];
+                v[e] = T::one();
+                // Orthogonalize twice to keep the result orthogonal to working precision.
+                for _ in 0..2 {
+                    for b in basis.iter() {
+                        let d = dot(b, &v);
+                        v.iter_mut().zip(b).for_each(|(x, &y)| *x = *x - d * y);
+                    }
+                }
+                let norm = dot(&v, &v).sqrt();
+                (v, norm)
+            })
+            .reduce(|best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+        match best {
+            Some((v, norm)) => basis.push(v.into_iter().map(|x| x / norm).collect()),
+            None => return,
+        }
+    }
+}
+
+/// The factors `(U, S, Vt)` of a singular value decomposition, as returned by
+/// [`svd`].
+pub type Svd<T> = (Tensor<T>, Tensor<T>, Tensor<T>);
+
+/// The shape of the factors returned by [`svd`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SvdMode {
+    /// `U` is `[m, k]`, `S` is `[k]` and `Vt` is `[k, n]`, where
+    /// `k = min(m, n)`.
+    Thin,
+    /// `U` is an orthogonal `[m, m]` matrix and `Vt` an orthogonal `[n, n]`
+    /// matrix; `S` still has `min(m, n)` entries.
+    Full,
+}
+
+/// Computes the singular value decomposition `A = U diag(S) Vt` of a 2D
+/// matrix.
+///
+/// Singular values are non-negative and in decreasing order. Uses one-sided
+/// Jacobi rotations, which orthogonalize the columns of `A` (of its transpose
+/// when it is wide) and give singular values to high relative accuracy at
+/// `O(m n^2)` cost per sweep. Singular vectors of zero singular values are
+/// completed to an orthonormal basis.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not 2D, and
+/// `TensorError::Cancelled` if the current thread's
+/// [`CancellationToken`](crate::cancel::CancellationToken) is cancelled
+/// between sweeps.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::{SvdMode, svd};
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![3.0f64, 0.0, 0.0, -4.0, 0.0, 0.0], vec![3, 2]).unwrap();
+/// let (u, s, vt) = svd(&a, SvdMode::Thin).unwrap();
+/// assert_eq!((u.shape(), vt.shape()), (&[3, 2][..], &[2, 2][..]));
+/// let s = s.as_slice().unwrap();
+/// assert!((s[0] - 4.0).abs() < 1e-12 && (s[1] - 3.0).abs() < 1e-12);
+///
+/// let (u, _, _) = svd(&a, SvdMode::Full).unwrap();
+/// assert_eq!(u.shape(), &[3, 3]);
+/// ```
+pub fn svd<T: Float>(a: &Tensor<T>, mode: SvdMode) -> Result<Svd<T>, TensorError> {
+    let (m, n) = match a.shape() {
+        [m, n] => (*m, *n),
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "svd needs a 2D matrix, got shape {:?}",
+                shape
+            )));
+        }
+    };
+    // Orthogonalize the shorter side: for a wide matrix, decompose its
+    // transpose A^T = W S V^T and swap the roles of the factors.
+    let transposed = m < n;
+    let (rows, cols) = (m.max(n), m.min(n));
+    let data: Vec<T> = a.iter().copied().collect();
+    let mut w: Vec<Vec<T>> = (0..cols)
+        .map(|j| {
+            (0..rows)
+                .map(|i| {
+                    if transposed {
+                        data[j * n + i]
+                    } else {
+                        data[i * n + j]
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let mut v: Vec<Vec<T>> = (0..cols)
+        .map(|j| {
+            (0..cols)
+                .map(|i| if i == j { T::one() } else { T::zero() })
+                .collect()
+        })
+        .collect();
+
+    let eps = machine_epsilon::<T>();
+    for _ in 0..JACOBI_MAX_SWEEPS {
+        cancel::check("svd")?;
+        let mut rotated = false;
+        for p in 0..cols {
+            for q in p + 1..cols {
+                let alpha = dot(&w[p], &w[p]);
+                let beta = dot(&w[q], &w[q]);
+                let gamma = dot(&w[p], &w[q]);
+                if gamma.abs() <= eps * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                // The rotation that makes columns p and q orthogonal.
+                let zeta = (beta - alpha) / (T::from_usize(2) * gamma);
+                let t = T::one() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+                let t = if zeta < T::zero() { -t } else { t };
+                let c = T::one() / (T::one() + t * t).sqrt();
+                let s = c * t;
+                for columns in [&mut w, &mut v] {
+                    let (head, tail) = columns.split_at_mut(q);
+                    for (x, y) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                        (*x, *y) = (c * *x - s * *y, s * *x + c * *y);
+                    }
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let norms: Vec<T> = w.iter().map(|column| dot(column, column).sqrt()).collect();
+    let mut order: Vec<usize> = (0..cols).collect();
+    order.sort_by(|&i, &j| total_cmp(&norms[j], &norms[i]));
+    let singular: Vec<T> = order.iter().map(|&j| norms[j]).collect();
+    let largest = singular.first().copied().unwrap_or_else(T::zero);
+    let tolerance = eps * T::from_usize(rows) * largest;
+
+    let mut left: Vec<Vec<T>> = order
+        .iter()
+        .take_while(|&&j| norms[j] > tolerance)
+        .map(|&j| w[j].iter().map(|&x| x / norms[j]).collect())
+        .collect();
+    let right: Vec<Vec<T>> = order.iter().map(|&j| v[j].clone()).collect();
+
+    let count = match mode {
+        SvdMode::Thin => cols,
+        SvdMode::Full => rows,
+    };
+    complete_basis(&mut left, rows, count);
+    let (u_columns, vt_rows) = if transposed {
+        (right, left)
+    } else {
+        (left, right)
+    };
+
+    let (k_u, k_v) = (u_columns.len(), vt_rows.len());
+    let u = Tensor::from_fn(vec![m, k_u], |index| u_columns[index[1]][index[0]]);
+    let vt = Tensor::new(vt_rows.concat(), vec![k_v, n])?;
+    Ok((u, Tensor::new(singular, vec![cols])?, vt))
+}
+
+/// Computes the eigenvalues and eigenvectors of a symmetric matrix.
+///
+/// Returns the eigenvalues in increasing order and a matrix whose orthonormal
+/// columns are the matching eigenvectors, so that `A V = V diag(w)`. Only the
+/// lower triangle of `a` is read; the upper triangle is assumed to mirror it.
+/// Uses cyclic Jacobi rotations, which converge quadratically and give
+/// accurate eigenvalues for the small and medium matrices this crate targets.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not a square 2D matrix, and
+/// `TensorError::Cancelled` if the current thread's
+/// [`CancellationToken`](crate::cancel::CancellationToken) is cancelled
+/// between sweeps.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::eigh;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![2.0f64, 1.0, 1.0, 2.0], vec![2, 2]).unwrap();
+/// let (w, v) = eigh(&a).unwrap();
+/// let w = w.as_slice().unwrap();
+/// assert!((w[0] - 1.0).abs() < 1e-12 && (w[1] - 3.0).abs() < 1e-12);
+/// // The eigenvector of 3 is (1, 1) / sqrt(2), up to sign.
+/// assert!((v.get(&[0, 1]).unwrap().abs() - 0.5f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn eigh<T: Float>(a: &Tensor<T>) -> Result<(Tensor<T>, Tensor<T>), TensorError> {
+    let n = square_order(a, "eigh")?;
+    let data: Vec<T> = a.iter().copied().collect();
+    let mut m: Vec<T> = (0..n * n)
+        .map(|index| {
+            let (i, j) = (index / n, index % n);
+            data[i.max(j) * n + i.min(j)]
+        })
+        .collect();
+    let mut v = vec![T::zero(); n * n];
+    for i in 0..n {
+        v[i * n + i] = T::one();
+    }
+
+    let eps = machine_epsilon::<T>();
+    let total = dot(&m, &m);
+    for _ in 0..JACOBI_MAX_SWEEPS {
+        let off = (0..n).fold(T::zero(), |acc, i| {
+            (0..n)
+                .filter(|&j| j != i)
+                .fold(acc, |acc, j| acc + m[i * n + j] * m[i * n + j])
+        });
+        if off <= eps * eps * total {
+            break;
+        }
+        cancel::check("eigh")?;
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = m[p * n + q];
+                if apq == T::zero() {
+                    continue;
+                }
+                // The rotation that zeroes the (p, q) entry.
+                let theta = (m[q * n + q] - m[p * n + p]) / (T::from_usize(2) * apq);
+                let t = T::one() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                let t = if theta < T::zero() { -t } else { t };
+                let c = T::one() / (t * t + T::one()).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (m[k * n + p], m[k * n + q]);
+                    m[k * n + p] = c * akp - s * akq;
+                    m[k * n + q] = s * akp + c * akq;
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (m[p * n + k], m[q * n + k]);
+                    m[p * n + k] = c * apk - s * aqk;
+                    m[q * n + k] = s * apk + c * aqk;
+                }
+                m[p * n + q] = T::zero();
+                m[q * n + p] = T::zero();
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| total_cmp(&m[i * n + i], &m[j * n + j]));
+    let values: Vec<T> = order.iter().map(|&i| m[i * n + i]).collect();
+    let vectors = Tensor::from_fn(vec![n, n], |index| v[index[0] * n + order[index[1]]]);
+    Ok((Tensor::new(values, vec![n])?, vectors))
+}
+
+/// Extra sketch columns [`rsvd`] draws beyond the requested rank, which make
+/// the captured subspace accurate with high probability.
+#[cfg(feature = "rand")]
+const RSVD_OVERSAMPLES: usize = 10;
+
+/// Returns the dimensions of a 2D matrix, or a `ShapeError` naming `what`.
+#[cfg(feature = "rand")]
+fn matrix_dims<T>(a: &Tensor<T>, what: &str) -> Result<(usize, usize), TensorError> {
+    match a.shape() {
+        [m, n] => Ok((*m, *n)),
+        shape => Err(TensorError::ShapeError(format!(
+            "{} needs a 2D matrix, got shape {:?}",
+            what, shape
+        ))),
+    }
+}
+
+/// Multiplies the row-major matrices `a` (`[m, k]`) and `b` (`[k, n]`).
+#[cfg(feature = "rand")]
+fn multiply<T: Float>(a: &[T], b: &[T], m: usize, k: usize, n: usize) -> Vec<T> {
+    let mut c = vec![T::zero(); m * n];
+    for i in 0..m {
+        for p in 0..k {
+            let x = a[i * k + p];
+            for j in 0..n {
+                c[i * n + j] = c[i * n + j] + x * b[p * n + j];
+            }
+        }
+    }
+    c
+}
+
+/// Returns the transpose of the row-major `[m, n]` matrix `a`.
+#[cfg(feature = "rand")]
+fn transpose<T: Copy>(a: &[T], m: usize, n: usize) -> Vec<T> {
+    (0..m * n)
+        .map(|index| a[(index % m) * n + index / m])
+        .collect()
+}
+
+/// Returns an approximation of the matrix product `a b` through a Gaussian
+/// sketch of the shared dimension, available with the `rand` feature.
+///
+/// `a` is `[m, k]` and `b` is `[k, n]`. Both are compressed to `rank` along
+/// `k` by the same random matrix `S` with independent `N(0, 1 / rank)`
+/// entries, and the result is `(a S^T) (S b)`. It is unbiased, its error
+/// shrinks like `1 / sqrt(rank)` relative to `|a| |b|`, and it costs
+/// `O((m + n) k rank + m n rank)` instead of `O(m k n)`, which pays off when
+/// `k` is much larger than `rank`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` or `b` is not 2D or their inner
+/// dimensions differ, and `TensorError::ValueError` if `rank` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::sketched_matmul;
+/// use tiny_tensor::random::seeded_rng;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![1.0f64, 2.0, 3.0, 4.0], vec![1, 4]).unwrap();
+/// let b = Tensor::new(vec![1.0f64; 4], vec![4, 1]).unwrap();
+/// let product = sketched_matmul(&a, &b, 4000, &mut seeded_rng(0)).unwrap();
+/// assert!((product.get(&[0, 0]).unwrap() - 10.0).abs() < 1.5);
+/// ```
+#[cfg(feature = "rand")]
+pub fn sketched_matmul<T, R>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    rank: usize,
+    rng: &mut R,
+) -> Result<Tensor<T>, TensorError>
+where
+    T: Float,
+    R: rand::Rng + ?Sized,
+{
+    let (m, k) = matrix_dims(a, "sketched_matmul")?;
+    let (k_b, n) = matrix_dims(b, "sketched_matmul")?;
+    if k != k_b {
+        return Err(TensorError::ShapeError(format!(
+            "cannot multiply matrices of shapes {:?} and {:?}",
+            a.shape(),
+            b.shape()
+        )));
+    }
+    if rank == 0 {
+        return Err(TensorError::ValueError(
+            "sketched_matmul needs a rank of at least 1".to_string(),
+        ));
+    }
+
+    let scale = T::one() / T::from_usize(rank).sqrt();
+    let sketch = crate::random::rand_normal(&[rank, k], T::zero(), scale, rng)?;
+    let sketch: Vec<T> = sketch.iter().copied().collect();
+    let a: Vec<T> = a.iter().copied().collect();
+    let b: Vec<T> = b.iter().copied().collect();
+
+    let left = multiply(&a, &transpose(&sketch, rank, k), m, k, rank);
+    let right = multiply(&sketch, &b, rank, k, n);
+    Tensor::new(multiply(&left, &right, m, rank, n), vec![m, n])
+}
+
+/// Computes a rank-`rank` truncated singular value decomposition with a
+/// randomized range finder, available with the `rand` feature.
+///
+/// The range of `a` is sampled with a Gaussian sketch of `rank` plus a few
+/// oversampling columns, refined by `power_iterations` rounds of
+/// multiplication by `a a^T` (each sharpens the decay of the spectrum, which
+/// helps when it is flat), and orthonormalized with [`qr`]. The exact [`svd`]
+/// of the small projected matrix then gives the factors. The result has the
+/// shapes of [`SvdMode::Thin`] truncated to `rank`: `U` is `[m, rank]`, `S` is
+/// `[rank]` and `Vt` is `[rank, n]`. It is exact when `a` has rank at most
+/// `rank`, and close to the best rank-`rank` approximation otherwise.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `a` is not 2D, and
+/// `TensorError::ValueError` if `rank` is zero or exceeds `min(m, n)`.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::linalg::rsvd;
+/// use tiny_tensor::random::seeded_rng;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// // A rank-one matrix is recovered exactly.
+/// let a = Tensor::from_fn(vec![6, 4], |i| ((i[0] + 1) * (i[1] + 1)) as f64);
+/// let (u, s, vt) = rsvd(&a, 1, 1, &mut seeded_rng(3)).unwrap();
+/// assert_eq!((u.shape(), s.shape(), vt.shape()), (&[6, 1][..], &[1][..], &[1, 4][..]));
+/// assert!((s.as_slice().unwrap()[0] - (91.0f64 * 30.0).sqrt()).abs() < 1e-9);
+/// ```
+#[cfg(feature = "rand")]
+pub fn rsvd<T, R>(
+    a: &Tensor<T>,
+    rank: usize,
+    power_iterations: usize,
+    rng: &mut R,
+) -> Result<Svd<T>, TensorError>
+where
+    T: Float,
+    R: rand::Rng + ?Sized,
+{
+    let (m, n) = matrix_dims(a, "rsvd")?;
+    if rank == 0 || rank > m.min(n) {
+        return Err(TensorError::ValueError(format!(
+            "rsvd rank must be between 1 and {}, got {}",
+            m.min(n),
+            rank
+        )));
+    }
+
+    let samples = (rank + RSVD_OVERSAMPLES).min(m).min(n);
+    let data: Vec<T> = a.iter().copied().collect();
+    let data_t = transpose(&data, m, n);
+    let orthonormal = |y: Vec<T>, rows: usize| -> Result<Vec<T>, TensorError> {
+        let (q, _) = qr(&Tensor::new(y, vec![rows, samples])?, QrMode::Reduced)?;
+        Ok(q.iter().copied().collect())
+    };
+
+    let omega = crate::random::rand_normal(&[n, samples], T::zero(), T::one(), rng)?;
+    let omega: Vec<T> = omega.iter().copied().collect();
+    let mut q = orthonormal(multiply(&data, &omega, m, n, samples), m)?;
+    for _ in 0..power_iterations {
+        // Re-orthonormalizing after each product keeps the small singular
+        // directions from being lost to rounding.
+        let z = orthonormal(multiply(&data_t, &q, n, m, samples), n)?;
+        q = orthonormal(multiply(&data, &z, m, n, samples), m)?;
+    }
+
+    // B = Q^T A is small enough for the exact decomposition.
+    let projected = multiply(&transpose(&q, m, samples), &data, samples, m, n);
+    let (u_small, s, vt) = svd(&Tensor::new(projected, vec![samples, n])?, SvdMode::Thin)?;
+    let u_small: Vec<T> = u_small.iter().copied().collect();
+    let u = multiply(&q, &u_small, m, samples, samples);
+
+    let u = Tensor::from_fn(vec![m, rank], |index| u[index[0] * samples + index[1]]);
+    let s = Tensor::new(s.iter().take(rank).copied().collect(), vec![rank])?;
+    let vt = Tensor::new(vt.iter().take(rank * n).copied().collect(), vec![rank, n])?;
+    Ok((u, s, vt))
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns the determinant of a square matrix, computed from its LU
+    /// factorization with partial pivoting. Singular matrices have a
+    /// determinant of zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` is not a square 2D matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let a = Tensor::new(vec![1.0f64, 2.0, 3.0, 4.0], vec![2, 2]).unwrap();
+    /// assert!((a.det().unwrap() + 2.0).abs() < 1e-12);
+    /// ```
+    pub fn det(&self) -> Result<T, TensorError> {
+        Ok(lu(self)?.det())
+    }
+
+    /// Returns the inverse of a square matrix, computed from its LU
+    /// factorization with partial pivoting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` is not a square 2D matrix
+    /// and `TensorError::SingularMatrix` if it has no inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let a = Tensor::new(vec![4.0, 7.0, 2.0, 6.0], vec![2, 2]).unwrap();
+    /// let inverse = a.inv().unwrap();
+    /// let expected = Tensor::new(vec![0.6, -0.7, -0.2, 0.4], vec![2, 2]).unwrap();
+    /// assert!(inverse.max_abs_diff(&expected).unwrap() < 1e-12);
+    /// ```
+    pub fn inv(&self) -> Result<Tensor<T>, TensorError> {
+        lu(self)?.inverse()
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Tensor<T> {
+    /// Returns the Kronecker product of two 2D tensors.
+    ///
+    /// For `self` of shape `[m, n]` and `other` of shape `[p, q]`, the result
+    /// has shape `[m p, n q]` and is made of the blocks `self[i, j] * other`:
+    /// element `[i p + k, j q + l]` is `self[i, j] * other[k, l]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if either tensor is not 2D.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let a = Tensor::new(vec![1, 2], vec![1, 2]).unwrap();
+    /// let b = Tensor::new(vec![1, 0, 0, 1], vec![2, 2]).unwrap();
+    /// let k = a.kron(&b).unwrap();
+    /// assert_eq!(k.shape(), &[2, 4]);
+    /// assert_eq!(k.as_slice(), Some(&[1, 0, 2, 0, 0, 1, 0, 2][..]));
+    /// ```
+    pub fn kron(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        let ((m, n), (p, q)) = match (self.shape(), other.shape()) {
+            ([m, n], [p, q]) => ((*m, *n), (*p, *q)),
+            (a, b) => {
+                return Err(TensorError::ShapeError(format!(
+                    "kron needs two 2D tensors, got shapes {:?} and {:?}",
+                    a, b
+                )));
+            }
+        };
+        let a: Vec<T> = self.iter().copied().collect();
+        let b: Vec<T> = other.iter().copied().collect();
+        Ok(Tensor::from_fn(vec![m * p, n * q], |index| {
+            let (i, k) = (index[0] / p, index[0] % p);
+            let (j, l) = (index[1] / q, index[1] % q);
+            a[i * n + j] * b[k * q + l]
+        }))
+    }
+
+    /// Returns the outer product of two 1D tensors: for lengths `m` and `n`,
+    /// the `[m, n]` matrix with element `[i, j]` equal to `self[i] * other[j]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if either tensor is not 1D.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let x = Tensor::new(vec![1, 2], vec![2]).unwrap();
+    /// let y = Tensor::new(vec![3, 4, 5], vec![3]).unwrap();
+    /// let o = x.outer(&y).unwrap();
+    /// assert_eq!(o.shape(), &[2, 3]);
+    /// assert_eq!(o.as_slice(), Some(&[3, 4, 5, 6, 8, 10][..]));
+    /// ```
+    pub fn outer(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        match (self.shape(), other.shape()) {
+            ([m], [n]) => {
+                let a: Vec<T> = self.iter().copied().collect();
+                let b: Vec<T> = other.iter().copied().collect();
+                Ok(Tensor::from_fn(vec![*m, *n], |i| a[i[0]] * b[i[1]]))
+            }
+            (a, b) => Err(TensorError::ShapeError(format!(
+                "outer needs two 1D tensors, got shapes {:?} and {:?}",
+                a, b
+            ))),
+        }
+    }
+
+    /// Returns the outer products of matching rows of two 2D tensors: for
+    /// shapes `[batch, m]` and `[batch, n]`, the `[batch, m, n]` tensor whose
+    /// `b`-th matrix is the outer product of row `b` of `self` and of `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if either tensor is not 2D or their
+    /// batch sizes differ.
+    pub fn outer_batched(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        match (self.shape(), other.shape()) {
+            ([batch, m], [other_batch, n]) if batch == other_batch => {
+                let (m, n) = (*m, *n);
+                let a: Vec<T> = self.iter().copied().collect();
+                let b: Vec<T> = other.iter().copied().collect();
+                Ok(Tensor::from_fn(vec![*batch, m, n], |i| {
+                    a[i[0] * m + i[1]] * b[i[0] * n + i[2]]
+                }))
+            }
+            (a, b) => Err(TensorError::ShapeError(format!(
+                "outer_batched needs [batch, m] and [batch, n] tensors, got shapes {:?} and {:?}",
+                a, b
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::creation::{eye, zeros};
+
+    /// Returns the product of an `[m, k]` and a `[k, n]` matrix.
+    fn matmul(a: &Tensor<f64>, b: &Tensor<f64>) -> Tensor<f64> {
+        let (m, k, n) = (a.shape()[0], a.shape()[1], b.shape()[1]);
+        Tensor::from_fn(vec![m, n], |index| {
+            (0..k)
+                .map(|k| a.get(&[index[0], k]).unwrap() * b.get(&[k, index[1]]).unwrap())
+                .sum()
+        })
+    }
+
+    #[test]
+    fn test_inv_and_det_need_pivoting() {
+        // The zero in the top-left corner forces a row exchange.
+        let a = Tensor::new(
+            vec![0.0, 2.0, 1.0, 1.0, 1.0, 0.0, 3.0, 0.0, 1.0],
+            vec![3, 3],
+        )
+        .unwrap();
+
+        assert!((a.det().unwrap() + 5.0).abs() < 1e-12);
+        let identity = Tensor::from_fn(vec![3, 3], |i| if i[0] == i[1] { 1.0 } else { 0.0 });
+        assert!(
+            matmul(&a, &a.inv().unwrap())
+                .max_abs_diff(&identity)
+                .unwrap()
+                < 1e-12
+        );
+        assert!(
+            matmul(
+                &a.permute(&[1, 0]).unwrap(),
+                &a.permute(&[1, 0]).unwrap().inv().unwrap()
+            )
+            .max_abs_diff(&identity)
+            .unwrap()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_singular_and_invalid_matrices() {
+        let singular = Tensor::new(vec![1.0, 2.0, 2.0, 4.0], vec![2, 2]).unwrap();
+        assert_eq!(singular.det().unwrap(), 0.0);
+        assert!(matches!(
+            singular.inv(),
+            Err(TensorError::SingularMatrix(_))
+        ));
+
+        let rectangular = Tensor::new(vec![1.0f32; 6], vec![2, 3]).unwrap();
+        assert!(matches!(rectangular.det(), Err(TensorError::ShapeError(_))));
+        assert!(matches!(rectangular.inv(), Err(TensorError::ShapeError(_))));
+
+        let empty = Tensor::<f64>::new(vec![], vec![0, 0]).unwrap();
+        assert_eq!(empty.det().unwrap(), 1.0);
+        assert_eq!(empty.inv().unwrap().shape(), &[0, 0]);
+    }
+
+    #[test]
+    fn test_solve_vector_and_matrix_right_hand_sides() {
+        let a = Tensor::new(
+            vec![0.0, 2.0, 1.0, 1.0, 1.0, 0.0, 3.0, 0.0, 1.0],
+            vec![3, 3],
+        )
+        .unwrap();
+        let x = Tensor::new(vec![1.0, -2.0, 3.0, 0.5, 4.0, -1.0], vec![3, 2]).unwrap();
+        let b = Tensor::from_fn(vec![3, 2], |i| {
+            (0..3)
+                .map(|k| a.get(&[i[0], k]).unwrap() * x.get(&[k, i[1]]).unwrap())
+                .sum()
+        });
+
+        assert!(solve(&a, &b).unwrap().max_abs_diff(&x).unwrap() < 1e-12);
+        let column = Tensor::new(vec![b.data[0], b.data[2], b.data[4]], vec![3]).unwrap();
+        let solved = solve(&a, &column).unwrap();
+        assert_eq!(solved.shape(), &[3]);
+        assert!((solved.data[1] - 3.0).abs() < 1e-12);
+
+        let singular = Tensor::new(vec![1.0, 2.0, 2.0, 4.0], vec![2, 2]).unwrap();
+        let rhs = Tensor::new(vec![1.0, 2.0], vec![2]).unwrap();
+        assert!(matches!(
+            solve(&singular, &rhs),
+            Err(TensorError::SingularMatrix(_))
+        ));
+        assert!(matches!(solve(&a, &rhs), Err(TensorError::ShapeError(_))));
+    }
+
+    #[test]
+    fn test_lstsq_minimizes_residual() {
+        // Noisy samples of y = 2 - t; the least-squares line through
+        // (0, 2.1), (1, 0.9), (2, 0.1) and (3, -1.1) is 2.06 - 1.04 t.
+        let a = Tensor::new(vec![1.0, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0], vec![4, 2]).unwrap();
+        let y = Tensor::new(vec![2.1, 0.9, 0.1, -1.1, 4.2, 1.8, 0.2, -2.2], vec![2, 4])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+
+        let c = lstsq(&a, &y).unwrap();
+
+        let expected = Tensor::new(vec![2.06, 4.12, -1.04, -2.08], vec![2, 2]).unwrap();
+        assert!(c.max_abs_diff(&expected).unwrap() < 1e-12);
+
+        let dependent = Tensor::new(vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0], vec![3, 2]).unwrap();
+        let rhs = Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap();
+        assert!(matches!(
+            lstsq(&dependent, &rhs),
+            Err(TensorError::SingularMatrix(_))
+        ));
+        assert!(matches!(
+            lstsq(&dependent.permute(&[1, 0]).unwrap(), &rhs),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_lu_factors_reconstruct_matrix() {
+        let a = Tensor::new(
+            vec![0.0, 2.0, 1.0, 1.0, 1.0, 0.0, 3.0, 0.0, 1.0],
+            vec![3, 3],
+        )
+        .unwrap();
+
+        let factors = lu(&a).unwrap();
+
+        assert_eq!(factors.permutation(), &[2, 0, 1]);
+        let pa = matmul(&factors.p(), &a);
+        let product = matmul(&factors.l(), &factors.u());
+        assert!(pa.max_abs_diff(&product).unwrap() < 1e-12);
+        assert!(!factors.is_singular());
+        assert!((factors.det() + 5.0).abs() < 1e-12);
+        assert!(
+            factors
+                .inverse()
+                .unwrap()
+                .max_abs_diff(&a.inv().unwrap())
+                .unwrap()
+                < 1e-15
+        );
+
+        let singular = lu(&Tensor::new(vec![1.0, 2.0, 2.0, 4.0], vec![2, 2]).unwrap()).unwrap();
+        assert!(singular.is_singular());
+        assert!(matches!(
+            singular.inverse(),
+            Err(TensorError::SingularMatrix(_))
+        ));
+        assert!(matches!(
+            singular.solve(&Tensor::new(vec![1.0; 3], vec![3]).unwrap()),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_qr_reconstructs_with_orthonormal_q() {
+        for (m, n) in [(4, 3), (3, 3), (2, 4)] {
+            let a = Tensor::from_fn(vec![m, n], |i| ((i[0] * 5 + i[1] * 3) % 7) as f64 - 2.5);
+            for mode in [QrMode::Reduced, QrMode::Full] {
+                let (q, r) = qr(&a, mode).unwrap();
+
+                let k = q.shape()[1];
+                assert_eq!(k, if mode == QrMode::Full { m } else { m.min(n) });
+                assert_eq!(r.shape(), &[k, n]);
+                assert!(matmul(&q, &r).max_abs_diff(&a).unwrap() < 1e-12);
+                let qtq = matmul(&q.permute(&[1, 0]).unwrap(), &q);
+                assert!(qtq.max_abs_diff(&eye(k)).unwrap() < 1e-12);
+                for i in 0..k {
+                    for j in 0..i.min(n) {
+                        assert_eq!(*r.get(&[i, j]).unwrap(), 0.0);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_qr_rank_deficient_and_invalid() {
+        // The zero column needs no reflection.
+        let a = Tensor::new(vec![0.0, 1.0, 0.0, 1.0, 0.0, 2.0], vec![3, 2]).unwrap();
+        let (q, r) = qr(&a, QrMode::Full).unwrap();
+        assert!(matmul(&q, &r).max_abs_diff(&a).unwrap() < 1e-12);
+        assert_eq!(*r.get(&[0, 0]).unwrap(), 0.0);
+
+        assert!(matches!(
+            qr(
+                &Tensor::new(vec![1.0, 2.0], vec![2]).unwrap(),
+                QrMode::Reduced
+            ),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_svd_reconstructs_with_orthonormal_factors() {
+        for (m, n) in [(4, 3), (3, 3), (2, 5)] {
+            let a = Tensor::from_fn(vec![m, n], |i| ((i[0] * 5 + i[1] * 3) % 7) as f64 - 2.5);
+            for mode in [SvdMode::Thin, SvdMode::Full] {
+                let (u, s, vt) = svd(&a, mode).unwrap();
+
+                let k = m.min(n);
+                let s: Vec<f64> = s.iter().copied().collect();
+                assert_eq!(s.len(), k);
+                assert!(s.windows(2).all(|pair| pair[0] >= pair[1]));
+                let (ku, kv) = match mode {
+                    SvdMode::Thin => (k, k),
+                    SvdMode::Full => (m, n),
+                };
+                assert_eq!((u.shape(), vt.shape()), (&[m, ku][..], &[kv, n][..]));
+                let ut = u.permute(&[1, 0]).unwrap();
+                assert!(matmul(&ut, &u).max_abs_diff(&eye(ku)).unwrap() < 1e-12);
+                let v = vt.permute(&[1, 0]).unwrap();
+                assert!(matmul(&vt, &v).max_abs_diff(&eye(kv)).unwrap() < 1e-12);
+
+                let us = Tensor::from_fn(vec![m, k], |i| u.get(&[i[0], i[1]]).unwrap() * s[i[1]]);
+                let vt_k = Tensor::from_fn(vec![k, n], |i| *vt.get(&[i[0], i[1]]).unwrap());
+                assert!(matmul(&us, &vt_k).max_abs_diff(&a).unwrap() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_svd_rank_deficient_and_invalid() {
+        // An outer product has a single nonzero singular value, |x| |y|.
+        let a = Tensor::from_fn(vec![3, 3], |i| ((i[0] + 1) * (2 * i[1] + 1)) as f64);
+        let (u, s, _) = svd(&a, SvdMode::Thin).unwrap();
+        let s = s.as_slice().unwrap();
+        assert!((s[0] - (14.0f64 * 35.0).sqrt()).abs() < 1e-10);
+        assert!(s[1].abs() < 1e-10 && s[2].abs() < 1e-10);
+        let ut = u.permute(&[1, 0]).unwrap();
+        assert!(matmul(&ut, &u).max_abs_diff(&eye(3)).unwrap() < 1e-12);
+
+        let (u, s, vt) = svd(&zeros::<f64>(&[2, 2]), SvdMode::Full).unwrap();
+        assert_eq!(s.as_slice(), Some(&[0.0, 0.0][..]));
+        assert!(
+            u.max_abs_diff(&eye(2)).unwrap() < 1e-12 && vt.max_abs_diff(&eye(2)).unwrap() < 1e-12
+        );
+
+        assert!(matches!(
+            svd(
+                &Tensor::new(vec![1.0, 2.0], vec![2]).unwrap(),
+                SvdMode::Thin
+            ),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sketched_matmul_approximates_product() {
+        use crate::random::seeded_rng;
+
+        let a = Tensor::from_fn(vec![3, 6], |i| ((i[0] * 4 + i[1]) % 5) as f64 - 2.0);
+        let b = Tensor::from_fn(vec![6, 2], |i| ((i[0] + 3 * i[1]) % 4) as f64 - 1.5);
+        let exact = matmul(&a, &b);
+        let norm = |t: &Tensor<f64>| t.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+        let error = |rank| {
+            let approx = sketched_matmul(&a, &b, rank, &mut seeded_rng(5)).unwrap();
+            assert_eq!(approx.shape(), &[3, 2]);
+            let residual = approx
+                .iter()
+                .zip(exact.iter())
+                .map(|(x, y)| (x - y).powi(2));
+            residual.sum::<f64>().sqrt() / (norm(&a) * norm(&b))
+        };
+        assert!(error(20_000) < 0.03);
+        assert!(error(20_000) < error(10));
+
+        assert!(matches!(
+            sketched_matmul(&a, &a, 4, &mut seeded_rng(0)),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            sketched_matmul(&a, &b, 0, &mut seeded_rng(0)),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_rsvd_recovers_low_rank_matrices() {
+        use crate::random::seeded_rng;
+
+        // A rank-two 12 x 9 matrix: the sum of two outer products.
+        let a = Tensor::from_fn(vec![12, 9], |i| {
+            let (r, c) = (i[0] as f64, i[1] as f64);
+            (r + 1.0) * (c - 4.0) + (r % 3.0 - 1.0) * (c * c - 10.0) / 4.0
+        });
+        let (_, exact, _) = svd(&a, SvdMode::Thin).unwrap();
+        let exact: Vec<f64> = exact.iter().copied().take(2).collect();
+
+        for power_iterations in [0, 2] {
+            let (u, s, vt) = rsvd(&a, 2, power_iterations, &mut seeded_rng(11)).unwrap();
+            assert_eq!((u.shape(), vt.shape()), (&[12, 2][..], &[2, 9][..]));
+            let s: Vec<f64> = s.iter().copied().collect();
+            assert!(s.iter().zip(&exact).all(|(x, y)| (x - y).abs() < 1e-9));
+
+            let ut = u.permute(&[1, 0]).unwrap();
+            assert!(matmul(&ut, &u).max_abs_diff(&eye(2)).unwrap() < 1e-12);
+            let us = Tensor::from_fn(vec![12, 2], |i| u.get(&[i[0], i[1]]).unwrap() * s[i[1]]);
+            assert!(matmul(&us, &vt).max_abs_diff(&a).unwrap() < 1e-9);
+        }
+
+        for rank in [0, 10] {
+            assert!(matches!(
+                rsvd(&a, rank, 0, &mut seeded_rng(0)),
+                Err(TensorError::ValueError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_cholesky_reconstructs_matrix() {
+        // B B^T + I is symmetric positive definite.
+        let b = Tensor::from_fn(vec![4, 4], |i| ((i[0] * 3 + i[1] * 5) % 7) as f64 - 3.0);
+        let bbt = matmul(&b, &b.permute(&[1, 0]).unwrap());
+        let a = Tensor::from_fn(vec![4, 4], |i| {
+            bbt.get(&[i[0], i[1]]).unwrap() + if i[0] == i[1] { 1.0 } else { 0.0 }
+        });
+
+        let l = cholesky(&a).unwrap();
+
+        let lt = l.permute(&[1, 0]).unwrap();
+        assert!(matmul(&l, &lt).max_abs_diff(&a).unwrap() < 1e-10);
+        for i in 0..4 {
+            assert!(*l.get(&[i, i]).unwrap() > 0.0);
+            for j in i + 1..4 {
+                assert_eq!(*l.get(&[i, j]).unwrap(), 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cholesky_rejects_indefinite_matrices() {
+        let indefinite = Tensor::new(vec![1.0, 2.0, 2.0, 1.0], vec![2, 2]).unwrap();
+        assert!(matches!(
+            cholesky(&indefinite),
+            Err(TensorError::NotPositiveDefinite(_))
+        ));
+        let semidefinite = Tensor::new(vec![1.0, 1.0, 1.0, 1.0], vec![2, 2]).unwrap();
+        assert!(matches!(
+            cholesky(&semidefinite),
+            Err(TensorError::NotPositiveDefinite(_))
+        ));
+        assert!(matches!(
+            cholesky(&Tensor::new(vec![f64::NAN], vec![1, 1]).unwrap()),
+            Err(TensorError::NotPositiveDefinite(_))
+        ));
+        assert!(matches!(
+            cholesky(&Tensor::new(vec![1.0, 2.0], vec![1, 2]).unwrap()),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_eigh_diagonalizes_symmetric_matrix() {
+        let b = Tensor::from_fn(vec![5, 5], |i| ((i[0] * 3 + i[1] * 5) % 7) as f64 - 3.0);
+        let a = Tensor::from_fn(vec![5, 5], |i| {
+            b.get(&[i[0], i[1]]).unwrap() + b.get(&[i[1], i[0]]).unwrap()
+        });
+
+        let (w, v) = eigh(&a).unwrap();
+
+        let w: Vec<f64> = w.iter().copied().collect();
+        assert!(w.windows(2).all(|pair| pair[0] <= pair[1]));
+        let vt = v.permute(&[1, 0]).unwrap();
+        assert!(matmul(&vt, &v).max_abs_diff(&eye(5)).unwrap() < 1e-12);
+        let vw = Tensor::from_fn(vec![5, 5], |i| v.get(&[i[0], i[1]]).unwrap() * w[i[1]]);
+        assert!(matmul(&a, &v).max_abs_diff(&vw).unwrap() < 1e-10);
+        let trace: f64 = (0..5).map(|i| a.get(&[i, i]).unwrap()).sum();
+        assert!((w.iter().sum::<f64>() - trace).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_eigh_reads_lower_triangle() {
+        // The upper triangle is ignored, so this is [[2, 0, 0], [0, 3, 4], [0, 4, 3]].
+        let a = Tensor::new(
+            vec![2.0, 9.0, 9.0, 0.0, 3.0, 9.0, 0.0, 4.0, 3.0],
+            vec![3, 3],
+        )
+        .unwrap();
+        let (w, _) = eigh(&a).unwrap();
+        let expected = Tensor::new(vec![-1.0, 2.0, 7.0], vec![3]).unwrap();
+        assert!(w.max_abs_diff(&expected).unwrap() < 1e-12);
+
+        let (w, v) = eigh(&Tensor::<f64>::new(vec![], vec![0, 0]).unwrap()).unwrap();
+        assert_eq!((w.len(), v.shape()), (0, &[0, 0][..]));
+        assert!(matches!(
+            eigh(&Tensor::new(vec![1.0, 2.0], vec![1, 2]).unwrap()),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_jacobi_sweeps_stop_when_cancelled() {
+        use crate::cancel::CancellationToken;
+
+        let a = Tensor::new(vec![2.0, 1.0, 1.0, 3.0], vec![2, 2]).unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        assert!(matches!(
+            token.scoped(|| svd(&a, SvdMode::Thin)),
+            Err(TensorError::Cancelled(_))
+        ));
+        assert!(matches!(
+            token.scoped(|| eigh(&a)),
+            Err(TensorError::Cancelled(_))
+        ));
+        // An uncancelled token lets the sweeps run to completion.
+        let (w, _) = CancellationToken::new().scoped(|| eigh(&a)).unwrap();
+        assert!(w.max_abs_diff(&eigh(&a).unwrap().0).unwrap() < 1e-15);
+    }
+
+    #[test]
+    fn test_kron_builds_2d_laplacian() {
+        // The 2D Laplacian on a 2 x 3 grid is L2 (x) I3 + I2 (x) L3.
+        let laplacian = |n: usize| {
+            Tensor::from_fn(vec![n, n], |i| match i[0].abs_diff(i[1]) {
+                0 => 2.0,
+                1 => -1.0,
+                _ => 0.0,
+            })
+        };
+        let lap = laplacian(2).kron(&eye(3)).unwrap();
+        let lap = lap
+            .zip_with(&eye::<f64>(2).kron(&laplacian(3)).unwrap(), |a, b| a + b)
+            .unwrap();
+
+        assert_eq!(lap.shape(), &[6, 6]);
+        assert_eq!(
+            lap.as_slice().unwrap()[..12],
+            [
+                4.0, -1.0, 0.0, -1.0, 0.0, 0.0, -1.0, 4.0, -1.0, 0.0, -1.0, 0.0
+            ]
+        );
+        assert_eq!(lap.trace().unwrap(), 24.0);
+    }
+
+    #[test]
+    fn test_kron_shapes() {
+        let a = Tensor::from_fn(vec![2, 3], |i| i[0] * 3 + i[1]);
+        let b = Tensor::new(vec![1, 10], vec![2, 1]).unwrap();
+
+        let k = a.kron(&b).unwrap();
+
+        assert_eq!(k.shape(), &[4, 3]);
+        assert_eq!(
+            k.as_slice(),
+            Some(&[0, 1, 2, 0, 10, 20, 3, 4, 5, 30, 40, 50][..])
+        );
+        let t = a.permute(&[1, 0]).unwrap();
+        assert_eq!(t.kron(&b).unwrap().get(&[5, 1]), Some(&50));
+        assert!(matches!(
+            a.kron(&Tensor::new(vec![1], vec![1]).unwrap()),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_outer_product() {
+        let x = Tensor::new(vec![1.0, -2.0, 0.5], vec![3]).unwrap();
+        let y = Tensor::new(vec![4.0, 2.0], vec![2]).unwrap();
+
+        let o = x.outer(&y).unwrap();
+
+        assert_eq!(o.shape(), &[3, 2]);
+        assert_eq!(o.as_slice(), Some(&[4.0, 2.0, -8.0, -4.0, 2.0, 1.0][..]));
+        // The outer product is the Kronecker product of a column and a row.
+        let column = Tensor::new(vec![1.0, -2.0, 0.5], vec![3, 1]).unwrap();
+        let row = Tensor::new(vec![4.0, 2.0], vec![1, 2]).unwrap();
+        assert_eq!(column.kron(&row).unwrap(), o);
+        assert!(matches!(o.outer(&y), Err(TensorError::ShapeError(_))));
+    }
+
+    #[test]
+    fn test_outer_batched() {
+        let a = Tensor::from_fn(vec![2, 3], |i| (i[0] * 3 + i[1]) as i64);
+        let b = Tensor::new(vec![1, -1, 2, 10], vec![2, 2])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+
+        let o = a.outer_batched(&b).unwrap();
+
+        assert_eq!(o.shape(), &[2, 3, 2]);
+        assert_eq!(
+            o.as_slice(),
+            Some(&[0, 0, 1, 2, 2, 4, -3, 30, -4, 40, -5, 50][..])
+        );
+        let short = Tensor::new(vec![1, 2], vec![1, 2]).unwrap();
+        assert!(a.outer_batched(&short).is_err());
+        assert!(a.outer_batched(&a.outer_batched(&b).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+//! Operations that combine or rearrange tensors without computing new values.
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+/// Resolves a possibly negative `axis` against `ndim` axes, NumPy style.
+pub(crate) fn normalize_axis(axis: isize, ndim: usize) -> Result<usize, TensorError> {
+    let resolved = if axis < 0 { axis + ndim as isize } else { axis };
+    if resolved < 0 || resolved >= ndim as isize {
+        return Err(TensorError::AxisError(format!(
+            "axis {} is out of bounds for {} dimensions",
+            axis, ndim
+        )));
+    }
+
+    Ok(resolved as usize)
+}
+
+impl<T: Copy> Tensor<T> {
+    /// Returns a copy of the tensor with its elements stored in row-major order.
+    pub fn to_contiguous(&self) -> Tensor<T> {
+        Tensor::new(self.iter().copied().collect(), self.shape.clone()).unwrap()
+    }
+
+    /// Reorders the axes so that axis `i` of the result is axis `axes[i]` of `self`.
+    ///
+    /// Only the shape and strides are rearranged; the buffer is cloned as is, so
+    /// the result is generally not contiguous. This keeps the copy a plain
+    /// `memcpy` and lets a permutation followed by another one, or by a
+    /// reduction that walks the strides, avoid a gather pass. Call
+    /// [`Tensor::to_contiguous`] when row-major storage is needed, e.g. for
+    /// [`Tensor::as_slice`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axes` is not a permutation of
+    /// `0..self.ndim()`.
+    pub fn permute(&self, axes: &[usize]) -> Result<Tensor<T>, TensorError> {
+        let mut seen = vec![false; self.ndim()];
+        for &axis in axes {
+            if axis >= self.ndim() || seen[axis] {
+                return Err(TensorError::AxisError(format!(
+                    "{:?} is not a permutation of the {} axes",
+                    axes,
+                    self.ndim()
+                )));
+            }
+            seen[axis] = true;
+        }
+        if axes.len() != self.ndim() {
+            return Err(TensorError::AxisError(format!(
+                "{:?} is not a permutation of the {} axes",
+                axes,
+                self.ndim()
+            )));
+        }
+
+        Ok(Tensor {
+            data: self.data.clone(),
+            shape: axes.iter().map(|&axis| self.shape[axis]).collect(),
+            strides: axes.iter().map(|&axis| self.strides[axis]).collect(),
+        })
+    }
+
+    /// Moves axis `source` to position `destination`, keeping the order of the
+    /// remaining axes. Negative values count from the end, as in NumPy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if either axis is out of bounds.
+    pub fn moveaxis(&self, source: isize, destination: isize) -> Result<Tensor<T>, TensorError> {
+        let source = normalize_axis(source, self.ndim())?;
+        let destination = normalize_axis(destination, self.ndim())?;
+
+        let mut axes: Vec<usize> = (0..self.ndim()).filter(|&axis| axis != source).collect();
+        axes.insert(destination, source);
+        self.permute(&axes)
+    }
+
+    /// Inserts a new axis of length 1 at `position`. Negative positions count
+    /// from the end of the result, so `-1` appends a trailing axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `position` is out of bounds for a
+    /// tensor with one more dimension than `self`.
+    pub fn insert_axis(&self, position: isize) -> Result<Tensor<T>, TensorError> {
+        let position = normalize_axis(position, self.ndim() + 1)?;
+
+        let mut tensor = self.clone();
+        tensor.shape.insert(position, 1);
+        // A unit axis is never stepped along; give it the stride of the axis it
+        // precedes so contiguity is preserved.
+        let stride = self
+            .strides
+            .get(position)
+            .map_or(1, |&s| s * self.shape[position]);
+        tensor.strides.insert(position, stride);
+        Ok(tensor)
+    }
+
+    /// Stacks the tensors produced by `tensors` along a new axis at position `axis`.
+    ///
+    /// Shapes are validated as the iterator is consumed, so thousands of
+    /// per-sample tensors can be stacked without first collecting them into a
+    /// `Vec`. When the iterator reports an exact length, each input is written
+    /// straight into its final position in a single pre-sized buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if the iterator is empty,
+    /// `TensorError::AxisError` if `axis` is greater than the number of
+    /// dimensions of the inputs, and `TensorError::ShapeError` if the inputs do
+    /// not all have the same shape.
+    pub fn stack_from_iter<I>(axis: usize, tensors: I) -> Result<Self, TensorError>
+    where
+        I: IntoIterator<Item = Tensor<T>>,
+    {
+        let mut tensors = tensors.into_iter();
+        let first = tensors.next().ok_or_else(|| {
+            TensorError::ValueError("cannot stack an empty sequence of tensors".to_string())
+        })?;
+        if axis > first.ndim() {
+            return Err(TensorError::AxisError(format!(
+                "axis {} is out of bounds for stacking tensors with {} dimensions",
+                axis,
+                first.ndim()
+            )));
+        }
+
+        let item_shape = first.shape.clone();
+        let item_len = first.len();
+        let inner: usize = item_shape[axis..].iter().product();
+        let exact_count = match tensors.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower + 1),
+            _ => None,
+        };
+
+        // With a known count, elements go straight to their final offsets;
+        // otherwise they are buffered in input order and placed at the end.
+        let mut data = match exact_count {
+            Some(count) => Vec::with_capacity(count * item_len),
+            None => Vec::new(),
+        };
+        let mut count = 0;
+        for tensor in std::iter::once(first).chain(tensors) {
+            if tensor.shape != item_shape {
+                return Err(TensorError::ShapeError(format!(
+                    "cannot stack tensor {} of shape {:?} with tensors of shape {:?}",
+                    count, tensor.shape, item_shape
+                )));
+            }
+            match exact_count {
+                Some(n) if n > 1 && inner < item_len => {
+                    if count == n {
+                        return Err(TensorError::ValueError(
+                            "iterator yielded more tensors than its size hint reported".to_string(),
+                        ));
+                    }
+                    if data.is_empty() {
+                        data.resize(n * item_len, tensor.data[0]);
+                    }
+                    for (j, value) in tensor.iter().enumerate() {
+                        data[(j / inner) * n * inner + count * inner + j % inner] = *value;
+                    }
+                }
+                _ => data.extend(tensor.iter().copied()),
+            }
+            count += 1;
+        }
+
+        if exact_count.is_none() && inner < item_len && count > 1 {
+            let buffered = std::mem::take(&mut data);
+            data = (0..buffered.len())
+                .map(|k| {
+                    let (outer, i) = (k / (count * inner), k / inner % count);
+                    buffered[i * item_len + outer * inner + k % inner]
+                })
+                .collect();
+        }
+
+        let mut shape = item_shape;
+        shape.insert(axis, count);
+        Tensor::new(data, shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<Tensor<i32>> {
+        vec![
+            Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap(),
+            Tensor::new(vec![5, 6, 7, 8], vec![2, 2]).unwrap(),
+            Tensor::new(vec![9, 10, 11, 12], vec![2, 2]).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_stack_from_iter_axis0() {
+        let result = Tensor::stack_from_iter(0, samples()).unwrap();
+
+        assert_eq!(result.shape, &[3, 2, 2]);
+        assert_eq!(result.data, (1..=12).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_stack_from_iter_inner_axes() {
+        let middle = Tensor::stack_from_iter(1, samples()).unwrap();
+        let last = Tensor::stack_from_iter(2, samples()).unwrap();
+        // A filtered iterator has no exact length, exercising the buffered path.
+        let filtered = Tensor::stack_from_iter(2, samples().into_iter().filter(|_| true)).unwrap();
+
+        assert_eq!(middle.shape, &[2, 3, 2]);
+        assert_eq!(middle.data, vec![1, 2, 5, 6, 9, 10, 3, 4, 7, 8, 11, 12]);
+        assert_eq!(last.shape, &[2, 2, 3]);
+        assert_eq!(last.data, vec![1, 5, 9, 2, 6, 10, 3, 7, 11, 4, 8, 12]);
+        assert_eq!(filtered, last);
+        let unsized_middle = samples().into_iter().filter(|_| true);
+        assert_eq!(Tensor::stack_from_iter(1, unsized_middle).unwrap(), middle);
+    }
+
+    #[test]
+    fn test_permute_and_to_contiguous() {
+        let tensor = Tensor::new((0..6).collect(), vec![2, 3]).unwrap();
+
+        let transposed = tensor.permute(&[1, 0]).unwrap();
+
+        assert_eq!(transposed.shape, &[3, 2]);
+        assert_eq!(transposed.strides, &[1, 3]);
+        assert_eq!(transposed.get(&[2, 1]), Some(&5));
+        assert_eq!(transposed.to_contiguous().data, vec![0, 3, 1, 4, 2, 5]);
+        assert!(matches!(
+            tensor.permute(&[0, 0]),
+            Err(TensorError::AxisError(_))
+        ));
+        assert!(matches!(
+            tensor.permute(&[0]),
+            Err(TensorError::AxisError(_))
+        ));
+    }
+
+    #[test]
+    fn test_moveaxis() {
+        let tensor = Tensor::new((0..24).collect(), vec![2, 3, 4]).unwrap();
+
+        assert_eq!(tensor.moveaxis(0, -1).unwrap().shape, &[3, 4, 2]);
+        assert_eq!(tensor.moveaxis(-1, 0).unwrap().shape, &[4, 2, 3]);
+        assert_eq!(tensor.moveaxis(2, 1).unwrap().get(&[1, 3, 2]), Some(&23));
+        assert!(matches!(
+            tensor.moveaxis(3, 0),
+            Err(TensorError::AxisError(_))
+        ));
+    }
+
+    #[test]
+    fn test_insert_axis() {
+        let tensor = Tensor::new((0..6).collect(), vec![2, 3]).unwrap();
+
+        let front = tensor.insert_axis(0).unwrap();
+        let back = tensor.insert_axis(-1).unwrap();
+        let middle = tensor.insert_axis(-2).unwrap();
+
+        assert_eq!(front.shape, &[1, 2, 3]);
+        assert_eq!(back.shape, &[2, 3, 1]);
+        assert_eq!(middle.shape, &[2, 1, 3]);
+        assert!(front.is_contiguous() && back.is_contiguous() && middle.is_contiguous());
+        assert!(matches!(
+            tensor.insert_axis(4),
+            Err(TensorError::AxisError(_))
+        ));
+    }
+
+    #[test]
+    fn test_stack_from_iter_errors() {
+        let mismatched = vec![
+            Tensor::new(vec![1, 2], vec![2]).unwrap(),
+            Tensor::new(vec![1, 2, 3], vec![3]).unwrap(),
+        ];
+
+        assert!(matches!(
+            Tensor::<i32>::stack_from_iter(0, Vec::new()),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            Tensor::stack_from_iter(3, samples()),
+            Err(TensorError::AxisError(_))
+        ));
+        assert!(matches!(
+            Tensor::stack_from_iter(0, mismatched),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+}
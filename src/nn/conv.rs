@@ -0,0 +1,208 @@
+//! Two-dimensional convolution over channel-first images.
+
+use std::ops::{Add, Mul};
+
+use crate::cancel;
+use crate::error::TensorError;
+use crate::num::Zero;
+use crate::tensor::Tensor;
+
+/// Returns the output length of a sliding window of `window` elements over
+/// `len` elements padded by `padding` on both sides, or `None` if the window
+/// does not fit.
+pub(crate) fn window_count(
+    len: usize,
+    window: usize,
+    stride: usize,
+    padding: usize,
+) -> Option<usize> {
+    (len + 2 * padding)
+        .checked_sub(window)
+        .map(|span| span / stride + 1)
+}
+
+impl<T> Tensor<T>
+where
+    T: Copy + Zero + Add<Output = T> + Mul<Output = T>,
+{
+    /// Convolves a `[C_in, H, W]` image, or a `[N, C_in, H, W]` batch of them,
+    /// with `kernel` of shape `[C_out, C_in, kH, kW]`.
+    ///
+    /// The kernel is slid without flipping (cross-correlation), as in deep
+    /// learning frameworks. `stride` and `padding` apply to both spatial axes,
+    /// and padded positions are zero. The output has shape
+    /// `[C_out, H_out, W_out]`, or `[N, C_out, H_out, W_out]` for a batch, with
+    /// `H_out = (H + 2 * padding - kH) / stride + 1` and likewise for `W_out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `stride` is zero, and
+    /// `TensorError::ShapeError` if the input or kernel has the wrong number of
+    /// axes, their channel counts differ, or the kernel is larger than the
+    /// padded image. Returns `TensorError::Cancelled` if the current
+    /// [`CancellationToken`](crate::cancel::CancellationToken) is cancelled
+    /// before an output channel is computed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let image = Tensor::new((1..=9).collect(), vec![1, 3, 3]).unwrap();
+    /// let kernel = Tensor::new(vec![1, 0, 0, 1], vec![1, 1, 2, 2]).unwrap();
+    ///
+    /// let out = image.conv2d(&kernel, 1, 0).unwrap();
+    /// assert_eq!(out.shape(), &[1, 2, 2]);
+    /// assert_eq!(out.as_slice(), Some(&[6, 8, 12, 14][..]));
+    /// ```
+    pub fn conv2d(
+        &self,
+        kernel: &Tensor<T>,
+        stride: usize,
+        padding: usize,
+    ) -> Result<Tensor<T>, TensorError> {
+        if stride == 0 {
+            return Err(TensorError::ValueError(
+                "conv2d stride must be positive".to_string(),
+            ));
+        }
+        let (batch, channels, height, width) = match *self.shape() {
+            [c, h, w] => (None, c, h, w),
+            [n, c, h, w] => (Some(n), c, h, w),
+            _ => {
+                return Err(TensorError::ShapeError(format!(
+                    "conv2d input must have shape [C_in, H, W] or [N, C_in, H, W], got {:?}",
+                    self.shape()
+                )));
+            }
+        };
+        let (out_channels, kh, kw) = match *kernel.shape() {
+            [o, c, kh, kw] if c == channels => (o, kh, kw),
+            _ => {
+                return Err(TensorError::ShapeError(format!(
+                    "conv2d kernel must have shape [C_out, {}, kH, kW], got {:?}",
+                    channels,
+                    kernel.shape()
+                )));
+            }
+        };
+        let (out_h, out_w) = match (
+            window_count(height, kh, stride, padding),
+            window_count(width, kw, stride, padding),
+        ) {
+            (Some(out_h), Some(out_w)) => (out_h, out_w),
+            _ => {
+                return Err(TensorError::ShapeError(format!(
+                    "conv2d kernel of size {}x{} does not fit a {}x{} image with padding {}",
+                    kh, kw, height, width, padding
+                )));
+            }
+        };
+
+        let input: Vec<T> = self.iter().copied().collect();
+        let weights: Vec<T> = kernel.iter().copied().collect();
+        let image_len = channels * height * width;
+        let mut data = Vec::with_capacity(batch.unwrap_or(1) * out_channels * out_h * out_w);
+        let filter_len = channels * kh * kw;
+        for n in 0..batch.unwrap_or(1) {
+            let image = &input[n * image_len..(n + 1) * image_len];
+            for o in 0..out_channels {
+                cancel::check("conv2d")?;
+                let filter = &weights[o * filter_len..(o + 1) * filter_len];
+                for oy in 0..out_h {
+                    for ox in 0..out_w {
+                        let mut acc = T::zero();
+                        for c in 0..channels {
+                            for ky in 0..kh {
+                                // Rows and columns of the padding contribute nothing.
+                                let Some(y) = (oy * stride + ky).checked_sub(padding) else {
+                                    continue;
+                                };
+                                if y >= height {
+                                    continue;
+                                }
+                                for kx in 0..kw {
+                                    let Some(x) = (ox * stride + kx).checked_sub(padding) else {
+                                        continue;
+                                    };
+                                    if x < width {
+                                        acc = acc
+                                            + image[(c * height + y) * width + x]
+                                                * filter[(c * kh + ky) * kw + kx];
+                                    }
+                                }
+                            }
+                        }
+                        data.push(acc);
+                    }
+                }
+            }
+        }
+
+        let mut shape: Vec<usize> = batch.into_iter().collect();
+        shape.extend([out_channels, out_h, out_w]);
+        Tensor::new(data, shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conv2d_stride_padding_and_channels() {
+        // Two input channels summed by a single 3x3 box filter.
+        let image = Tensor::from_fn(vec![2, 3, 3], |i| (i[0] * 9 + i[1] * 3 + i[2]) as i64);
+        let kernel = Tensor::new(vec![1i64; 18], vec![1, 2, 3, 3]).unwrap();
+
+        let same = image.conv2d(&kernel, 1, 1).unwrap();
+        assert_eq!(same.shape(), &[1, 3, 3]);
+        // The corner sees a 2x2 window of each channel: 0+1+3+4 and 9+10+12+13.
+        assert_eq!(same.get(&[0, 0, 0]), Some(&52));
+        // The centre sees both full channels: 36 + 117.
+        assert_eq!(same.get(&[0, 1, 1]), Some(&153));
+
+        let strided = image.conv2d(&kernel, 2, 1).unwrap();
+        assert_eq!(strided.shape(), &[1, 2, 2]);
+        assert_eq!(strided.get(&[0, 0, 0]), same.get(&[0, 0, 0]));
+        assert_eq!(strided.get(&[0, 1, 1]), same.get(&[0, 2, 2]));
+
+        // Several output channels from a batch of images.
+        let kernels = Tensor::from_fn(vec![2, 2, 1, 1], |i| if i[0] == i[1] { 1 } else { 0 });
+        let batch = Tensor::from_fn(vec![2, 2, 3, 3], |i| {
+            (i[0] * 18 + i[1] * 9 + i[2] * 3 + i[3]) as i64
+        });
+        let out = batch.conv2d(&kernels, 1, 0).unwrap();
+        assert_eq!(out, batch);
+    }
+
+    #[test]
+    fn test_conv2d_rejects_bad_shapes() {
+        let image = Tensor::new(vec![0.0f32; 12], vec![1, 3, 4]).unwrap();
+        let kernel = Tensor::new(vec![1.0f32; 4], vec![1, 1, 2, 2]).unwrap();
+        assert!(matches!(
+            image.conv2d(&kernel, 0, 0),
+            Err(TensorError::ValueError(_))
+        ));
+        let wrong_channels = Tensor::new(vec![1.0f32; 8], vec![1, 2, 2, 2]).unwrap();
+        assert!(matches!(
+            image.conv2d(&wrong_channels, 1, 0),
+            Err(TensorError::ShapeError(_))
+        ));
+        let too_big = Tensor::new(vec![1.0f32; 25], vec![1, 1, 5, 5]).unwrap();
+        assert!(matches!(
+            image.conv2d(&too_big, 1, 0),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert_eq!(image.conv2d(&too_big, 1, 1).unwrap().shape(), &[1, 1, 2]);
+        let flat = Tensor::new(vec![0.0f32; 12], vec![12]).unwrap();
+        assert!(flat.conv2d(&kernel, 1, 0).is_err());
+
+        let token = crate::cancel::CancellationToken::new();
+        token.cancel();
+        assert!(matches!(
+            token.scoped(|| image.conv2d(&kernel, 1, 0)),
+            Err(TensorError::Cancelled(_))
+        ));
+    }
+}
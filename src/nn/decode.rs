@@ -0,0 +1,219 @@
+//! Decoding per-step class scores into label sequences, as needed after
+//! sequence models for speech and handwriting recognition.
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::sorting::total_cmp;
+use crate::tensor::Tensor;
+
+/// Best label sequences with their scores, as returned by [`beam_search`].
+pub type Beams<T> = (Tensor<usize>, Tensor<T>);
+
+/// Returns the number of steps and classes of a `[steps, classes]` tensor.
+fn steps_and_classes<T>(scores: &Tensor<T>, what: &str) -> Result<(usize, usize), TensorError> {
+    match scores.shape() {
+        [steps, classes] if *classes > 0 => Ok((*steps, *classes)),
+        shape => Err(TensorError::ShapeError(format!(
+            "{} must have shape [steps, classes] with at least one class, got {:?}",
+            what, shape
+        ))),
+    }
+}
+
+/// Greedily decodes connectionist temporal classification (CTC) output.
+///
+/// `logits` holds unnormalized scores of shape `[steps, classes]`. The most
+/// likely class is taken at every step, then repeated classes are merged and
+/// the `blank` class is dropped, so `a a - a b` decodes to `a a b`. Returns the
+/// decoded labels with the log-probability of the chosen best path under a
+/// softmax over each step.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `logits` is not a `[steps, classes]`
+/// tensor with at least one class and `TensorError::ValueError` if `blank` is
+/// not a class index.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::nn::decode::greedy_ctc;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// // Classes are [blank, 'a', 'b'].
+/// let logits = Tensor::new(
+///     vec![0.1, 2.0, 0.0, 0.1, 2.0, 0.0, 3.0, 0.0, 0.0, 0.0, 2.0, 0.5, 0.0, 0.0, 2.0],
+///     vec![5, 3],
+/// )
+/// .unwrap();
+/// let (labels, _score) = greedy_ctc(&logits, 0).unwrap();
+/// assert_eq!(labels.as_slice(), Some(&[1, 1, 2][..]));
+/// ```
+pub fn greedy_ctc<T: Float>(
+    logits: &Tensor<T>,
+    blank: usize,
+) -> Result<(Tensor<usize>, T), TensorError> {
+    let (_, classes) = steps_and_classes(logits, "logits")?;
+    if blank >= classes {
+        return Err(TensorError::ValueError(format!(
+            "blank index {} is out of range for {} classes",
+            blank, classes
+        )));
+    }
+
+    let values: Vec<T> = logits.iter().copied().collect();
+    let mut labels = Vec::new();
+    let mut score = T::zero();
+    let mut previous = None;
+    for step in values.chunks(classes) {
+        let (best, &max) = step
+            .iter()
+            .enumerate()
+            .reduce(|best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+            .unwrap_or((0, &step[0]));
+        // log_softmax(x)[best] = x[best] - logsumexp(x), and x[best] is the max.
+        let sum = step.iter().fold(T::zero(), |acc, &x| acc + (x - max).exp());
+        score = score - sum.ln();
+        if best != blank && previous != Some(best) {
+            labels.push(best);
+        }
+        previous = Some(best);
+    }
+
+    let len = labels.len();
+    Ok((Tensor::new(labels, vec![len])?, score))
+}
+
+/// Returns the `beam_width` most likely label sequences under per-step class
+/// probabilities, found by beam search.
+///
+/// `probs` has shape `[steps, classes]` and each row is a probability
+/// distribution over the classes at that step, such as the output of a softmax.
+/// The score of a sequence is the sum of the log-probabilities of its labels.
+/// Returns a `[beams, steps]` tensor of label sequences, best first, and the
+/// `[beams]` tensor of their scores; there are fewer than `beam_width` beams
+/// only when fewer sequences exist.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `probs` is not a `[steps, classes]`
+/// tensor with at least one class and `TensorError::ValueError` if
+/// `beam_width` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::nn::decode::beam_search;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let probs = Tensor::new(vec![0.6, 0.4, 0.3, 0.7], vec![2, 2]).unwrap();
+/// let (sequences, scores) = beam_search(&probs, 2).unwrap();
+/// assert_eq!(sequences.as_slice(), Some(&[0, 1, 1, 1][..]));
+/// assert!((scores.as_slice().unwrap()[0] - 0.42f64.ln()).abs() < 1e-12);
+/// ```
+pub fn beam_search<T: Float>(
+    probs: &Tensor<T>,
+    beam_width: usize,
+) -> Result<Beams<T>, TensorError> {
+    let (steps, classes) = steps_and_classes(probs, "probabilities")?;
+    if beam_width == 0 {
+        return Err(TensorError::ValueError(
+            "beam width must be positive".to_string(),
+        ));
+    }
+
+    let values: Vec<T> = probs.iter().copied().collect();
+    let mut beams: Vec<(Vec<usize>, T)> = vec![(Vec::new(), T::zero())];
+    for step in values.chunks(classes) {
+        // Only the best `beam_width` classes of a step can extend a surviving beam.
+        let mut ranked: Vec<(usize, T)> = step.iter().map(|&p| p.ln()).enumerate().collect();
+        ranked.sort_by(|a, b| total_cmp(&b.1, &a.1));
+        ranked.truncate(beam_width);
+
+        let mut candidates: Vec<(Vec<usize>, T)> = beams
+            .iter()
+            .flat_map(|(labels, score)| {
+                ranked.iter().map(move |&(class, log_prob)| {
+                    let mut extended = Vec::with_capacity(labels.len() + 1);
+                    extended.extend_from_slice(labels);
+                    extended.push(class);
+                    (extended, *score + log_prob)
+                })
+            })
+            .collect();
+        candidates.sort_by(|a, b| total_cmp(&b.1, &a.1));
+        candidates.truncate(beam_width);
+        beams = candidates;
+    }
+
+    let count = beams.len();
+    let (sequences, scores): (Vec<Vec<usize>>, Vec<T>) = beams.into_iter().unzip();
+    Ok((
+        Tensor::new(sequences.concat(), vec![count, steps])?,
+        Tensor::new(scores, vec![count])?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greedy_ctc_collapses_repeats_and_blanks() {
+        let logits = Tensor::new(
+            vec![
+                0.0, 5.0, 0.0, 0.0, 5.0, 0.0, 5.0, 0.0, 0.0, 0.0, 5.0, 0.0, 0.0, 0.0, 5.0,
+            ],
+            vec![5, 3],
+        )
+        .unwrap();
+
+        let (labels, score) = greedy_ctc(&logits, 0).unwrap();
+
+        assert_eq!(labels.as_slice(), Some(&[1, 1, 2][..]));
+        let step = -(1.0 + 2.0 * (-5.0f64).exp()).ln();
+        assert!((score - 5.0 * step).abs() < 1e-12);
+
+        // With class 1 as the blank, the same steps decode differently.
+        let (labels, _) = greedy_ctc(&logits, 1).unwrap();
+        assert_eq!(labels.as_slice(), Some(&[0, 2][..]));
+
+        let empty = Tensor::<f64>::new(vec![], vec![0, 3]).unwrap();
+        assert_eq!(greedy_ctc(&empty, 0).unwrap().0.len(), 0);
+        assert!(matches!(
+            greedy_ctc(&logits, 3),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(greedy_ctc(&Tensor::new(vec![1.0], vec![1]).unwrap(), 0).is_err());
+    }
+
+    #[test]
+    fn test_beam_search_ranks_sequences() {
+        let probs = Tensor::new(vec![0.5, 0.3, 0.2, 0.1, 0.1, 0.8], vec![2, 3]).unwrap();
+
+        let (sequences, scores) = beam_search(&probs, 3).unwrap();
+
+        assert_eq!(sequences.shape(), &[3, 2]);
+        assert_eq!(sequences.as_slice(), Some(&[0, 2, 1, 2, 2, 2][..]));
+        let expected = [0.4f64.ln(), 0.24f64.ln(), 0.16f64.ln()];
+        for (score, expected) in scores.iter().zip(expected) {
+            assert!((score - expected).abs() < 1e-12);
+        }
+
+        // A single beam is the greedy path.
+        let (greedy, _) = beam_search(&probs, 1).unwrap();
+        assert_eq!(greedy.as_slice(), Some(&[0, 2][..]));
+        // Every sequence is kept when the beam is wider than their number.
+        assert_eq!(beam_search(&probs, 20).unwrap().1.len(), 9);
+        assert!(matches!(
+            beam_search(&probs, 0),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+}
@@ -0,0 +1,389 @@
+//! Building blocks for running neural network models on tensors.
+
+pub mod conv;
+pub mod decode;
+pub mod params;
+pub mod pool;
+pub mod sequence;
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Base of the geometric progression of wavelengths used by both positional
+/// encodings, as in "Attention Is All You Need".
+const POSITION_BASE: f64 = 10_000.0;
+
+/// Returns the angular frequency of the `i`-th of `dim / 2` rotation pairs.
+fn frequency(i: usize, dim: usize) -> f64 {
+    POSITION_BASE.powf(-((2 * i) as f64) / dim as f64)
+}
+
+/// Returns the sinusoidal positional encoding of shape `[seq_len, dim]`.
+///
+/// Position `p` is encoded as `sin(p w_i)` in column `2i` and `cos(p w_i)` in
+/// column `2i + 1`, with frequencies `w_i = 10000^(-2i / dim)`, so that every
+/// relative offset is a fixed linear map of the encoding. An odd final column
+/// holds a sine.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::nn::sinusoidal_positional_encoding;
+///
+/// let pe = sinusoidal_positional_encoding::<f64>(4, 6);
+/// assert_eq!(pe.shape(), &[4, 6]);
+/// assert_eq!(pe.get(&[0, 0]), Some(&0.0));
+/// assert_eq!(pe.get(&[0, 1]), Some(&1.0));
+/// assert!((pe.get(&[2, 0]).unwrap() - 2f64.sin()).abs() < 1e-12);
+/// ```
+pub fn sinusoidal_positional_encoding<T: Float>(seq_len: usize, dim: usize) -> Tensor<T> {
+    Tensor::from_fn(vec![seq_len, dim], |index| {
+        let (position, column) = (index[0], index[1]);
+        let angle = position as f64 * frequency(column / 2, dim);
+        T::from_f64(if column % 2 == 0 {
+            angle.sin()
+        } else {
+            angle.cos()
+        })
+    })
+}
+
+/// Applies rotary position embeddings (RoPE) to a query and a key tensor.
+///
+/// `q` and `k` have shape `[..., seq, dim]`, such as `[batch, heads, seq, dim]`,
+/// and may differ in their leading dimensions. Row `s` of each is rotated by
+/// the angles of `positions[s]`: feature `i` is paired with feature
+/// `i + dim / 2` and the pair is rotated by `positions[s] w_i`, with
+/// `w_i = 10000^(-2i / dim)`. This is the half-split layout of GPT-NeoX and
+/// Llama. Dot products between rotated queries and keys then depend only on
+/// the offset between their positions.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `positions` is not 1D, or `q` or `k`
+/// has fewer than two dimensions, an odd last dimension or a sequence length
+/// other than the number of positions, or their last dimensions differ.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::nn::apply_rope;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let q = Tensor::new(vec![1.0f64, 0.0, 1.0, 0.0], vec![2, 2]).unwrap();
+/// let positions = Tensor::new(vec![0, 1], vec![2]).unwrap();
+/// let (q, _) = apply_rope(&q, &q, &positions).unwrap();
+/// // Position 0 is unchanged; position 1 is rotated by one radian.
+/// assert_eq!(q.get(&[0, 0]), Some(&1.0));
+/// assert!((q.get(&[1, 1]).unwrap() - 1f64.sin()).abs() < 1e-12);
+/// ```
+pub fn apply_rope<T: Float>(
+    q: &Tensor<T>,
+    k: &Tensor<T>,
+    positions: &Tensor<usize>,
+) -> Result<(Tensor<T>, Tensor<T>), TensorError> {
+    if positions.ndim() != 1 {
+        return Err(TensorError::ShapeError(format!(
+            "positions must be 1D, got shape {:?}",
+            positions.shape()
+        )));
+    }
+    let seq = positions.len();
+    let dim = rope_dim(q, seq, "queries")?;
+    if rope_dim(k, seq, "keys")? != dim {
+        return Err(TensorError::ShapeError(format!(
+            "queries of shape {:?} and keys of shape {:?} have different feature sizes",
+            q.shape(),
+            k.shape()
+        )));
+    }
+
+    // cos and sin of every (position, pair) angle, shared by q and k.
+    let half = dim / 2;
+    let (cos, sin): (Vec<T>, Vec<T>) = positions
+        .iter()
+        .flat_map(|&p| (0..half).map(move |i| p as f64 * frequency(i, dim)))
+        .map(|angle| (T::from_f64(angle.cos()), T::from_f64(angle.sin())))
+        .unzip();
+
+    let rotate = |x: &Tensor<T>| {
+        let mut data: Vec<T> = x.iter().copied().collect();
+        if dim > 0 {
+            for (row, features) in data.chunks_mut(dim).enumerate() {
+                let offset = (row % seq) * half;
+                let (first, second) = features.split_at_mut(half);
+                for (i, (a, b)) in first.iter_mut().zip(second).enumerate() {
+                    let (c, s) = (cos[offset + i], sin[offset + i]);
+                    (*a, *b) = (*a * c - *b * s, *b * c + *a * s);
+                }
+            }
+        }
+        Tensor::new(data, x.shape().to_vec())
+    };
+    Ok((rotate(q)?, rotate(k)?))
+}
+
+/// Returns the feature size of a `[..., seq, dim]` input to [`apply_rope`].
+fn rope_dim<T>(x: &Tensor<T>, seq: usize, what: &str) -> Result<usize, TensorError> {
+    match x.shape() {
+        [.., len, dim] if *len == seq && dim % 2 == 0 => Ok(*dim),
+        shape => Err(TensorError::ShapeError(format!(
+            "{} must have shape [..., {}, dim] with an even dim, got {:?}",
+            what, seq, shape
+        ))),
+    }
+}
+
+/// A key/value cache for incremental (autoregressive) attention.
+///
+/// Keys and values live in buffers preallocated for `[batch, heads, max_len,
+/// dim]`, so appending a decoding step copies only that step instead of
+/// reallocating and copying the whole history. The cached prefix of every
+/// batch entry and head is a contiguous `[len, dim]` slice, available without
+/// copying through [`KvCache::keys`] and [`KvCache::values`].
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::nn::KvCache;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let mut cache = KvCache::new(1, 1, 8, 2);
+/// let step = Tensor::new(vec![1.0, 2.0], vec![1, 1, 1, 2]).unwrap();
+/// cache.append(&step, &step).unwrap();
+/// cache.append(&step, &step).unwrap();
+/// assert_eq!(cache.keys(0, 0), Some(&[1.0, 2.0, 1.0, 2.0][..]));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct KvCache<T> {
+    keys: Vec<T>,
+    values: Vec<T>,
+    batch: usize,
+    heads: usize,
+    max_len: usize,
+    dim: usize,
+    len: usize,
+}
+
+impl<T: Copy + Default> KvCache<T> {
+    /// Creates an empty cache with room for `max_len` steps of `batch` entries
+    /// with `heads` heads of size `dim`.
+    pub fn new(batch: usize, heads: usize, max_len: usize, dim: usize) -> Self {
+        let size = batch * heads * max_len * dim;
+        KvCache {
+            keys: vec![T::default(); size],
+            values: vec![T::default(); size],
+            batch,
+            heads,
+            max_len,
+            dim,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of cached steps.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no steps are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of steps the cache can hold.
+    pub fn capacity(&self) -> usize {
+        self.max_len
+    }
+
+    /// Appends the keys and values of one or more new steps, each of shape
+    /// `[batch, heads, steps, dim]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if a step does not have the cache's
+    /// batch, head and feature sizes or the two steps differ in length, and
+    /// `TensorError::ValueError` if the cache has no room left for them. The
+    /// cache is unchanged on error.
+    pub fn append(&mut self, step_k: &Tensor<T>, step_v: &Tensor<T>) -> Result<(), TensorError> {
+        let steps = self.step_len(step_k, "keys")?;
+        if self.step_len(step_v, "values")? != steps {
+            return Err(TensorError::ShapeError(format!(
+                "keys of shape {:?} and values of shape {:?} differ in length",
+                step_k.shape(),
+                step_v.shape()
+            )));
+        }
+        if self.len + steps > self.max_len {
+            return Err(TensorError::ValueError(format!(
+                "cannot append {} steps to a cache holding {} of at most {}",
+                steps, self.len, self.max_len
+            )));
+        }
+
+        let chunk = steps * self.dim;
+        for (buffer, step) in [(&mut self.keys, step_k), (&mut self.values, step_v)] {
+            let data: Vec<T> = step.iter().copied().collect();
+            if chunk == 0 {
+                continue;
+            }
+            for (head, rows) in data.chunks(chunk).enumerate() {
+                let start = (head * self.max_len + self.len) * self.dim;
+                buffer[start..start + chunk].copy_from_slice(rows);
+            }
+        }
+        self.len += steps;
+        Ok(())
+    }
+
+    /// Returns the cached keys of a batch entry and head as a row-major
+    /// `[len, dim]` slice, or `None` if either index is out of range.
+    pub fn keys(&self, batch: usize, head: usize) -> Option<&[T]> {
+        self.prefix(&self.keys, batch, head)
+    }
+
+    /// Returns the cached values of a batch entry and head as a row-major
+    /// `[len, dim]` slice, or `None` if either index is out of range.
+    pub fn values(&self, batch: usize, head: usize) -> Option<&[T]> {
+        self.prefix(&self.values, batch, head)
+    }
+
+    /// Empties the cache, keeping its buffers for reuse.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn prefix<'a>(&self, buffer: &'a [T], batch: usize, head: usize) -> Option<&'a [T]> {
+        if batch >= self.batch || head >= self.heads {
+            return None;
+        }
+        let start = (batch * self.heads + head) * self.max_len * self.dim;
+        Some(&buffer[start..start + self.len * self.dim])
+    }
+
+    fn step_len(&self, step: &Tensor<T>, what: &str) -> Result<usize, TensorError> {
+        match step.shape() {
+            [batch, heads, steps, dim]
+                if (*batch, *heads, *dim) == (self.batch, self.heads, self.dim) =>
+            {
+                Ok(*steps)
+            }
+            shape => Err(TensorError::ShapeError(format!(
+                "{} must have shape [{}, {}, steps, {}], got {:?}",
+                what, self.batch, self.heads, self.dim, shape
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sinusoidal_positional_encoding() {
+        let pe = sinusoidal_positional_encoding::<f64>(3, 5);
+
+        assert_eq!(pe.shape(), &[3, 5]);
+        let w = 10_000f64.powf(-2.0 / 5.0);
+        assert!((pe.get(&[2, 1]).unwrap() - 2f64.cos()).abs() < 1e-12);
+        assert!((pe.get(&[2, 2]).unwrap() - (2.0 * w).sin()).abs() < 1e-12);
+        assert!((pe.get(&[1, 3]).unwrap() - w.cos()).abs() < 1e-12);
+        // The odd last column is a sine of the third frequency.
+        let w2 = 10_000f64.powf(-4.0 / 5.0);
+        assert!((pe.get(&[1, 4]).unwrap() - w2.sin()).abs() < 1e-12);
+        assert_eq!(sinusoidal_positional_encoding::<f32>(0, 4).len(), 0);
+    }
+
+    #[test]
+    fn test_apply_rope_depends_on_relative_position() {
+        // [batch = 1, heads = 2, seq = 3, dim = 4] queries with a single key head.
+        let q = Tensor::from_fn(vec![1, 2, 3, 4], |i| (i[1] + 2 * i[2] + i[3]) as f64 - 2.0);
+        let k = Tensor::from_fn(vec![1, 1, 3, 4], |i| (3 * i[2] + i[3]) as f64 * 0.5);
+        let rows = |x: &Tensor<f64>| x.iter().copied().collect::<Vec<f64>>();
+        let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+
+        let near = Tensor::new(vec![0, 1, 2], vec![3]).unwrap();
+        let far = Tensor::new(vec![10, 11, 12], vec![3]).unwrap();
+        let (q_near, k_near) = apply_rope(&q, &k, &near).unwrap();
+        let (q_far, k_far) = apply_rope(&q, &k, &far).unwrap();
+
+        assert_eq!(q_near.shape(), q.shape());
+        let (q_near, k_near, q_far, k_far) =
+            (rows(&q_near), rows(&k_near), rows(&q_far), rows(&k_far));
+        // Rotations keep norms, and shifting every position keeps q.k scores.
+        assert!((dot(&q_near[4..8], &q_near[4..8]) - dot(&q_far[4..8], &q_far[4..8])).abs() < 1e-9);
+        for (qi, ki) in [(0, 2), (5, 1), (2, 0)] {
+            let near = dot(&q_near[qi * 4..qi * 4 + 4], &k_near[ki * 4..ki * 4 + 4]);
+            let far = dot(&q_far[qi * 4..qi * 4 + 4], &k_far[ki * 4..ki * 4 + 4]);
+            assert!((near - far).abs() < 1e-9);
+        }
+
+        let odd = Tensor::new(vec![1.0; 9], vec![3, 3]).unwrap();
+        assert!(matches!(
+            apply_rope(&odd, &odd, &near),
+            Err(TensorError::ShapeError(_))
+        ));
+        let short = Tensor::new(vec![0, 1], vec![2]).unwrap();
+        assert!(apply_rope(&q, &k, &short).is_err());
+    }
+
+    #[test]
+    fn test_kv_cache_appends_steps() {
+        let mut cache = KvCache::<f64>::new(2, 2, 4, 3);
+        assert!(cache.is_empty());
+        assert_eq!(cache.keys(1, 1), Some(&[][..]));
+
+        // A two-step prefill followed by a single decoding step.
+        let prefill_k =
+            Tensor::from_fn(vec![2, 2, 2, 3], |i| (i[0] * 100 + i[1] * 10 + i[2]) as f64);
+        let prefill_v = Tensor::from_fn(vec![2, 2, 2, 3], |i| {
+            -((i[0] * 100 + i[1] * 10 + i[2]) as f64)
+        });
+        cache.append(&prefill_k, &prefill_v).unwrap();
+        let step = Tensor::from_fn(vec![2, 2, 1, 3], |i| (i[0] * 100 + i[1] * 10) as f64 + 0.5);
+        cache.append(&step, &step).unwrap();
+
+        assert_eq!(cache.len(), 3);
+        assert_eq!(
+            cache.keys(1, 0),
+            Some(
+                &[
+                    100.0, 100.0, 100.0, 101.0, 101.0, 101.0, 100.5, 100.5, 100.5
+                ][..]
+            )
+        );
+        assert_eq!(
+            cache.values(0, 1),
+            Some(&[-10.0, -10.0, -10.0, -11.0, -11.0, -11.0, 10.5, 10.5, 10.5][..])
+        );
+        assert_eq!(cache.keys(2, 0), None);
+
+        cache.clear();
+        assert_eq!(cache.keys(0, 0).map(<[f64]>::len), Some(0));
+        assert_eq!(cache.capacity(), 4);
+    }
+
+    #[test]
+    fn test_kv_cache_rejects_bad_steps() {
+        let mut cache = KvCache::<f32>::new(1, 2, 2, 4);
+        let step = Tensor::new(vec![1.0; 8], vec![1, 2, 1, 4]).unwrap();
+        let wrong_heads = Tensor::new(vec![1.0; 4], vec![1, 1, 1, 4]).unwrap();
+        let two_steps = Tensor::new(vec![1.0; 16], vec![1, 2, 2, 4]).unwrap();
+
+        assert!(matches!(
+            cache.append(&wrong_heads, &wrong_heads),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(cache.append(&step, &two_steps).is_err());
+        cache.append(&step, &step).unwrap();
+        assert!(matches!(
+            cache.append(&two_steps, &two_steps),
+            Err(TensorError::ValueError(_))
+        ));
+        assert_eq!(cache.len(), 1);
+        cache.append(&step, &step).unwrap();
+        assert_eq!(cache.len(), cache.capacity());
+    }
+}
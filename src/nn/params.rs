@@ -0,0 +1,338 @@
+//! A registry of named, shareable model parameters.
+//!
+//! Parameters are held behind `Arc<RwLock<_>>`, so a tensor registered under
+//! several names (weight tying, e.g. an embedding reused as the output
+//! projection) is a single tensor: updating it through one name is visible
+//! through every other, and it is saved and loaded as one value per name.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::error::TensorError;
+use crate::io::npy::NpyElement;
+use crate::io::npz::{read_npz, write_npz};
+use crate::tensor::Tensor;
+
+/// A shared handle to a parameter tensor.
+pub type Param<T> = Arc<RwLock<Tensor<T>>>;
+
+#[derive(Debug)]
+struct Entry<T> {
+    param: Param<T>,
+    frozen: bool,
+}
+
+/// Named parameters with per-parameter freeze flags.
+///
+/// Names are kept in sorted order. Freezing a parameter marks it as excluded
+/// from training; a frozen flag belongs to the tensor, so it covers every name
+/// tied to it.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::nn::params::ParamStore;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let mut store = ParamStore::new();
+/// let embedding = store.insert("embed", Tensor::new(vec![0.5, -0.5], vec![1, 2]).unwrap()).unwrap();
+/// store.tie("lm_head", "embed").unwrap();
+///
+/// // Both names share one tensor.
+/// embedding.write().unwrap().as_slice_mut().unwrap()[0] = 2.0;
+/// let head = store.get("lm_head").unwrap();
+/// assert_eq!(head.read().unwrap().as_slice(), Some(&[2.0, -0.5][..]));
+/// assert_eq!(store.trainable().len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct ParamStore<T> {
+    entries: BTreeMap<String, Entry<T>>,
+}
+
+impl<T> Default for ParamStore<T> {
+    fn default() -> Self {
+        ParamStore {
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> ParamStore<T> {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of registered names, counting tied names separately.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no parameters are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the registered names in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Registers `tensor` under `name`, returning a shared handle to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `name` is already registered.
+    pub fn insert(&mut self, name: &str, tensor: Tensor<T>) -> Result<Param<T>, TensorError> {
+        self.check_free(name)?;
+        let param = Arc::new(RwLock::new(tensor));
+        self.entries.insert(
+            name.to_string(),
+            Entry {
+                param: Arc::clone(&param),
+                frozen: false,
+            },
+        );
+        Ok(param)
+    }
+
+    /// Registers `name` as another name for the parameter `existing`, so both
+    /// share one tensor and one frozen flag.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `name` is already registered or
+    /// `existing` is not.
+    pub fn tie(&mut self, name: &str, existing: &str) -> Result<Param<T>, TensorError> {
+        self.check_free(name)?;
+        let entry = self.entry(existing)?;
+        let (param, frozen) = (Arc::clone(&entry.param), entry.frozen);
+        self.entries.insert(
+            name.to_string(),
+            Entry {
+                param: Arc::clone(&param),
+                frozen,
+            },
+        );
+        Ok(param)
+    }
+
+    /// Returns a shared handle to the parameter `name`.
+    pub fn get(&self, name: &str) -> Option<Param<T>> {
+        self.entries.get(name).map(|entry| Arc::clone(&entry.param))
+    }
+
+    /// Returns whether the parameter `name` is frozen, or `None` if it is not
+    /// registered.
+    pub fn is_frozen(&self, name: &str) -> Option<bool> {
+        self.entries.get(name).map(|entry| entry.frozen)
+    }
+
+    /// Excludes the parameter `name`, and every name tied to it, from training.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `name` is not registered.
+    pub fn freeze(&mut self, name: &str) -> Result<(), TensorError> {
+        self.set_frozen(name, true)
+    }
+
+    /// Makes the parameter `name`, and every name tied to it, trainable again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `name` is not registered.
+    pub fn unfreeze(&mut self, name: &str) -> Result<(), TensorError> {
+        self.set_frozen(name, false)
+    }
+
+    /// Returns the trainable parameters, each tied tensor once under its first
+    /// name in sorted order.
+    pub fn trainable(&self) -> Vec<(&str, Param<T>)> {
+        let mut seen: Vec<&Param<T>> = Vec::new();
+        let mut params = Vec::new();
+        for (name, entry) in &self.entries {
+            if entry.frozen || seen.iter().any(|p| Arc::ptr_eq(p, &entry.param)) {
+                continue;
+            }
+            seen.push(&entry.param);
+            params.push((name.as_str(), Arc::clone(&entry.param)));
+        }
+        params
+    }
+
+    fn entry(&self, name: &str) -> Result<&Entry<T>, TensorError> {
+        self.entries
+            .get(name)
+            .ok_or_else(|| TensorError::ValueError(format!("no parameter named '{}'", name)))
+    }
+
+    fn check_free(&self, name: &str) -> Result<(), TensorError> {
+        if self.entries.contains_key(name) {
+            return Err(TensorError::ValueError(format!(
+                "a parameter named '{}' is already registered",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    fn set_frozen(&mut self, name: &str, frozen: bool) -> Result<(), TensorError> {
+        let param = Arc::clone(&self.entry(name)?.param);
+        for entry in self.entries.values_mut() {
+            if Arc::ptr_eq(&entry.param, &param) {
+                entry.frozen = frozen;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: NpyElement> ParamStore<T> {
+    /// Saves every parameter to a `.npz` archive, one array per name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::IoError` if the file cannot be written.
+    pub fn save_npz<P: AsRef<Path>>(&self, path: P) -> Result<(), TensorError> {
+        let tensors: HashMap<String, Tensor<T>> = self
+            .entries
+            .iter()
+            .map(|(name, entry)| {
+                let tensor = entry.param.read().unwrap_or_else(PoisonError::into_inner);
+                (name.clone(), tensor.clone())
+            })
+            .collect();
+        write_npz(path, &tensors)
+    }
+
+    /// Loads every registered parameter in place from a `.npz` archive written
+    /// by [`ParamStore::save_npz`], keeping ties and handles intact. Arrays in
+    /// the archive that match no name are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`read_npz`], `TensorError::ValueError` if a
+    /// registered name is missing from the archive and
+    /// `TensorError::ShapeError` if an array does not have the shape of its
+    /// parameter. No parameter is changed on error.
+    pub fn load_npz<P: AsRef<Path>>(&mut self, path: P) -> Result<(), TensorError> {
+        let mut tensors = read_npz::<T, P>(path)?;
+        for (name, entry) in &self.entries {
+            let loaded = tensors.get(name).ok_or_else(|| {
+                TensorError::ValueError(format!("archive has no array named '{}'", name))
+            })?;
+            let current = entry.param.read().unwrap_or_else(PoisonError::into_inner);
+            if loaded.shape() != current.shape() {
+                return Err(TensorError::ShapeError(format!(
+                    "parameter '{}' has shape {:?} but the archive holds {:?}",
+                    name,
+                    current.shape(),
+                    loaded.shape()
+                )));
+            }
+        }
+        for (name, entry) in &self.entries {
+            if let Some(loaded) = tensors.remove(name) {
+                *entry.param.write().unwrap_or_else(PoisonError::into_inner) = loaded;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_param_store_ties_and_freezes() {
+        let mut store = ParamStore::new();
+        store
+            .insert(
+                "encoder.weight",
+                Tensor::new(vec![1.0f32; 4], vec![2, 2]).unwrap(),
+            )
+            .unwrap();
+        store
+            .insert(
+                "decoder.weight",
+                Tensor::new(vec![2.0f32; 4], vec![2, 2]).unwrap(),
+            )
+            .unwrap();
+        store.tie("head.weight", "encoder.weight").unwrap();
+
+        assert_eq!(store.len(), 3);
+        assert_eq!(
+            store.names().collect::<Vec<_>>(),
+            ["decoder.weight", "encoder.weight", "head.weight"]
+        );
+        let trainable: Vec<&str> = store
+            .trainable()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(trainable, ["decoder.weight", "encoder.weight"]);
+
+        // Freezing one tied name freezes the shared tensor.
+        store.freeze("head.weight").unwrap();
+        assert_eq!(store.is_frozen("encoder.weight"), Some(true));
+        assert_eq!(store.trainable().len(), 1);
+        store.unfreeze("encoder.weight").unwrap();
+        assert_eq!(store.is_frozen("head.weight"), Some(false));
+
+        assert!(matches!(
+            store.insert("decoder.weight", Tensor::new(vec![0.0], vec![1]).unwrap()),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(store.tie("x", "missing").is_err());
+        assert!(store.freeze("missing").is_err());
+        assert_eq!(store.is_frozen("missing"), None);
+    }
+
+    #[test]
+    fn test_param_store_npz_round_trip() {
+        let path = std::env::temp_dir().join("tiny_tensor_test_param_store.npz");
+        let mut source = ParamStore::new();
+        source
+            .insert("w", Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap())
+            .unwrap();
+        source.tie("w_tied", "w").unwrap();
+        source
+            .insert("b", Tensor::new(vec![0.5], vec![1]).unwrap())
+            .unwrap();
+        source.save_npz(&path).unwrap();
+
+        let mut target = ParamStore::new();
+        let w = target
+            .insert("w", Tensor::new(vec![0.0; 3], vec![3]).unwrap())
+            .unwrap();
+        target.tie("w_tied", "w").unwrap();
+        target
+            .insert("b", Tensor::new(vec![0.0], vec![1]).unwrap())
+            .unwrap();
+        target.load_npz(&path).unwrap();
+
+        // Existing handles see the loaded values, and the tie survives.
+        assert_eq!(w.read().unwrap().as_slice(), Some(&[1.0, 2.0, 3.0][..]));
+        let tied = target.get("w_tied").unwrap();
+        assert!(Arc::ptr_eq(&w, &tied));
+
+        let mut mismatched = ParamStore::new();
+        let b = mismatched
+            .insert("b", Tensor::new(vec![0.0; 2], vec![2]).unwrap())
+            .unwrap();
+        assert!(matches!(
+            mismatched.load_npz(&path),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert_eq!(b.read().unwrap().as_slice(), Some(&[0.0, 0.0][..]));
+        mismatched
+            .insert("extra", Tensor::new(vec![0.0], vec![1]).unwrap())
+            .unwrap();
+        assert!(mismatched.load_npz(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
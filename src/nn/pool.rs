@@ -0,0 +1,238 @@
+//! Max and average pooling over channel-first images.
+
+use crate::error::TensorError;
+use crate::nn::conv::window_count;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+impl<T: Copy> Tensor<T> {
+    /// Slides a `kernel`x`kernel` window over every channel of a `[C, H, W]`
+    /// image or `[N, C, H, W]` batch and reduces each window with `reduce`.
+    ///
+    /// `reduce` receives the window's elements inside the image, each with its
+    /// flat index `y * W + x` in its channel; padded positions are skipped.
+    fn pool<U, F>(
+        &self,
+        kernel: usize,
+        stride: usize,
+        padding: usize,
+        mut reduce: F,
+    ) -> Result<Tensor<U>, TensorError>
+    where
+        U: Copy,
+        F: FnMut(&[(usize, T)]) -> U,
+    {
+        if kernel == 0 || stride == 0 {
+            return Err(TensorError::ValueError(
+                "pooling kernel and stride must be positive".to_string(),
+            ));
+        }
+        if 2 * padding > kernel {
+            return Err(TensorError::ValueError(format!(
+                "pooling padding {} exceeds half the kernel size {}",
+                padding, kernel
+            )));
+        }
+        let (height, width) = match *self.shape() {
+            [_, h, w] | [_, _, h, w] => (h, w),
+            _ => {
+                return Err(TensorError::ShapeError(format!(
+                    "pooling input must have shape [C, H, W] or [N, C, H, W], got {:?}",
+                    self.shape()
+                )));
+            }
+        };
+        let (out_h, out_w) = match (
+            window_count(height, kernel, stride, padding),
+            window_count(width, kernel, stride, padding),
+        ) {
+            (Some(out_h), Some(out_w)) => (out_h, out_w),
+            _ => {
+                return Err(TensorError::ShapeError(format!(
+                    "pooling kernel of size {} does not fit a {}x{} image with padding {}",
+                    kernel, height, width, padding
+                )));
+            }
+        };
+
+        let input: Vec<T> = self.iter().copied().collect();
+        let planes = input.len() / (height * width).max(1);
+        let mut data = Vec::with_capacity(planes * out_h * out_w);
+        let mut window = Vec::with_capacity(kernel * kernel);
+        for p in 0..planes {
+            let plane = &input[p * height * width..(p + 1) * height * width];
+            for oy in 0..out_h {
+                for ox in 0..out_w {
+                    window.clear();
+                    let rows = (oy * stride).saturating_sub(padding)
+                        ..(oy * stride + kernel - padding).min(height);
+                    for y in rows {
+                        let columns = (ox * stride).saturating_sub(padding)
+                            ..(ox * stride + kernel - padding).min(width);
+                        window.extend(columns.map(|x| (y * width + x, plane[y * width + x])));
+                    }
+                    data.push(reduce(&window));
+                }
+            }
+        }
+
+        let mut shape = self.shape()[..self.ndim() - 2].to_vec();
+        shape.extend([out_h, out_w]);
+        Tensor::new(data, shape)
+    }
+}
+
+impl<T: Copy + PartialOrd> Tensor<T> {
+    /// Takes the maximum over every `kernel`x`kernel` window of a `[C, H, W]`
+    /// image or `[N, C, H, W]` batch, moving `stride` elements at a time.
+    ///
+    /// The image is padded by `padding` on every side; padded positions never
+    /// win. The output has shape `[.., H_out, W_out]` with
+    /// `H_out = (H + 2 * padding - kernel) / stride + 1` and likewise for
+    /// `W_out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `kernel` or `stride` is zero or
+    /// `padding` is more than half of `kernel`, and `TensorError::ShapeError` if
+    /// the input does not have three or four axes or the kernel is larger than
+    /// the padded image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let image = Tensor::new((0..16).collect(), vec![1, 4, 4]).unwrap();
+    ///
+    /// let pooled = image.max_pool2d(2, 2, 0).unwrap();
+    /// assert_eq!(pooled.shape(), &[1, 2, 2]);
+    /// assert_eq!(pooled.as_slice(), Some(&[5, 7, 13, 15][..]));
+    /// ```
+    pub fn max_pool2d(
+        &self,
+        kernel: usize,
+        stride: usize,
+        padding: usize,
+    ) -> Result<Tensor<T>, TensorError> {
+        Ok(self.max_pool2d_with_indices(kernel, stride, padding)?.0)
+    }
+
+    /// Like [`Tensor::max_pool2d`], but also returns where each maximum came
+    /// from, as the flat index `y * W + x` within its channel. The first
+    /// maximum in row-major order wins ties. The indices are what max
+    /// unpooling needs to put values back in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Tensor::max_pool2d`].
+    pub fn max_pool2d_with_indices(
+        &self,
+        kernel: usize,
+        stride: usize,
+        padding: usize,
+    ) -> Result<(Tensor<T>, Tensor<usize>), TensorError> {
+        let best = self.pool(kernel, stride, padding, |window| {
+            window[1..].iter().fold(window[0], |best, &candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            })
+        })?;
+        let shape = best.shape().to_vec();
+        let (indices, values) = best.iter().copied().unzip();
+        Ok((
+            Tensor::new(values, shape.clone())?,
+            Tensor::new(indices, shape)?,
+        ))
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Averages every `kernel`x`kernel` window of a `[C, H, W]` image or
+    /// `[N, C, H, W]` batch, moving `stride` elements at a time.
+    ///
+    /// The image is padded by `padding` on every side, and windows overlapping
+    /// the padding average only the elements inside the image, so borders are
+    /// not darkened. The output shape is that of [`Tensor::max_pool2d`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Tensor::max_pool2d`].
+    pub fn avg_pool2d(
+        &self,
+        kernel: usize,
+        stride: usize,
+        padding: usize,
+    ) -> Result<Tensor<T>, TensorError> {
+        self.pool(kernel, stride, padding, |window| {
+            window.iter().fold(T::zero(), |acc, &(_, x)| acc + x) / T::from_usize(window.len())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_pool2d_indices_and_padding() {
+        let image =
+            Tensor::new(vec![1, 9, 2, 3, 4, 5, 8, 6, 7, 0, 9, 1], vec![1, 1, 3, 4]).unwrap();
+
+        let (values, indices) = image.max_pool2d_with_indices(2, 2, 0).unwrap();
+        assert_eq!(values.shape(), &[1, 1, 1, 2]);
+        assert_eq!(values.as_slice(), Some(&[9, 8][..]));
+        assert_eq!(indices.as_slice(), Some(&[1, 6][..]));
+
+        // With padding the windows reach the last row and the edges.
+        let (values, indices) = image.max_pool2d_with_indices(3, 2, 1).unwrap();
+        assert_eq!(values.shape(), &[1, 1, 2, 2]);
+        assert_eq!(values.as_slice(), Some(&[9, 9, 7, 9][..]));
+        assert_eq!(indices.as_slice(), Some(&[1, 1, 8, 10][..]));
+        assert_eq!(image.max_pool2d(3, 2, 1).unwrap(), values);
+
+        // Channels are pooled independently.
+        let channels = Tensor::from_fn(vec![2, 2, 2], |i| (i[0] * 4 + i[1] * 2 + i[2]) as i32);
+        let (values, indices) = channels.max_pool2d_with_indices(2, 1, 0).unwrap();
+        assert_eq!(values.as_slice(), Some(&[3, 7][..]));
+        assert_eq!(indices.as_slice(), Some(&[3, 3][..]));
+    }
+
+    #[test]
+    fn test_avg_pool2d_and_errors() {
+        let image = Tensor::from_fn(vec![1, 4, 4], |i| (i[1] * 4 + i[2]) as f64);
+
+        let pooled = image.avg_pool2d(2, 2, 0).unwrap();
+        assert_eq!(pooled.as_slice(), Some(&[2.5, 4.5, 10.5, 12.5][..]));
+        // The padded corner window averages only the four real elements.
+        let padded = image.avg_pool2d(3, 3, 1).unwrap();
+        assert_eq!(padded.shape(), &[1, 2, 2]);
+        assert_eq!(padded.get(&[0, 0, 0]), Some(&2.5));
+        assert_eq!(padded.get(&[0, 1, 1]), Some(&12.5));
+
+        assert!(matches!(
+            image.avg_pool2d(0, 1, 0),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            image.avg_pool2d(2, 0, 0),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            image.max_pool2d(2, 1, 2),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            image.max_pool2d(5, 1, 0),
+            Err(TensorError::ShapeError(_))
+        ));
+        let flat = Tensor::new(vec![1.0; 4], vec![4]).unwrap();
+        assert!(matches!(
+            flat.avg_pool2d(1, 1, 0),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+}
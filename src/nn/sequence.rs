@@ -0,0 +1,173 @@
+//! Batching variable-length sequences into padded tensors and back.
+
+use crate::error::TensorError;
+use crate::num::Zero;
+use crate::ragged::RaggedTensor;
+use crate::tensor::Tensor;
+
+/// A padded batch with the length of every sequence and a validity mask, as
+/// returned by [`pack_padded`].
+pub type Padded<T> = (Tensor<T>, Tensor<usize>, Tensor<bool>);
+
+/// Pads `sequences` to a common length and stacks them into one batch.
+///
+/// Every sequence has shape `[len, ...]`, with lengths free to differ but the
+/// trailing feature axes shared. Returns the batch of shape
+/// `[batch, max_len, ...]` with zeros after the end of each sequence, the
+/// lengths of shape `[batch]` and a mask of shape `[batch, max_len]` that is
+/// `true` at valid steps. The batch is that of
+/// [`RaggedTensor::to_padded`] with zero padding.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `sequences` is empty and
+/// `TensorError::ShapeError` if a sequence has no axes or its feature axes
+/// differ from those of the first.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::nn::sequence::{pack_padded, unpack};
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+/// let b = Tensor::new(vec![4], vec![1]).unwrap();
+///
+/// let (padded, lengths, mask) = pack_padded(&[a.clone(), b.clone()]).unwrap();
+/// assert_eq!(padded.as_slice(), Some(&[1, 2, 3, 4, 0, 0][..]));
+/// assert_eq!(lengths.as_slice(), Some(&[3, 1][..]));
+/// assert_eq!(mask.as_slice(), Some(&[true, true, true, true, false, false][..]));
+/// assert_eq!(unpack(&padded, &lengths).unwrap(), vec![a, b]);
+/// ```
+pub fn pack_padded<T: Copy + Zero>(sequences: &[Tensor<T>]) -> Result<Padded<T>, TensorError> {
+    let ragged = RaggedTensor::from_named_rows(sequences, "sequence")?;
+    let padded = ragged.to_padded(T::zero());
+    let lengths = ragged.row_lengths();
+    let max_len = padded.shape()[1];
+    let mask = lengths
+        .iter()
+        .flat_map(|&len| (0..max_len).map(move |t| t < len))
+        .collect();
+
+    Ok((
+        padded,
+        Tensor::new(lengths, vec![sequences.len()])?,
+        Tensor::new(mask, vec![sequences.len(), max_len])?,
+    ))
+}
+
+/// Splits a padded batch of shape `[batch, max_len, ...]` back into one
+/// sequence of shape `[lengths[b], ...]` per row, dropping the padding. This
+/// reverses [`pack_padded`].
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `padded` has fewer than two axes or
+/// `lengths` is not one length per row, and `TensorError::ValueError` if a
+/// length exceeds `max_len`.
+pub fn unpack<T: Copy>(
+    padded: &Tensor<T>,
+    lengths: &Tensor<usize>,
+) -> Result<Vec<Tensor<T>>, TensorError> {
+    let (batch, max_len, features) = match padded.shape() {
+        [batch, max_len, features @ ..] => (*batch, *max_len, features),
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "padded batch must have shape [batch, max_len, ...], got {:?}",
+                shape
+            )));
+        }
+    };
+    if lengths.shape() != [batch] {
+        return Err(TensorError::ShapeError(format!(
+            "expected {} lengths, got shape {:?}",
+            batch,
+            lengths.shape()
+        )));
+    }
+    if let Some(&len) = lengths.iter().find(|&&len| len > max_len) {
+        return Err(TensorError::ValueError(format!(
+            "length {} exceeds the padded length {}",
+            len, max_len
+        )));
+    }
+
+    let step: usize = features.iter().product();
+    let values: Vec<T> = padded.iter().copied().collect();
+    let row_len = max_len * step;
+    lengths
+        .iter()
+        .enumerate()
+        .map(|(b, &len)| {
+            let mut shape = vec![len];
+            shape.extend_from_slice(features);
+            Tensor::new(
+                values[b * row_len..b * row_len + len * step].to_vec(),
+                shape,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_padded_feature_axes_round_trip() {
+        let a = Tensor::from_fn(vec![2, 2], |i| (i[0] * 2 + i[1]) as f32 + 1.0);
+        let b = Tensor::from_fn(vec![3, 2], |i| -((i[0] * 2 + i[1]) as f32) - 1.0);
+        let empty = Tensor::new(vec![], vec![0, 2]).unwrap();
+        let sequences = vec![a, empty, b];
+
+        let (padded, lengths, mask) = pack_padded(&sequences).unwrap();
+
+        assert_eq!(padded.shape(), &[3, 3, 2]);
+        assert_eq!(padded.get(&[0, 1, 1]), Some(&4.0));
+        assert_eq!(padded.get(&[0, 2, 0]), Some(&0.0));
+        assert_eq!(padded.get(&[1, 0, 0]), Some(&0.0));
+        assert_eq!(padded.get(&[2, 2, 1]), Some(&-6.0));
+        assert_eq!(lengths.as_slice(), Some(&[2, 0, 3][..]));
+        assert_eq!(mask.shape(), &[3, 3]);
+        assert_eq!(
+            mask.as_slice(),
+            Some(&[true, true, false, false, false, false, true, true, true][..])
+        );
+        assert_eq!(unpack(&padded, &lengths).unwrap(), sequences);
+        // A batch of empty sequences has no steps.
+        let empties = vec![Tensor::<f32>::new(vec![], vec![0, 2]).unwrap(); 2];
+        let (padded, lengths, _) = pack_padded(&empties).unwrap();
+        assert_eq!(padded.shape(), &[2, 0, 2]);
+        assert_eq!(unpack(&padded, &lengths).unwrap(), empties);
+    }
+
+    #[test]
+    fn test_pack_padded_and_unpack_errors() {
+        let a = Tensor::new(vec![1, 2], vec![1, 2]).unwrap();
+        let b = Tensor::new(vec![1, 2, 3], vec![1, 3]).unwrap();
+        assert!(matches!(
+            pack_padded::<i32>(&[]),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            pack_padded(&[a.clone(), b]),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            pack_padded(&[Tensor::new(vec![1], vec![]).unwrap()]),
+            Err(TensorError::ShapeError(_))
+        ));
+
+        let (padded, _, _) = pack_padded(&[a]).unwrap();
+        let too_long = Tensor::new(vec![2], vec![1]).unwrap();
+        assert!(matches!(
+            unpack(&padded, &too_long),
+            Err(TensorError::ValueError(_))
+        ));
+        let too_many = Tensor::new(vec![1, 1], vec![2]).unwrap();
+        assert!(matches!(
+            unpack(&padded, &too_many),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+}
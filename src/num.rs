@@ -0,0 +1,243 @@
+//! Numeric traits used to bound generic tensor operations.
+//!
+//! These are deliberately minimal stand-ins for the traits found in crates such as
+//! `num-traits`, so that the library keeps zero required dependencies.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Types that have an additive identity.
+pub trait Zero {
+    /// Returns the additive identity, `0`.
+    fn zero() -> Self;
+}
+
+/// Types that have a multiplicative identity.
+pub trait One {
+    /// Returns the multiplicative identity, `1`.
+    fn one() -> Self;
+}
+
+macro_rules! impl_identities {
+    ($zero:expr, $one:expr => $($t:ty),*) => {
+        $(
+            impl Zero for $t {
+                fn zero() -> Self {
+                    $zero
+                }
+            }
+
+            impl One for $t {
+                fn one() -> Self {
+                    $one
+                }
+            }
+        )*
+    };
+}
+
+impl_identities!(0, 1 => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_identities!(0.0, 1.0 => f32, f64);
+
+/// Types whose arithmetic progressions can be evaluated term by term.
+pub trait Progression: Sized {
+    /// Returns `start + step * n`, or `None` if the term is not representable.
+    ///
+    /// Integers compute the term exactly, so terms near the type's limits do
+    /// not overflow along the way; floats evaluate the product directly rather
+    /// than by repeated addition, so rounding error does not accumulate.
+    fn nth_term(start: Self, step: Self, n: usize) -> Option<Self>;
+}
+
+macro_rules! impl_progression_widened {
+    ($($t:ty),*) => {
+        $(
+            impl Progression for $t {
+                fn nth_term(start: Self, step: Self, n: usize) -> Option<Self> {
+                    let offset = i128::try_from(n).ok()?.checked_mul(step as i128)?;
+                    Self::try_from((start as i128).checked_add(offset)?).ok()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_progression_checked {
+    ($($t:ty),*) => {
+        $(
+            impl Progression for $t {
+                fn nth_term(start: Self, step: Self, n: usize) -> Option<Self> {
+                    start.checked_add(Self::try_from(n).ok()?.checked_mul(step)?)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_progression_float {
+    ($($t:ty),*) => {
+        $(
+            impl Progression for $t {
+                fn nth_term(start: Self, step: Self, n: usize) -> Option<Self> {
+                    Some(start + step * n as $t)
+                }
+            }
+        )*
+    };
+}
+
+impl_progression_widened!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_progression_checked!(i128, u128);
+impl_progression_float!(f32, f64);
+
+/// Floating-point element types.
+///
+/// Implemented for `f32` and `f64`; generic float operations are written against
+/// this trait so they work for both precisions.
+pub trait Float:
+    Copy
+    + PartialOrd
+    + Zero
+    + One
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Converts a `usize` to the nearest representable value.
+    fn from_usize(n: usize) -> Self;
+    /// Converts an `f64` to the nearest representable value.
+    fn from_f64(x: f64) -> Self;
+    /// Raises `self` to a floating-point power.
+    fn powf(self, n: Self) -> Self;
+    /// Returns the absolute value.
+    fn abs(self) -> Self;
+    /// Returns `true` if `self` is NaN.
+    fn is_nan(self) -> bool;
+    /// Returns `true` if `self` is neither infinite nor NaN.
+    fn is_finite(self) -> bool;
+    /// Returns `e^self`.
+    fn exp(self) -> Self;
+    /// Returns the natural logarithm.
+    fn ln(self) -> Self;
+    /// Returns the base-2 logarithm.
+    fn log2(self) -> Self;
+    /// Returns the base-10 logarithm.
+    fn log10(self) -> Self;
+    /// Returns the square root.
+    fn sqrt(self) -> Self;
+    /// Returns `1 / self`.
+    fn recip(self) -> Self;
+    /// Raises `self` to an integer power.
+    fn powi(self, n: i32) -> Self;
+    /// Returns the sine (in radians).
+    fn sin(self) -> Self;
+    /// Returns the cosine (in radians).
+    fn cos(self) -> Self;
+    /// Returns the tangent (in radians).
+    fn tan(self) -> Self;
+    /// Returns the arcsine, in radians.
+    fn asin(self) -> Self;
+    /// Returns the hyperbolic sine.
+    fn sinh(self) -> Self;
+    /// Returns the hyperbolic cosine.
+    fn cosh(self) -> Self;
+    /// Returns the hyperbolic tangent.
+    fn tanh(self) -> Self;
+    /// Returns the four-quadrant arctangent of `self` (y) and `other` (x), in radians.
+    fn atan2(self, other: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {
+        $(
+            impl Float for $t {
+                fn from_usize(n: usize) -> Self {
+                    n as $t
+                }
+
+                fn from_f64(x: f64) -> Self {
+                    x as $t
+                }
+
+                fn powf(self, n: Self) -> Self {
+                    <$t>::powf(self, n)
+                }
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+
+                fn is_finite(self) -> bool {
+                    <$t>::is_finite(self)
+                }
+
+                fn exp(self) -> Self {
+                    <$t>::exp(self)
+                }
+
+                fn ln(self) -> Self {
+                    <$t>::ln(self)
+                }
+
+                fn log2(self) -> Self {
+                    <$t>::log2(self)
+                }
+
+                fn log10(self) -> Self {
+                    <$t>::log10(self)
+                }
+
+                fn sqrt(self) -> Self {
+                    <$t>::sqrt(self)
+                }
+
+                fn recip(self) -> Self {
+                    <$t>::recip(self)
+                }
+
+                fn powi(self, n: i32) -> Self {
+                    <$t>::powi(self, n)
+                }
+
+                fn sin(self) -> Self {
+                    <$t>::sin(self)
+                }
+
+                fn cos(self) -> Self {
+                    <$t>::cos(self)
+                }
+
+                fn tan(self) -> Self {
+                    <$t>::tan(self)
+                }
+
+                fn asin(self) -> Self {
+                    <$t>::asin(self)
+                }
+
+                fn sinh(self) -> Self {
+                    <$t>::sinh(self)
+                }
+
+                fn cosh(self) -> Self {
+                    <$t>::cosh(self)
+                }
+
+                fn tanh(self) -> Self {
+                    <$t>::tanh(self)
+                }
+
+                fn atan2(self, other: Self) -> Self {
+                    <$t>::atan2(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
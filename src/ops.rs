@@ -0,0 +1,550 @@
+//! Elementwise arithmetic with NumPy-style broadcasting.
+
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::error::TensorError;
+use crate::num::{Float, One, Zero};
+use crate::tensor::{Offsets, Tensor};
+
+/// Computes the shape that `a` and `b` broadcast to.
+///
+/// Shapes are aligned at their trailing axes; each pair of lengths must be equal,
+/// or one of them must be 1.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if the shapes are incompatible.
+pub fn broadcast_shapes(a: &[usize], b: &[usize]) -> Result<Vec<usize>, TensorError> {
+    let ndim = a.len().max(b.len());
+    let mut shape = vec![0; ndim];
+    for i in 0..ndim {
+        let da = if i < ndim - a.len() {
+            1
+        } else {
+            a[i - (ndim - a.len())]
+        };
+        let db = if i < ndim - b.len() {
+            1
+        } else {
+            b[i - (ndim - b.len())]
+        };
+        shape[i] = match (da, db) {
+            (x, y) if x == y => x,
+            (1, y) => y,
+            (x, 1) => x,
+            _ => {
+                return Err(TensorError::ShapeError(format!(
+                    "shapes {:?} and {:?} cannot be broadcast together",
+                    a, b
+                )));
+            }
+        };
+    }
+
+    Ok(shape)
+}
+
+impl<T> Tensor<T> {
+    /// Returns strides that view `self` as a tensor of the larger `shape`, using a
+    /// stride of zero along every broadcast axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` cannot be broadcast to `shape`.
+    pub(crate) fn broadcast_strides(&self, shape: &[usize]) -> Result<Vec<usize>, TensorError> {
+        if self.shape.len() > shape.len() {
+            return Err(TensorError::ShapeError(format!(
+                "cannot broadcast shape {:?} to {:?}",
+                self.shape, shape
+            )));
+        }
+
+        let lead = shape.len() - self.shape.len();
+        let mut strides = vec![0; shape.len()];
+        for (axis, (&dim, &stride)) in self.shape.iter().zip(&self.strides).enumerate() {
+            if dim == shape[lead + axis] {
+                strides[lead + axis] = stride;
+            } else if dim != 1 {
+                return Err(TensorError::ShapeError(format!(
+                    "cannot broadcast shape {:?} to {:?}",
+                    self.shape, shape
+                )));
+            }
+        }
+
+        Ok(strides)
+    }
+}
+
+impl<T: Copy> Tensor<T> {
+    /// Combines `self` and `other` elementwise with `f`, broadcasting both to
+    /// their common shape.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the shapes cannot be broadcast together.
+    pub fn zip_with<U, V, F>(&self, other: &Tensor<U>, mut f: F) -> Result<Tensor<V>, TensorError>
+    where
+        U: Copy,
+        V: Copy,
+        F: FnMut(T, U) -> V,
+    {
+        let shape = broadcast_shapes(&self.shape, &other.shape)?;
+        let lhs_strides = self.broadcast_strides(&shape)?;
+        let rhs_strides = other.broadcast_strides(&shape)?;
+        let data = Offsets::new(&shape, &lhs_strides)
+            .zip(Offsets::new(&shape, &rhs_strides))
+            .map(|(i, j)| f(self.data[i], other.data[j]))
+            .collect();
+
+        Tensor::new(data, shape)
+    }
+
+    /// Updates every element of `self` with `f(element, other_element)`, where
+    /// `other` is broadcast to the shape of `self`.
+    ///
+    /// `other` can never alias `self`: tensors own their buffers, and the borrow
+    /// checker rejects passing a tensor as both arguments, so the update always
+    /// reads unmodified values of `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `other` does not broadcast to the
+    /// shape of `self`. Broadcasting never changes the shape of `self`.
+    pub fn zip_mut_with<U, F>(&mut self, other: &Tensor<U>, mut f: F) -> Result<(), TensorError>
+    where
+        U: Copy,
+        F: FnMut(T, U) -> T,
+    {
+        let other_strides = other.broadcast_strides(&self.shape)?;
+        let targets = Offsets::new(&self.shape, &self.strides);
+        let sources = Offsets::new(&self.shape, &other_strides);
+        for (target, source) in targets.zip(sources) {
+            self.data[target] = f(self.data[target], other.data[source]);
+        }
+
+        Ok(())
+    }
+
+    /// Returns a new tensor with `f` applied to every element.
+    pub fn map<U: Copy, F: FnMut(T) -> U>(&self, mut f: F) -> Tensor<U> {
+        let data = self.iter().map(|&x| f(x)).collect();
+        Tensor::new(data, self.shape.clone()).unwrap()
+    }
+
+    /// Applies `f` to every element in place.
+    pub fn map_inplace<F: FnMut(T) -> T>(&mut self, mut f: F) {
+        for value in &mut self.data {
+            *value = f(*value);
+        }
+    }
+}
+
+macro_rules! impl_assign_ops {
+    ($($trait:ident, $method:ident, $op:ident, $op_method:ident;)*) => {
+        $(
+            impl<T: Copy + $op<Output = T>> $trait<&Tensor<T>> for Tensor<T> {
+                /// Applies the operation elementwise, broadcasting `rhs` to the shape of `self`.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `rhs` does not broadcast to the shape of `self`.
+                fn $method(&mut self, rhs: &Tensor<T>) {
+                    if let Err(err) = self.zip_mut_with(rhs, |a, b| a.$op_method(b)) {
+                        panic!("{}", err);
+                    }
+                }
+            }
+
+            impl<T: Copy + $op<Output = T>> $trait<T> for Tensor<T> {
+                /// Applies the operation between every element and the scalar `rhs`.
+                fn $method(&mut self, rhs: T) {
+                    self.map_inplace(|a| a.$op_method(rhs));
+                }
+            }
+        )*
+    };
+}
+
+impl_assign_ops! {
+    AddAssign, add_assign, Add, add;
+    SubAssign, sub_assign, Sub, sub;
+    MulAssign, mul_assign, Mul, mul;
+    DivAssign, div_assign, Div, div;
+}
+
+impl<T: Copy + Neg<Output = T>> Neg for Tensor<T> {
+    type Output = Tensor<T>;
+
+    /// Negates every element.
+    fn neg(mut self) -> Tensor<T> {
+        self.map_inplace(|x| -x);
+        self
+    }
+}
+
+impl<T: Copy + Neg<Output = T>> Neg for &Tensor<T> {
+    type Output = Tensor<T>;
+
+    /// Returns a new tensor with every element negated.
+    fn neg(self) -> Tensor<T> {
+        self.map(|x| -x)
+    }
+}
+
+impl<T: Copy + PartialOrd + Zero + Neg<Output = T>> Tensor<T> {
+    /// Returns a new tensor with the absolute value of every element.
+    ///
+    /// Negative zero maps to positive zero, as with `f64::abs`.
+    pub fn abs(&self) -> Tensor<T> {
+        self.map(|x| match x.partial_cmp(&T::zero()) {
+            Some(Ordering::Less) => -x,
+            Some(Ordering::Equal) => T::zero(),
+            _ => x,
+        })
+    }
+}
+
+impl<T: Copy + PartialOrd + Zero + One + Neg<Output = T>> Tensor<T> {
+    /// Returns a new tensor holding `1` for positive elements, `-1` for negative
+    /// elements and `0` for zeros. NaN elements stay NaN.
+    ///
+    /// Unlike `f64::signum`, zero maps to zero, matching NumPy's `sign`.
+    pub fn signum(&self) -> Tensor<T> {
+        self.map(|x| match x.partial_cmp(&T::zero()) {
+            Some(Ordering::Greater) => T::one(),
+            Some(Ordering::Less) => -T::one(),
+            _ => x,
+        })
+    }
+}
+
+/// Returns the smaller of `a` and `b`, or whichever is NaN if they are unordered.
+fn partial_min<T: PartialOrd>(a: T, b: T) -> T {
+    match a.partial_cmp(&b) {
+        Some(Ordering::Greater) => b,
+        Some(_) => a,
+        None if a.partial_cmp(&a).is_none() => a,
+        None => b,
+    }
+}
+
+/// Returns the larger of `a` and `b`, or whichever is NaN if they are unordered.
+fn partial_max<T: PartialOrd>(a: T, b: T) -> T {
+    match a.partial_cmp(&b) {
+        Some(Ordering::Less) => b,
+        Some(_) => a,
+        None if a.partial_cmp(&a).is_none() => a,
+        None => b,
+    }
+}
+
+impl<T: Copy + PartialOrd> Tensor<T> {
+    /// Returns a new tensor with every element limited to the interval `[lo, hi]`.
+    ///
+    /// NaN elements are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `lo > hi` or either bound is NaN.
+    pub fn clamp(&self, lo: T, hi: T) -> Result<Tensor<T>, TensorError> {
+        check_bounds(lo, hi)?;
+        Ok(self.map(|x| partial_min(partial_max(x, lo), hi)))
+    }
+
+    /// Limits every element to the interval `[lo, hi]` in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `lo > hi` or either bound is NaN.
+    pub fn clamp_mut(&mut self, lo: T, hi: T) -> Result<(), TensorError> {
+        check_bounds(lo, hi)?;
+        self.map_inplace(|x| partial_min(partial_max(x, lo), hi));
+        Ok(())
+    }
+
+    /// Returns the elementwise minimum of `self` and `other`, broadcasting both
+    /// to their common shape. NaN is propagated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the shapes cannot be broadcast together.
+    pub fn minimum(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        self.zip_with(other, partial_min)
+    }
+
+    /// Returns the elementwise maximum of `self` and `other`, broadcasting both
+    /// to their common shape. NaN is propagated.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the shapes cannot be broadcast together.
+    pub fn maximum(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        self.zip_with(other, partial_max)
+    }
+
+    /// Replaces every element with the minimum of itself and the broadcast `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `other` cannot be broadcast to the shape of `self`.
+    pub fn minimum_mut(&mut self, other: &Tensor<T>) -> Result<(), TensorError> {
+        self.zip_mut_with(other, partial_min)
+    }
+
+    /// Replaces every element with the maximum of itself and the broadcast `other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `other` cannot be broadcast to the shape of `self`.
+    pub fn maximum_mut(&mut self, other: &Tensor<T>) -> Result<(), TensorError> {
+        self.zip_mut_with(other, partial_max)
+    }
+}
+
+/// Checks that `[lo, hi]` is a valid clamping interval.
+fn check_bounds<T: PartialOrd>(lo: T, hi: T) -> Result<(), TensorError> {
+    match lo.partial_cmp(&hi) {
+        Some(Ordering::Less | Ordering::Equal) => Ok(()),
+        _ => Err(TensorError::ValueError(
+            "clamp requires lo <= hi and neither bound to be NaN".to_string(),
+        )),
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Zero> Tensor<T> {
+    /// Returns the elementwise sum of all `tensors`, broadcast to their common
+    /// shape.
+    ///
+    /// Every input is accumulated into a single output buffer, so no
+    /// intermediate tensors are allocated as with a chain of binary additions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `tensors` is empty and
+    /// `TensorError::ShapeError` if the shapes cannot be broadcast together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let a = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+    /// let b = Tensor::new(vec![10, 20, 30], vec![3]).unwrap();
+    /// let bias = Tensor::new(vec![100], vec![1]).unwrap();
+    /// let sum = Tensor::add_n(&[&a, &b, &bias]).unwrap();
+    /// assert_eq!(sum.as_slice(), Some(&[111, 122, 133][..]));
+    /// ```
+    pub fn add_n(tensors: &[&Tensor<T>]) -> Result<Tensor<T>, TensorError> {
+        let shape = tensors.iter().try_fold(
+            tensors
+                .first()
+                .ok_or_else(|| {
+                    TensorError::ValueError("add_n needs at least one tensor".to_string())
+                })?
+                .shape
+                .clone(),
+            |shape, tensor| broadcast_shapes(&shape, &tensor.shape),
+        )?;
+        let strides = tensors
+            .iter()
+            .map(|tensor| tensor.broadcast_strides(&shape))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut data: Vec<T> = vec![T::zero(); shape.iter().product()];
+        // Accumulate one input at a time so each is read in a single sweep.
+        for (tensor, strides) in tensors.iter().zip(&strides) {
+            for (acc, offset) in data.iter_mut().zip(Offsets::new(&shape, strides)) {
+                *acc = *acc + tensor.data[offset];
+            }
+        }
+        Tensor::new(data, shape)
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns the elementwise mean of all `tensors`, broadcast to their
+    /// common shape, computed like [`Tensor::add_n`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `tensors` is empty and
+    /// `TensorError::ShapeError` if the shapes cannot be broadcast together.
+    pub fn mean_n(tensors: &[&Tensor<T>]) -> Result<Tensor<T>, TensorError> {
+        let mut sum = Tensor::add_n(tensors)?;
+        let count = T::from_usize(tensors.len());
+        sum.map_inplace(|x| x / count);
+        Ok(sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_n_and_mean_n_broadcast() {
+        let a = Tensor::from_fn(vec![2, 3], |i| (i[0] * 3 + i[1]) as f64);
+        let row = Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap();
+        let column = Tensor::new(vec![10.0, 20.0], vec![2, 1]).unwrap();
+
+        let sum = Tensor::add_n(&[&a, &row, &column]).unwrap();
+        let mean = Tensor::mean_n(&[&a, &row, &column]).unwrap();
+
+        let expected = a
+            .zip_with(&row, |x, y| x + y)
+            .unwrap()
+            .zip_with(&column, |x, y| x + y)
+            .unwrap();
+        assert_eq!(sum, expected);
+        assert_eq!(mean.shape(), &[2, 3]);
+        assert_eq!(mean.get(&[1, 2]), Some(&((5.0 + 3.0 + 20.0) / 3.0)));
+        // Strided inputs are read in logical order.
+        let t = a.permute(&[1, 0]).unwrap();
+        assert_eq!(Tensor::add_n(&[&t]).unwrap(), t.to_contiguous());
+    }
+
+    #[test]
+    fn test_add_n_errors() {
+        assert!(matches!(
+            Tensor::<i32>::add_n(&[]),
+            Err(TensorError::ValueError(_))
+        ));
+        let a = Tensor::new(vec![1, 2], vec![2]).unwrap();
+        let b = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+        assert!(matches!(
+            Tensor::add_n(&[&a, &b]),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(Tensor::<f64>::mean_n(&[]).is_err());
+    }
+
+    #[test]
+    fn test_broadcast_shapes() {
+        assert_eq!(
+            broadcast_shapes(&[2, 1, 4], &[3, 1]).unwrap(),
+            vec![2, 3, 4]
+        );
+        assert_eq!(broadcast_shapes(&[], &[2, 2]).unwrap(), vec![2, 2]);
+        assert!(matches!(
+            broadcast_shapes(&[2, 3], &[4]),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_zip_with_broadcasts_both_sides() {
+        let column = Tensor::new(vec![1, 2], vec![2, 1]).unwrap();
+        let row = Tensor::new(vec![10, 20, 30], vec![3]).unwrap();
+
+        let result = column.zip_with(&row, |a, b| a * b).unwrap();
+
+        assert_eq!(result.shape, &[2, 3]);
+        assert_eq!(result.data, vec![10, 20, 30, 20, 40, 60]);
+    }
+
+    #[test]
+    fn test_add_assign_broadcast_row() {
+        let mut tensor = Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![2, 3]).unwrap();
+        let row = Tensor::new(vec![10, 20, 30], vec![3]).unwrap();
+        let column = Tensor::new(vec![100, 200], vec![2, 1]).unwrap();
+
+        tensor += &row;
+        tensor -= &column;
+
+        assert_eq!(tensor.data, vec![-89, -78, -67, -186, -175, -164]);
+    }
+
+    #[test]
+    fn test_assign_ops_respect_strides() {
+        let mut transposed = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+        let row = Tensor::new(vec![1.0, 10.0], vec![2]).unwrap();
+
+        transposed *= &row;
+        transposed /= 2.0;
+
+        assert_eq!(
+            transposed.iter().copied().collect::<Vec<_>>(),
+            vec![0.5, 15.0, 1.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn test_zip_mut_with_rejects_growing_self() {
+        let mut row = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+        let matrix = Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![2, 3]).unwrap();
+
+        let result = row.zip_mut_with(&matrix, |a, b| a + b);
+
+        assert!(matches!(result, Err(TensorError::ShapeError(_))));
+        assert_eq!(row.data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot broadcast")]
+    fn test_add_assign_incompatible_panics() {
+        let mut tensor = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+        tensor += &Tensor::new(vec![1, 2], vec![2]).unwrap();
+    }
+
+    #[test]
+    fn test_clamp() {
+        let mut tensor = Tensor::new(vec![-2.0, 0.5, 3.0, f64::NAN], vec![2, 2]).unwrap();
+
+        let result = tensor.clamp(0.0, 1.0).unwrap();
+        assert_eq!(&result.as_slice().unwrap()[..3], &[0.0, 0.5, 1.0]);
+        assert!(result.as_slice().unwrap()[3].is_nan());
+        assert!(tensor.clamp(1.0, 0.0).is_err());
+        assert!(tensor.clamp(f64::NAN, 0.0).is_err());
+
+        tensor.clamp_mut(-1.0, 2.0).unwrap();
+        assert_eq!(&tensor.as_slice().unwrap()[..3], &[-1.0, 0.5, 2.0]);
+    }
+
+    #[test]
+    fn test_minimum_and_maximum_broadcast() {
+        let mut a = Tensor::new(vec![1, 5, 3, 7], vec![2, 2]).unwrap();
+        let b = Tensor::new(vec![4, 4], vec![2]).unwrap();
+
+        assert_eq!(a.minimum(&b).unwrap().data, vec![1, 4, 3, 4]);
+        assert_eq!(a.maximum(&b).unwrap().data, vec![4, 5, 4, 7]);
+
+        a.maximum_mut(&b).unwrap();
+        assert_eq!(a.data, vec![4, 5, 4, 7]);
+        a.minimum_mut(&Tensor::new(vec![6], vec![1]).unwrap())
+            .unwrap();
+        assert_eq!(a.data, vec![4, 5, 4, 6]);
+        let mut small = b.clone();
+        assert!(small.minimum_mut(&a).is_err());
+    }
+
+    #[test]
+    fn test_minimum_propagates_nan() {
+        let a = Tensor::new(vec![f64::NAN, 1.0], vec![2]).unwrap();
+        let b = Tensor::new(vec![0.0, f64::NAN], vec![2]).unwrap();
+
+        assert!(a.minimum(&b).unwrap().data.iter().all(|x| x.is_nan()));
+        assert!(b.maximum(&a).unwrap().data.iter().all(|x| x.is_nan()));
+    }
+
+    #[test]
+    fn test_neg_abs_and_signum() {
+        let tensor = Tensor::new(vec![-3, 0, 2, -1], vec![2, 2]).unwrap();
+
+        assert_eq!((-&tensor).data, vec![3, 0, -2, 1]);
+        assert_eq!(tensor.abs().data, vec![3, 0, 2, 1]);
+        assert_eq!(tensor.signum().data, vec![-1, 0, 1, -1]);
+        assert_eq!((-tensor.clone()).shape, tensor.shape);
+
+        let floats = Tensor::new(vec![-0.5, 0.0, f64::NAN], vec![3]).unwrap();
+        let signs = floats.signum();
+        assert_eq!(&signs.data[..2], &[-1.0, 0.0]);
+        assert!(signs.data[2].is_nan());
+        assert_eq!(floats.abs().data[0], 0.5);
+        let negative_zero = Tensor::new(vec![-0.0f64], vec![1]).unwrap();
+        assert!(negative_zero.abs().data[0].is_sign_positive());
+    }
+}
@@ -0,0 +1,203 @@
+//! Combinatorial optimization over cost matrices.
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Solves the linear sum assignment problem for the 2D `cost` matrix.
+///
+/// Each row is matched to at most one column and vice versa so that
+/// `min(rows, cols)` pairs are formed with the smallest total cost, as in
+/// SciPy's `linear_sum_assignment`. Returns the matched row indices, in
+/// increasing order, and the column matched to each of them.
+///
+/// Uses the Hungarian algorithm with row and column potentials, which takes
+/// `O(n^2 m)` time for `n <= m` rows and columns.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `cost` is not 2D and
+/// `TensorError::ValueError` if it has a NaN or infinite entry.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::optimize::linear_sum_assignment;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let cost = Tensor::new(vec![4.0, 1.0, 3.0, 2.0, 0.0, 5.0, 3.0, 2.0, 2.0], vec![3, 3]).unwrap();
+/// let (rows, cols) = linear_sum_assignment(&cost).unwrap();
+/// assert_eq!(rows.as_slice(), Some(&[0, 1, 2][..]));
+/// assert_eq!(cols.as_slice(), Some(&[1, 0, 2][..]));
+/// ```
+pub fn linear_sum_assignment<T: Float>(
+    cost: &Tensor<T>,
+) -> Result<(Tensor<usize>, Tensor<usize>), TensorError> {
+    let (rows, cols) = match cost.shape() {
+        [rows, cols] => (*rows, *cols),
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "cost matrix must be 2D, got shape {:?}",
+                shape
+            )));
+        }
+    };
+    let infinity = T::from_f64(f64::INFINITY);
+    if cost.iter().any(|&c| c.is_nan() || c.abs() == infinity) {
+        return Err(TensorError::ValueError(
+            "cost matrix entries must be finite".to_string(),
+        ));
+    }
+
+    // The algorithm below assigns every row, so it needs rows <= cols; solve
+    // the transposed problem otherwise.
+    let transposed = rows > cols;
+    let matrix = if transposed {
+        cost.permute(&[1, 0])?
+    } else {
+        cost.clone()
+    };
+    let (n, m) = (rows.min(cols), rows.max(cols));
+    let a: Vec<T> = matrix.iter().copied().collect();
+    let at = |i: usize, j: usize| a[(i - 1) * m + (j - 1)];
+
+    // One-based potentials and matching; column 0 is a virtual start column.
+    let mut u = vec![T::zero(); n + 1];
+    let mut v = vec![T::zero(); m + 1];
+    let mut matched_row = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+    for i in 1..=n {
+        matched_row[0] = i;
+        let mut j0 = 0;
+        let mut min_slack = vec![infinity; m + 1];
+        let mut used = vec![false; m + 1];
+        // Grow a shortest augmenting path from row `i` until it reaches a free column.
+        loop {
+            used[j0] = true;
+            let i0 = matched_row[j0];
+            let mut delta = infinity;
+            let mut j1 = 0;
+            for j in (1..=m).filter(|&j| !used[j]) {
+                let slack = at(i0, j) - u[i0] - v[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    way[j] = j0;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[matched_row[j]] = u[matched_row[j]] + delta;
+                    v[j] = v[j] - delta;
+                } else {
+                    min_slack[j] = min_slack[j] - delta;
+                }
+            }
+            j0 = j1;
+            if matched_row[j0] == 0 {
+                break;
+            }
+        }
+        // Flip the matching along the path back to the start column.
+        while j0 != 0 {
+            let j1 = way[j0];
+            matched_row[j0] = matched_row[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = (1..=m)
+        .filter(|&j| matched_row[j] != 0)
+        .map(|j| {
+            let (i, j) = (matched_row[j] - 1, j - 1);
+            if transposed { (j, i) } else { (i, j) }
+        })
+        .collect();
+    pairs.sort_unstable();
+
+    let (row_indices, col_indices) = pairs.into_iter().unzip();
+    Ok((
+        Tensor::new(row_indices, vec![n])?,
+        Tensor::new(col_indices, vec![n])?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the smallest total cost over every assignment of rows to
+    /// distinct columns, by brute force.
+    fn brute_force(cost: &[f64], rows: usize, cols: usize) -> f64 {
+        fn search(cost: &[f64], cols: usize, row: usize, rows: usize, used: &mut [bool]) -> f64 {
+            if row == rows {
+                return 0.0;
+            }
+            let mut best = f64::INFINITY;
+            for j in 0..cols {
+                if !used[j] {
+                    used[j] = true;
+                    best = best.min(cost[row * cols + j] + search(cost, cols, row + 1, rows, used));
+                    used[j] = false;
+                }
+            }
+            best
+        }
+        search(cost, cols, 0, rows, &mut vec![false; cols])
+    }
+
+    #[test]
+    fn test_linear_sum_assignment_matches_brute_force() {
+        for (rows, cols) in [(4, 4), (3, 5), (5, 3)] {
+            let cost = Tensor::from_fn(vec![rows, cols], |i| ((i[0] * 7 + i[1] * 13) % 10) as f64);
+
+            let (row_ind, col_ind) = linear_sum_assignment(&cost).unwrap();
+
+            assert_eq!(row_ind.len(), rows.min(cols));
+            let total: f64 = row_ind
+                .iter()
+                .zip(col_ind.iter())
+                .map(|(&i, &j)| cost.get(&[i, j]).unwrap())
+                .sum();
+            let data: Vec<f64> = cost.iter().copied().collect();
+            let expected = if rows <= cols {
+                brute_force(&data, rows, cols)
+            } else {
+                let t: Vec<f64> = cost.permute(&[1, 0]).unwrap().iter().copied().collect();
+                brute_force(&t, cols, rows)
+            };
+            assert_eq!(total, expected);
+            assert!(
+                row_ind
+                    .iter()
+                    .zip(row_ind.iter().skip(1))
+                    .all(|(a, b)| a < b)
+            );
+        }
+    }
+
+    #[test]
+    fn test_linear_sum_assignment_edge_cases() {
+        let tall = Tensor::new(vec![5.0, 1.0, 2.0], vec![3, 1]).unwrap();
+        let (rows, cols) = linear_sum_assignment(&tall).unwrap();
+        assert_eq!(rows.as_slice(), Some(&[1][..]));
+        assert_eq!(cols.as_slice(), Some(&[0][..]));
+
+        let empty = Tensor::<f64>::new(vec![], vec![0, 3]).unwrap();
+        assert_eq!(linear_sum_assignment(&empty).unwrap().0.len(), 0);
+
+        let negative = Tensor::new(vec![-1.0, -5.0, -3.0, -2.0], vec![2, 2]).unwrap();
+        assert_eq!(
+            linear_sum_assignment(&negative).unwrap().1.as_slice(),
+            Some(&[1, 0][..])
+        );
+        assert!(matches!(
+            linear_sum_assignment(&Tensor::new(vec![f64::NAN], vec![1, 1]).unwrap()),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(linear_sum_assignment(&Tensor::new(vec![1.0], vec![1]).unwrap()).is_err());
+    }
+}
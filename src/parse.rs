@@ -0,0 +1,203 @@
+//! Parsing tensors from their bracketed text representation.
+
+use std::str::FromStr;
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+impl<T: FromStr + Copy> Tensor<T> {
+    /// Parses a tensor from the bracketed format produced by `Display` and
+    /// [`Tensor::to_string_precise`], e.g. `[[1, 2], [3, 4]]`.
+    ///
+    /// Whitespace (including newlines) is ignored and a trailing comma is allowed
+    /// in each list. A bare value without brackets parses as a zero-dimensional
+    /// tensor, and `[]` parses as an empty 1D tensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ParseError` for malformed text, elements that fail
+    /// to parse as `T` or lists nested more than 64 levels deep, and `TensorError::ShapeError` for ragged nesting.
+    pub fn parse(text: &str) -> Result<Self, TensorError> {
+        let mut parser = Parser {
+            text,
+            pos: 0,
+            depth: 0,
+        };
+        let (shape, data) = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.pos != text.len() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+
+        Tensor::new(data, shape)
+    }
+}
+
+/// The deepest nesting of lists accepted, which bounds the recursion so that
+/// hostile input cannot exhaust the stack.
+const MAX_DEPTH: usize = 64;
+
+/// A recursive-descent parser over nested bracketed lists.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn error(&self, msg: &str) -> TensorError {
+        TensorError::ParseError(format!("{} at byte {}", msg, self.pos))
+    }
+
+    /// Parses either a bracketed list or a single element, returning its shape
+    /// and row-major data.
+    fn parse_value<T: FromStr>(&mut self) -> Result<(Vec<usize>, Vec<T>), TensorError> {
+        self.skip_whitespace();
+        if self.peek() == Some('[') {
+            if self.depth == MAX_DEPTH {
+                return Err(self.error(&format!("lists nest deeper than {} levels", MAX_DEPTH)));
+            }
+            self.pos += 1;
+            self.depth += 1;
+            let list = self.parse_list();
+            self.depth -= 1;
+            list
+        } else {
+            Ok((vec![], vec![self.parse_element()?]))
+        }
+    }
+
+    /// Parses the items of a list whose opening bracket was already consumed.
+    fn parse_list<T: FromStr>(&mut self) -> Result<(Vec<usize>, Vec<T>), TensorError> {
+        let mut inner_shape: Option<Vec<usize>> = None;
+        let mut data = Vec::new();
+        let mut count = 0;
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                break;
+            }
+
+            let (shape, values) = self.parse_value()?;
+            match &inner_shape {
+                Some(expected) if *expected != shape => {
+                    return Err(TensorError::ShapeError(format!(
+                        "ragged input: expected an item of shape {:?}, found {:?}",
+                        expected, shape
+                    )));
+                }
+                Some(_) => {}
+                None => inner_shape = Some(shape),
+            }
+            data.extend(values);
+            count += 1;
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+
+        let mut shape = vec![count];
+        shape.extend(inner_shape.unwrap_or_default());
+        Ok((shape, data))
+    }
+
+    fn parse_element<T: FromStr>(&mut self) -> Result<T, TensorError> {
+        let rest = &self.text[self.pos..];
+        let len = rest
+            .find(|c: char| c == ',' || c == '[' || c == ']' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected an element"));
+        }
+
+        let token = &rest[..len];
+        let value = token
+            .parse()
+            .map_err(|_| self.error(&format!("invalid element '{}'", token)))?;
+        self.pos += len;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested() {
+        let result = Tensor::<i32>::parse("[[1, 2, 3],\n [4, 5, 6],]").unwrap();
+
+        assert_eq!(result.shape(), &[2, 3]);
+        assert_eq!(result.as_slice(), Some(&[1, 2, 3, 4, 5, 6][..]));
+    }
+
+    #[test]
+    fn test_parse_scalar_and_empty() {
+        let scalar = Tensor::<f64>::parse(" 2.5 ").unwrap();
+        let empty = Tensor::<f64>::parse("[]").unwrap();
+
+        assert_eq!(scalar.shape(), &[] as &[usize]);
+        assert_eq!(scalar.as_slice(), Some(&[2.5][..]));
+        assert_eq!(empty.shape(), &[0]);
+    }
+
+    #[test]
+    fn test_precise_round_trip() {
+        let tensor = Tensor::new(vec![0.1, 1.0 / 3.0, -2e-300, 7.0], vec![2, 1, 2]).unwrap();
+
+        let text = tensor.to_string_precise();
+        let restored = Tensor::<f64>::parse(&text).unwrap();
+
+        assert_eq!(restored, tensor);
+        assert_eq!(text, tensor.to_string());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Tensor::<i32>::parse("[[1, 2], [3]]"),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            Tensor::<i32>::parse("[1, x]"),
+            Err(TensorError::ParseError(_))
+        ));
+        assert!(matches!(
+            Tensor::<i32>::parse("[1 2]"),
+            Err(TensorError::ParseError(_))
+        ));
+        assert!(matches!(
+            Tensor::<i32>::parse("[1, 2"),
+            Err(TensorError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_deep_nesting() {
+        let deep = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+        assert!(matches!(
+            Tensor::<f64>::parse(&deep),
+            Err(TensorError::ParseError(_))
+        ));
+
+        let limit = format!("{}1{}", "[".repeat(64), "]".repeat(64));
+        assert_eq!(Tensor::<f64>::parse(&limit).unwrap().ndim(), 64);
+        let beyond = format!("[{}]", limit);
+        assert!(Tensor::<f64>::parse(&beyond).is_err());
+    }
+}
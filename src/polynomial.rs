@@ -0,0 +1,152 @@
+//! Polynomial basis matrices: Vandermonde, Chebyshev and Legendre.
+//!
+//! Each builder evaluates a family of polynomials at every point of a 1D tensor
+//! and returns a `[points, terms]` matrix, one row per point, ready to be used as
+//! a design matrix for least-squares fitting.
+
+use std::ops::Mul;
+
+use crate::creation::vector_elements;
+use crate::error::TensorError;
+use crate::num::{Float, One};
+use crate::tensor::Tensor;
+
+/// Creates the Vandermonde matrix of `x` with `n` columns.
+///
+/// Column `j` holds `x^(n - 1 - j)`, so powers decrease from left to right as in
+/// NumPy; pass `increasing = true` to start at `x^0` instead.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `x` is not 1-dimensional.
+pub fn vander<T>(x: &Tensor<T>, n: usize, increasing: bool) -> Result<Tensor<T>, TensorError>
+where
+    T: One + Copy + Mul<Output = T>,
+{
+    let x = vector_elements(x, "vander input")?;
+    let mut data = Vec::with_capacity(x.len() * n);
+    for &value in &x {
+        let start = data.len();
+        if n > 0 {
+            let mut power = T::one();
+            data.push(power);
+            // Only powers that become columns are computed, so the highest
+            // one never overflows needlessly.
+            for _ in 1..n {
+                power = power * value;
+                data.push(power);
+            }
+        }
+        if !increasing {
+            data[start..].reverse();
+        }
+    }
+    Tensor::new(data, vec![x.len(), n])
+}
+
+/// Evaluates Chebyshev polynomials of the first kind `T_0` through `T_degree`
+/// at every point of `x`, returning a `[x.len(), degree + 1]` matrix.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `x` is not 1-dimensional.
+pub fn chebyshev<T: Float>(x: &Tensor<T>, degree: usize) -> Result<Tensor<T>, TensorError> {
+    let two = T::from_usize(2);
+    basis(x, degree, "chebyshev input", |_, x, previous, current| {
+        two * x * current - previous
+    })
+}
+
+/// Evaluates Legendre polynomials `P_0` through `P_degree` at every point of
+/// `x`, returning a `[x.len(), degree + 1]` matrix.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `x` is not 1-dimensional.
+pub fn legendre<T: Float>(x: &Tensor<T>, degree: usize) -> Result<Tensor<T>, TensorError> {
+    basis(x, degree, "legendre input", |k, x, previous, current| {
+        let k = T::from_usize(k);
+        ((k + k + T::one()) * x * current - k * previous) / (k + T::one())
+    })
+}
+
+/// Builds a basis from `P_0 = 1`, `P_1 = x` and a three-term recurrence giving
+/// `P_(k+1)` from `k`, `x`, `P_(k-1)` and `P_k`.
+fn basis<T, F>(x: &Tensor<T>, degree: usize, what: &str, next: F) -> Result<Tensor<T>, TensorError>
+where
+    T: Float,
+    F: Fn(usize, T, T, T) -> T,
+{
+    let x = vector_elements(x, what)?;
+    let terms = degree + 1;
+    let mut data = Vec::with_capacity(x.len() * terms);
+    for &value in &x {
+        let (mut previous, mut current) = (T::one(), value);
+        data.push(previous);
+        for k in 1..terms {
+            data.push(current);
+            (previous, current) = (current, next(k, value, previous, current));
+        }
+    }
+    Tensor::new(data, vec![x.len(), terms])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vander() {
+        let x = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+
+        let result = vander(&x, 3, false).unwrap();
+        assert_eq!(result.shape(), &[3, 3]);
+        assert_eq!(result.as_slice(), Some(&[1, 1, 1, 4, 2, 1, 9, 3, 1][..]));
+
+        let result = vander(&x, 2, true).unwrap();
+        assert_eq!(result.as_slice(), Some(&[1, 1, 1, 2, 1, 3][..]));
+
+        let matrix = Tensor::new(vec![1, 2], vec![1, 2]).unwrap();
+        assert!(vander(&matrix, 2, true).is_err());
+    }
+
+    #[test]
+    fn test_vander_integer_limit() {
+        let x = Tensor::new(vec![2u8], vec![1]).unwrap();
+
+        let result = vander(&x, 8, true).unwrap();
+        assert_eq!(result.as_slice(), Some(&[1, 2, 4, 8, 16, 32, 64, 128][..]));
+        assert_eq!(vander(&x, 0, false).unwrap().shape(), &[1, 0]);
+    }
+
+    #[test]
+    fn test_chebyshev_matches_closed_form() {
+        let x: Tensor<f64> = Tensor::new(vec![-0.5, 0.0, 0.3, 1.0], vec![4]).unwrap();
+
+        let result = chebyshev(&x, 4).unwrap();
+
+        assert_eq!(result.shape(), &[4, 5]);
+        // T_k(cos t) = cos(k t)
+        let expected = Tensor::from_fn(vec![4, 5], |index| {
+            let t: f64 = x.get(&[index[0]]).unwrap().acos();
+            (index[1] as f64 * t).cos()
+        });
+        assert!(result.max_abs_diff(&expected).unwrap() < 1e-12);
+    }
+
+    #[test]
+    fn test_legendre() {
+        let x = Tensor::new(vec![0.5, 1.0], vec![2]).unwrap();
+
+        let result = legendre(&x, 3).unwrap();
+
+        // P_2 = (3x^2 - 1) / 2, P_3 = (5x^3 - 3x) / 2; P_k(1) = 1.
+        let expected = Tensor::new(
+            vec![1.0, 0.5, -0.125, -0.4375, 1.0, 1.0, 1.0, 1.0],
+            vec![2, 4],
+        )
+        .unwrap();
+        assert!(result.max_abs_diff(&expected).unwrap() < 1e-12);
+        assert_eq!(legendre(&x, 0).unwrap().shape(), &[2, 1]);
+    }
+}
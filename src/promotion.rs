@@ -0,0 +1,258 @@
+//! Type promotion for arithmetic between tensors of different element types.
+//!
+//! [`DType::promote`] implements NumPy's promotion lattice. Mixed-type
+//! arithmetic is opt-in: the `*_promoted` methods are available for element
+//! type pairs implementing [`Promote`], and cast both operands to the promoted
+//! type before combining them.
+
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+/// Runtime identifier of a tensor element type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl DType {
+    /// Returns the size of one element in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            DType::Bool | DType::U8 | DType::I8 => 1,
+            DType::U16 | DType::I16 => 2,
+            DType::U32 | DType::I32 | DType::F32 => 4,
+            DType::U64 | DType::I64 | DType::F64 => 8,
+        }
+    }
+
+    /// Returns `true` for floating-point types.
+    pub fn is_float(self) -> bool {
+        matches!(self, DType::F32 | DType::F64)
+    }
+
+    /// Returns `true` for signed integer types.
+    pub fn is_signed_int(self) -> bool {
+        matches!(self, DType::I8 | DType::I16 | DType::I32 | DType::I64)
+    }
+
+    /// Returns `true` for unsigned integer types.
+    pub fn is_unsigned_int(self) -> bool {
+        matches!(self, DType::U8 | DType::U16 | DType::U32 | DType::U64)
+    }
+
+    /// Returns the smallest type that can represent values of both `self` and
+    /// `other`, following NumPy's promotion rules:
+    ///
+    /// | operands                         | result                                  |
+    /// |----------------------------------|-----------------------------------------|
+    /// | `Bool` and `X`                   | `X`                                     |
+    /// | two of the same kind             | the larger one                          |
+    /// | integer and float                | the float, widened to `F64` for integers of 4 bytes or more |
+    /// | signed `I` and unsigned `U`      | `I` if it is larger than `U`, otherwise the signed type twice the size of `U` (`F64` for `U64`) |
+    pub fn promote(self, other: DType) -> DType {
+        use DType::*;
+
+        let (a, b) = if self.rank() <= other.rank() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        match (a, b) {
+            (x, y) if x == y => x,
+            (Bool, y) => y,
+            (x, y) if x.is_float() && y.is_float() => F64,
+            (x, F32) if x.size() >= 4 => F64,
+            (_, y) if y.is_float() => y,
+            (x, y) if x.is_signed_int() == y.is_signed_int() => {
+                if x.size() >= y.size() {
+                    x
+                } else {
+                    y
+                }
+            }
+            (x, y) => {
+                let (signed, unsigned) = if x.is_signed_int() { (x, y) } else { (y, x) };
+                if signed.size() > unsigned.size() {
+                    signed
+                } else {
+                    match unsigned {
+                        U8 => I16,
+                        U16 => I32,
+                        U32 => I64,
+                        _ => F64,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Orders the kinds bool < integers < floats.
+    fn rank(self) -> u8 {
+        match self {
+            DType::Bool => 0,
+            d if d.is_float() => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Element types with a runtime [`DType`].
+pub trait Element: Copy {
+    /// The runtime identifier of this type.
+    const DTYPE: DType;
+}
+
+macro_rules! impl_element {
+    ($($t:ty => $dtype:ident),*) => {
+        $(
+            impl Element for $t {
+                const DTYPE: DType = DType::$dtype;
+            }
+        )*
+    };
+}
+
+impl_element!(
+    bool => Bool, u8 => U8, u16 => U16, u32 => U32, u64 => U64,
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64, f32 => F32, f64 => F64
+);
+
+/// Opt-in promotion between element types `Self` and `Rhs`.
+///
+/// `Output` is the Rust type corresponding to `Self::DTYPE.promote(Rhs::DTYPE)`.
+pub trait Promote<Rhs: Element>: Element {
+    /// The promoted element type.
+    type Output: Element;
+
+    /// Casts a left-hand operand to the promoted type.
+    fn promote_lhs(self) -> Self::Output;
+    /// Casts a right-hand operand to the promoted type.
+    fn promote_rhs(rhs: Rhs) -> Self::Output;
+}
+
+macro_rules! impl_promote {
+    ($($lhs:ty, $rhs:ty => $out:ty;)*) => {
+        $(
+            impl Promote<$rhs> for $lhs {
+                type Output = $out;
+
+                fn promote_lhs(self) -> $out {
+                    self as $out
+                }
+
+                fn promote_rhs(rhs: $rhs) -> $out {
+                    rhs as $out
+                }
+            }
+        )*
+    };
+}
+
+impl_promote! {
+    u8, u8 => u8;     u8, i32 => i32;   u8, i64 => i64;   u8, f32 => f32;   u8, f64 => f64;
+    i32, u8 => i32;   i32, i32 => i32;  i32, i64 => i64;  i32, f32 => f64;  i32, f64 => f64;
+    i64, u8 => i64;   i64, i32 => i64;  i64, i64 => i64;  i64, f32 => f64;  i64, f64 => f64;
+    f32, u8 => f32;   f32, i32 => f64;  f32, i64 => f64;  f32, f32 => f32;  f32, f64 => f64;
+    f64, u8 => f64;   f64, i32 => f64;  f64, i64 => f64;  f64, f32 => f64;  f64, f64 => f64;
+}
+
+macro_rules! impl_promoted_ops {
+    ($($name:ident, $op:ident, $method:ident, $doc:expr;)*) => {
+        impl<T: Element> Tensor<T> {
+            $(
+                #[doc = $doc]
+                ///
+                /// Both operands are cast to the promoted element type and broadcast
+                /// to their common shape.
+                ///
+                /// # Errors
+                ///
+                /// Returns `TensorError::ShapeError` if the shapes cannot be broadcast together.
+                pub fn $name<U>(&self, other: &Tensor<U>) -> Result<Tensor<T::Output>, TensorError>
+                where
+                    U: Element,
+                    T: Promote<U>,
+                    T::Output: $op<Output = T::Output>,
+                {
+                    self.zip_with(other, |a, b| a.promote_lhs().$method(T::promote_rhs(b)))
+                }
+            )*
+        }
+    };
+}
+
+impl_promoted_ops! {
+    add_promoted, Add, add, "Adds two tensors of possibly different element types.";
+    sub_promoted, Sub, sub, "Subtracts two tensors of possibly different element types.";
+    mul_promoted, Mul, mul, "Multiplies two tensors of possibly different element types.";
+    div_promoted, Div, div, "Divides two tensors of possibly different element types.";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promote_lattice() {
+        use DType::*;
+
+        assert_eq!(Bool.promote(I8), I8);
+        assert_eq!(I32.promote(F64), F64);
+        assert_eq!(I16.promote(F32), F32);
+        assert_eq!(I32.promote(F32), F64);
+        assert_eq!(U8.promote(I8), I16);
+        assert_eq!(I64.promote(U32), I64);
+        assert_eq!(U64.promote(I64), F64);
+        assert_eq!(U16.promote(U64), U64);
+        assert_eq!(F32.promote(F64), F64);
+    }
+
+    #[test]
+    fn test_promote_is_symmetric() {
+        use DType::*;
+
+        let all = [Bool, U8, U16, U32, U64, I8, I16, I32, I64, F32, F64];
+        for a in all {
+            for b in all {
+                assert_eq!(a.promote(b), b.promote(a), "{:?} and {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_promote_impls_match_lattice() {
+        fn check<A: Promote<B>, B: Element>() {
+            assert_eq!(A::Output::DTYPE, A::DTYPE.promote(B::DTYPE));
+        }
+
+        check::<u8, i32>();
+        check::<i32, f32>();
+        check::<i64, f32>();
+        check::<f32, u8>();
+        check::<f64, i64>();
+    }
+
+    #[test]
+    fn test_add_promoted() {
+        let ints = Tensor::new(vec![1i32, 2, 3], vec![3]).unwrap();
+        let floats = Tensor::new(vec![0.5f64], vec![1]).unwrap();
+
+        let sum = ints.add_promoted(&floats).unwrap();
+        let product = floats.mul_promoted(&ints).unwrap();
+
+        assert_eq!(sum.as_slice(), Some(&[1.5, 2.5, 3.5][..]));
+        assert_eq!(product.as_slice(), Some(&[0.5, 1.0, 1.5][..]));
+    }
+}
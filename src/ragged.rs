@@ -0,0 +1,269 @@
+//! Ragged tensors: rows of differing lengths stored without padding.
+//!
+//! A [`RaggedTensor`] keeps every row back to back in one `values` tensor
+//! and marks where each row starts with `row_splits`, as TensorFlow does.
+//! Row `i` is `values[row_splits[i]..row_splits[i + 1]]`, so a batch of
+//! sentences, point clouds or event lists costs no more than its elements.
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+/// Rows of differing lengths sharing trailing feature axes.
+///
+/// `values` has shape `[total, ...]` and `row_splits` holds `nrows + 1`
+/// non-decreasing offsets into its first axis, starting at 0 and ending at
+/// `total`.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::ragged::RaggedTensor;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let values = Tensor::new(vec![3, 1, 4, 1, 5, 9], vec![6]).unwrap();
+/// let ragged = RaggedTensor::new(values, vec![0, 3, 3, 6]).unwrap();
+///
+/// assert_eq!(ragged.row_lengths(), vec![3, 0, 3]);
+/// assert_eq!(ragged.reduce_rows(0, |a, b| a + b).as_slice(), Some(&[8, 0, 15][..]));
+/// let padded = ragged.to_padded(-1);
+/// assert_eq!(padded.as_slice(), Some(&[3, 1, 4, -1, -1, -1, 1, 5, 9][..]));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaggedTensor<T> {
+    values: Tensor<T>,
+    row_splits: Vec<usize>,
+}
+
+impl<T: Copy> RaggedTensor<T> {
+    /// Creates a ragged tensor from its concatenated rows and row offsets.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `values` has no axes, and
+    /// `TensorError::ValueError` if `row_splits` is empty, does not start at 0,
+    /// decreases, or does not end at the length of the first axis of `values`.
+    pub fn new(values: Tensor<T>, row_splits: Vec<usize>) -> Result<Self, TensorError> {
+        let total = *values.shape().first().ok_or_else(|| {
+            TensorError::ShapeError("ragged values must have at least one axis".to_string())
+        })?;
+        if row_splits.first() != Some(&0)
+            || row_splits.last() != Some(&total)
+            || row_splits.windows(2).any(|pair| pair[0] > pair[1])
+        {
+            return Err(TensorError::ValueError(format!(
+                "row splits {:?} must rise from 0 to {}",
+                row_splits, total
+            )));
+        }
+        Ok(RaggedTensor {
+            values: values.to_contiguous(),
+            row_splits,
+        })
+    }
+
+    /// Creates a ragged tensor whose rows are `rows`, each of shape
+    /// `[len, ...]` with shared trailing axes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `rows` is empty and
+    /// `TensorError::ShapeError` if a row has no axes or its trailing axes
+    /// differ from those of the first.
+    pub fn from_rows(rows: &[Tensor<T>]) -> Result<Self, TensorError> {
+        Self::from_named_rows(rows, "row")
+    }
+
+    /// Like [`from_rows`](Self::from_rows), with `what` naming a row in error
+    /// messages, so batching helpers can report their own terms.
+    pub(crate) fn from_named_rows(rows: &[Tensor<T>], what: &str) -> Result<Self, TensorError> {
+        let first = rows
+            .first()
+            .ok_or_else(|| TensorError::ValueError(format!("expected at least one {}", what)))?;
+        let features = first.shape().get(1..).ok_or_else(|| {
+            TensorError::ShapeError(format!("every {} must have at least one axis", what))
+        })?;
+        let mut row_splits = vec![0];
+        let mut data = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            if row.ndim() == 0 || row.shape()[1..] != *features {
+                return Err(TensorError::ShapeError(format!(
+                    "{} {} has shape {:?}, expected [len, {:?}]",
+                    what,
+                    i,
+                    row.shape(),
+                    features
+                )));
+            }
+            data.extend(row.iter().copied());
+            row_splits.push(row_splits[i] + row.shape()[0]);
+        }
+        let mut shape = vec![row_splits[rows.len()]];
+        shape.extend_from_slice(features);
+        RaggedTensor::new(Tensor::new(data, shape)?, row_splits)
+    }
+
+    /// Returns the rows concatenated along the first axis.
+    pub fn values(&self) -> &Tensor<T> {
+        &self.values
+    }
+
+    /// Returns the offsets at which each row starts, followed by the total
+    /// length.
+    pub fn row_splits(&self) -> &[usize] {
+        &self.row_splits
+    }
+
+    /// Returns the number of rows.
+    pub fn nrows(&self) -> usize {
+        self.row_splits.len() - 1
+    }
+
+    /// Returns the length of every row.
+    pub fn row_lengths(&self) -> Vec<usize> {
+        self.row_splits
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect()
+    }
+
+    /// Returns the number of elements in one step of a row.
+    fn step(&self) -> usize {
+        self.values.shape()[1..].iter().product()
+    }
+
+    /// Returns row `i` as a tensor of shape `[len, ...]`, or `None` if `i` is
+    /// out of range.
+    pub fn row(&self, i: usize) -> Option<Tensor<T>> {
+        let (start, end) = (*self.row_splits.get(i)?, *self.row_splits.get(i + 1)?);
+        let step = self.step();
+        let mut shape = vec![end - start];
+        shape.extend_from_slice(&self.values.shape()[1..]);
+        Tensor::new(self.values.data[start * step..end * step].to_vec(), shape).ok()
+    }
+
+    /// Returns an iterator over the rows in order.
+    pub fn rows(&self) -> impl Iterator<Item = Tensor<T>> + '_ {
+        (0..self.nrows()).filter_map(|i| self.row(i))
+    }
+
+    /// Applies `f` to every value, keeping the row structure.
+    pub fn map<U, F>(&self, f: F) -> RaggedTensor<U>
+    where
+        U: Copy,
+        F: FnMut(T) -> U,
+    {
+        let data = self.values.data.iter().copied().map(f).collect();
+        RaggedTensor {
+            values: Tensor::new(data, self.values.shape().to_vec()).unwrap(),
+            row_splits: self.row_splits.clone(),
+        }
+    }
+
+    /// Folds every row along its length, starting from `init`, and returns a
+    /// tensor of shape `[nrows, ...]`. Each trailing feature position is folded
+    /// separately, and an empty row gives `init`.
+    pub fn reduce_rows<U, F>(&self, init: U, mut f: F) -> Tensor<U>
+    where
+        U: Copy,
+        F: FnMut(U, T) -> U,
+    {
+        let step = self.step();
+        let mut data = Vec::with_capacity(self.nrows() * step);
+        for pair in self.row_splits.windows(2) {
+            let row = &self.values.data[pair[0] * step..pair[1] * step];
+            data.extend((0..step).map(|j| {
+                row.iter()
+                    .skip(j)
+                    .step_by(step)
+                    .fold(init, |acc, &x| f(acc, x))
+            }));
+        }
+        let mut shape = vec![self.nrows()];
+        shape.extend_from_slice(&self.values.shape()[1..]);
+        Tensor::new(data, shape).unwrap()
+    }
+
+    /// Returns the rows padded with `pad` to the longest row, as a tensor of
+    /// shape `[nrows, max_len, ...]`.
+    pub fn to_padded(&self, pad: T) -> Tensor<T> {
+        let step = self.step();
+        let max_len = self.row_lengths().into_iter().max().unwrap_or(0);
+        let mut data = Vec::with_capacity(self.nrows() * max_len * step);
+        for pair in self.row_splits.windows(2) {
+            data.extend_from_slice(&self.values.data[pair[0] * step..pair[1] * step]);
+            data.resize(data.len() + (max_len - (pair[1] - pair[0])) * step, pad);
+        }
+        let mut shape = vec![self.nrows(), max_len];
+        shape.extend_from_slice(&self.values.shape()[1..]);
+        Tensor::new(data, shape).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ragged_rows_with_features() {
+        let rows = vec![
+            Tensor::new(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2]).unwrap(),
+            Tensor::new(vec![], vec![0, 2]).unwrap(),
+            Tensor::new(vec![5.0, 6.0], vec![1, 2]).unwrap(),
+        ];
+        let ragged = RaggedTensor::from_rows(&rows).unwrap();
+
+        assert_eq!(ragged.nrows(), 3);
+        assert_eq!(ragged.row_splits(), &[0, 2, 2, 3]);
+        assert_eq!(ragged.values().shape(), &[3, 2]);
+        assert_eq!(ragged.rows().collect::<Vec<_>>(), rows);
+        assert_eq!(ragged.row(3), None);
+
+        let sums = ragged.reduce_rows(0.0, |acc, x| acc + x);
+        assert_eq!(sums.shape(), &[3, 2]);
+        assert_eq!(sums.as_slice(), Some(&[4.0, 6.0, 0.0, 0.0, 5.0, 6.0][..]));
+
+        let doubled = ragged.map(|x| x * 2.0);
+        assert_eq!(doubled.row(2).unwrap().as_slice(), Some(&[10.0, 12.0][..]));
+        let signs = ragged.map(|x| x > 2.5);
+        assert_eq!(signs.row_lengths(), vec![2, 0, 1]);
+
+        let padded = ragged.to_padded(f64::NAN);
+        assert_eq!(padded.shape(), &[3, 2, 2]);
+        assert_eq!(padded.get(&[0, 1, 1]), Some(&4.0));
+        assert!(padded.get(&[1, 0, 0]).unwrap().is_nan());
+        assert_eq!(padded.get(&[2, 0, 1]), Some(&6.0));
+        assert!(padded.get(&[2, 1, 0]).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_ragged_rejects_bad_splits() {
+        let values = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+        for splits in [vec![], vec![1, 3], vec![0, 2], vec![0, 2, 1, 3]] {
+            assert!(matches!(
+                RaggedTensor::new(values.clone(), splits),
+                Err(TensorError::ValueError(_))
+            ));
+        }
+        assert!(matches!(
+            RaggedTensor::new(Tensor::new(vec![1], vec![]).unwrap(), vec![0]),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(RaggedTensor::<i32>::from_rows(&[]).is_err());
+        let mismatched = [
+            Tensor::new(vec![1, 2], vec![1, 2]).unwrap(),
+            Tensor::new(vec![1, 2, 3], vec![1, 3]).unwrap(),
+        ];
+        assert!(matches!(
+            RaggedTensor::from_rows(&mismatched),
+            Err(TensorError::ShapeError(_))
+        ));
+
+        // A strided values tensor is stored in logical order.
+        let transposed = Tensor::new(vec![1, 2, 3, 4], vec![2, 2])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+        let ragged = RaggedTensor::new(transposed, vec![0, 1, 2]).unwrap();
+        assert_eq!(ragged.row(0).unwrap().as_slice(), Some(&[1, 3][..]));
+    }
+}
@@ -0,0 +1,220 @@
+//! Random tensor creation, available with the `rand` feature.
+//!
+//! Every function takes the random number generator explicitly, so results are
+//! reproducible whenever the generator is seeded, e.g. via [`seeded_rng`].
+
+use std::cmp::Ordering;
+use std::f64::consts::PI;
+
+use rand::distributions::uniform::SampleUniform;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Returns a deterministic generator for the given seed.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Creates a `Tensor` with values drawn uniformly from the half-open interval `[low, high)`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `low` is not less than `high`, either
+/// bound is not finite, or `high - low` overflows to infinity.
+pub fn rand_uniform<T, R>(
+    shape: &[usize],
+    low: T,
+    high: T,
+    rng: &mut R,
+) -> Result<Tensor<T>, TensorError>
+where
+    T: Float + SampleUniform,
+    R: Rng + ?Sized,
+{
+    // rand panics on ranges it cannot scale, so reject them up front.
+    if !(high - low).is_finite() {
+        return Err(TensorError::ValueError(
+            "uniform range must have finite bounds and a finite width".to_string(),
+        ));
+    }
+    sample_range(shape, low, high, rng)
+}
+
+/// Creates a `Tensor` of integers drawn uniformly from the half-open interval `[low, high)`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `low` is not less than `high`.
+pub fn rand_int<T, R>(
+    shape: &[usize],
+    low: T,
+    high: T,
+    rng: &mut R,
+) -> Result<Tensor<T>, TensorError>
+where
+    T: Copy + PartialOrd + SampleUniform,
+    R: Rng + ?Sized,
+{
+    sample_range(shape, low, high, rng)
+}
+
+/// Creates a `Tensor` with values drawn from a normal distribution.
+///
+/// Samples are generated with the Box-Muller transform in `f64` precision.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `std` is negative or not finite.
+pub fn rand_normal<T, R>(
+    shape: &[usize],
+    mean: T,
+    std: T,
+    rng: &mut R,
+) -> Result<Tensor<T>, TensorError>
+where
+    T: Float,
+    R: Rng + ?Sized,
+{
+    if !(std >= T::zero() && std < T::from_f64(f64::INFINITY)) {
+        return Err(TensorError::ValueError(
+            "normal standard deviation must be finite and non-negative".to_string(),
+        ));
+    }
+
+    let num_elements: usize = shape.iter().product();
+    let mut data = Vec::with_capacity(num_elements);
+    while data.len() < num_elements {
+        // `gen` samples from [0, 1); flip it to (0, 1] so the logarithm stays finite.
+        let u1 = 1.0 - rng.r#gen::<f64>();
+        let u2 = rng.r#gen::<f64>();
+        let radius = (-2.0 * u1.ln()).sqrt();
+        for z in [
+            radius * (2.0 * PI * u2).cos(),
+            radius * (2.0 * PI * u2).sin(),
+        ] {
+            if data.len() < num_elements {
+                data.push(mean + std * T::from_f64(z));
+            }
+        }
+    }
+
+    Tensor::new(data, shape.to_vec())
+}
+
+/// Creates a boolean `Tensor` where each element is `true` with probability `p`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `p` is outside `[0, 1]`.
+pub fn bernoulli<R>(shape: &[usize], p: f64, rng: &mut R) -> Result<Tensor<bool>, TensorError>
+where
+    R: Rng + ?Sized,
+{
+    if !(0.0..=1.0).contains(&p) {
+        return Err(TensorError::ValueError(format!(
+            "bernoulli probability must be in [0, 1], got {}",
+            p
+        )));
+    }
+
+    let num_elements: usize = shape.iter().product();
+    let data = (0..num_elements).map(|_| rng.gen_bool(p)).collect();
+
+    Tensor::new(data, shape.to_vec())
+}
+
+/// Samples every element uniformly from `[low, high)`.
+fn sample_range<T, R>(
+    shape: &[usize],
+    low: T,
+    high: T,
+    rng: &mut R,
+) -> Result<Tensor<T>, TensorError>
+where
+    T: Copy + PartialOrd + SampleUniform,
+    R: Rng + ?Sized,
+{
+    if low.partial_cmp(&high) != Some(Ordering::Less) {
+        return Err(TensorError::ValueError(
+            "random range requires low < high".to_string(),
+        ));
+    }
+
+    let num_elements: usize = shape.iter().product();
+    let data = (0..num_elements)
+        .map(|_| rng.gen_range(low..high))
+        .collect();
+
+    Tensor::new(data, shape.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rand_uniform_range_and_seed() {
+        let a = rand_uniform(&[4, 5], -1.0, 1.0, &mut seeded_rng(7)).unwrap();
+        let b = rand_uniform(&[4, 5], -1.0, 1.0, &mut seeded_rng(7)).unwrap();
+
+        assert_eq!(a.shape(), &[4, 5]);
+        assert!(a.iter().all(|&x| (-1.0..1.0).contains(&x)));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_rand_uniform_rejects_unbounded_ranges() {
+        let mut rng = seeded_rng(7);
+        for (low, high) in [
+            (0.0, f64::INFINITY),
+            (f64::NEG_INFINITY, 0.0),
+            (f64::MIN, f64::MAX),
+            (0.0, f64::NAN),
+        ] {
+            assert!(matches!(
+                rand_uniform(&[2], low, high, &mut rng),
+                Err(TensorError::ValueError(_))
+            ));
+        }
+        let wide = rand_uniform(&[2], f64::MIN / 2.0, f64::MAX / 2.0, &mut rng).unwrap();
+        assert!(wide.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn test_rand_int_range() {
+        let result = rand_int(&[100], 3i64, 6, &mut seeded_rng(1)).unwrap();
+
+        assert!(result.iter().all(|&x| (3..6).contains(&x)));
+        assert!(matches!(
+            rand_int(&[1], 6, 6, &mut seeded_rng(1)),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+
+    #[test]
+    fn test_rand_normal_moments() {
+        let result = rand_normal(&[10_000], 2.0f64, 0.5, &mut seeded_rng(3)).unwrap();
+        let n = result.len() as f64;
+        let mean = result.iter().sum::<f64>() / n;
+        let var = result.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+
+        assert_eq!(result.len(), 10_000);
+        assert!((mean - 2.0).abs() < 0.05);
+        assert!((var.sqrt() - 0.5).abs() < 0.05);
+        assert!(rand_normal(&[1], 0.0, -1.0, &mut seeded_rng(3)).is_err());
+    }
+
+    #[test]
+    fn test_bernoulli() {
+        let all = bernoulli(&[3, 3], 1.0, &mut seeded_rng(0)).unwrap();
+        let none = bernoulli(&[3], 0.0, &mut seeded_rng(0)).unwrap();
+
+        assert!(all.iter().all(|&x| x));
+        assert!(none.iter().all(|&x| !x));
+        assert!(bernoulli(&[1], 1.5, &mut seeded_rng(0)).is_err());
+    }
+}
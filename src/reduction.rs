@@ -0,0 +1,809 @@
+use std::ops::{Add, Mul};
+
+use crate::error::TensorError;
+use crate::num::{Float, One, Zero};
+use crate::tensor::{Offsets, Tensor};
+
+/// The shape, input strides and output strides of a planned traversal.
+type Traversal = (Vec<usize>, Vec<usize>, Vec<usize>);
+
+/// Orders the axes of a reduction for traversal, so that strided inputs, such
+/// as permuted tensors, are walked as close to memory order as possible.
+///
+/// Kept axes may move freely, and are placed by decreasing input stride.
+/// Reduced axes keep their relative order: each output slot then receives its
+/// elements in logical order, so folds that depend on order, like
+/// floating-point sums or extremes with NaN, give the same result as for a
+/// contiguous copy. Axes of length 1 are dropped, and neighbouring axes that
+/// step through both buffers as a single axis are merged.
+fn plan_traversal(
+    shape: &[usize],
+    strides: &[usize],
+    out_strides: &[usize],
+    reduced: &[bool],
+) -> Traversal {
+    let axes = |is_reduced: bool| {
+        (0..shape.len()).filter(move |&axis| reduced[axis] == is_reduced && shape[axis] != 1)
+    };
+    let mut kept: Vec<usize> = axes(false).collect();
+    kept.sort_by_key(|&axis| std::cmp::Reverse(strides[axis]));
+    let fixed: Vec<usize> = axes(true).collect();
+
+    // Merge the two sequences outermost first, taking the larger stride.
+    let (mut k, mut r) = (0, 0);
+    let mut order = Vec::with_capacity(kept.len() + fixed.len());
+    while k < kept.len() || r < fixed.len() {
+        let take_kept =
+            r == fixed.len() || (k < kept.len() && strides[kept[k]] >= strides[fixed[r]]);
+        if take_kept {
+            order.push(kept[k]);
+            k += 1;
+        } else {
+            order.push(fixed[r]);
+            r += 1;
+        }
+    }
+
+    let mut plan: Traversal = (Vec::new(), Vec::new(), Vec::new());
+    for axis in order {
+        let (len, stride, out_stride) = (shape[axis], strides[axis], out_strides[axis]);
+        match (plan.0.last_mut(), plan.1.last_mut(), plan.2.last_mut()) {
+            (Some(outer_len), Some(outer), Some(outer_out))
+                if *outer == stride * len && *outer_out == out_stride * len =>
+            {
+                *outer_len *= len;
+                *outer = stride;
+                *outer_out = out_stride;
+            }
+            _ => {
+                plan.0.push(len);
+                plan.1.push(stride);
+                plan.2.push(out_stride);
+            }
+        }
+    }
+    plan
+}
+
+impl<T: Copy> Tensor<T> {
+    /// Folds every element into the output slot selected by dropping `axes` from
+    /// its index, in a single traversal of the tensor.
+    ///
+    /// Returns the output shape (honoring `keepdims`), the folded values and the
+    /// number of elements folded into each output slot.
+    pub(crate) fn fold_axes<U, F>(
+        &self,
+        axes: &[usize],
+        keepdims: bool,
+        init: U,
+        mut f: F,
+    ) -> Result<(Vec<usize>, Vec<U>, usize), TensorError>
+    where
+        U: Clone,
+        F: FnMut(U, T) -> U,
+    {
+        let mut reduced = vec![false; self.ndim()];
+        for &axis in axes {
+            self.check_axis(axis)?;
+            if reduced[axis] {
+                return Err(TensorError::AxisError(format!(
+                    "axis {} is repeated in the reduction axes",
+                    axis
+                )));
+            }
+            reduced[axis] = true;
+        }
+
+        // Give the reduced axes a stride of zero so every element of a reduced
+        // group maps onto the same output slot.
+        let mut out_strides = vec![0; self.ndim()];
+        let mut stride = 1;
+        for axis in (0..self.ndim()).rev() {
+            if !reduced[axis] {
+                out_strides[axis] = stride;
+                stride *= self.shape[axis];
+            }
+        }
+
+        let mut out = vec![init; stride];
+        let (shape, strides, slot_strides) =
+            plan_traversal(&self.shape, &self.strides, &out_strides, &reduced);
+        let slots = Offsets::new(&shape, &slot_strides);
+        for (offset, slot) in Offsets::new(&shape, &strides).zip(slots) {
+            out[slot] = f(out[slot].clone(), self.data[offset]);
+        }
+
+        let count = (0..self.ndim())
+            .filter(|&axis| reduced[axis])
+            .map(|axis| self.shape[axis])
+            .product();
+        let shape = (0..self.ndim())
+            .filter_map(|axis| match (reduced[axis], keepdims) {
+                (false, _) => Some(self.shape[axis]),
+                (true, true) => Some(1),
+                (true, false) => None,
+            })
+            .collect();
+
+        Ok((shape, out, count))
+    }
+
+    /// Replaces every element with `f` applied to the running result and the
+    /// element, walking each lane along `axis` from its start.
+    fn scan_mut<F>(&mut self, axis: usize, mut f: F) -> Result<(), TensorError>
+    where
+        F: FnMut(T, T) -> T,
+    {
+        let (_, starts) = self.lanes(axis)?;
+        let len = self.shape[axis];
+        let stride = self.strides[axis];
+        for start in starts {
+            for k in 1..len {
+                let offset = start + k * stride;
+                self.data[offset] = f(self.data[offset - stride], self.data[offset]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Zero> Tensor<T> {
+    /// Returns the sum of all elements.
+    pub fn sum(&self) -> T {
+        self.iter().fold(T::zero(), |acc, &x| acc + x)
+    }
+
+    /// Returns the sum of the main diagonal of a 2D tensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` is not 2-dimensional.
+    pub fn trace(&self) -> Result<T, TensorError> {
+        Ok(self.diagonal(0)?.sum())
+    }
+
+    /// Sums over all `axes` at once.
+    ///
+    /// The reduced axes are removed from the result, or kept with length 1 when
+    /// `keepdims` is `true`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if an axis is out of bounds or repeated.
+    pub fn sum_axes(&self, axes: &[usize], keepdims: bool) -> Result<Tensor<T>, TensorError> {
+        let (shape, data, _) = self.fold_axes(axes, keepdims, T::zero(), |acc, x| acc + x)?;
+        Tensor::new(data, shape)
+    }
+
+    /// Returns the cumulative sum along `axis`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn cumsum(&self, axis: usize) -> Result<Tensor<T>, TensorError> {
+        let mut result = self.to_contiguous();
+        result.cumsum_mut(axis)?;
+        Ok(result)
+    }
+
+    /// Replaces every element with the cumulative sum along `axis` up to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn cumsum_mut(&mut self, axis: usize) -> Result<(), TensorError> {
+        self.scan_mut(axis, |acc, x| acc + x)
+    }
+}
+
+impl<T: Copy + Mul<Output = T> + One> Tensor<T> {
+    /// Returns the product of all elements.
+    pub fn prod(&self) -> T {
+        self.iter().fold(T::one(), |acc, &x| acc * x)
+    }
+
+    /// Multiplies over all `axes` at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if an axis is out of bounds or repeated.
+    pub fn prod_axes(&self, axes: &[usize], keepdims: bool) -> Result<Tensor<T>, TensorError> {
+        let (shape, data, _) = self.fold_axes(axes, keepdims, T::one(), |acc, x| acc * x)?;
+        Tensor::new(data, shape)
+    }
+
+    /// Returns the cumulative product along `axis`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn cumprod(&self, axis: usize) -> Result<Tensor<T>, TensorError> {
+        let mut result = self.to_contiguous();
+        result.cumprod_mut(axis)?;
+        Ok(result)
+    }
+
+    /// Replaces every element with the cumulative product along `axis` up to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn cumprod_mut(&mut self, axis: usize) -> Result<(), TensorError> {
+        self.scan_mut(axis, |acc, x| acc * x)
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns the arithmetic mean of all elements, or NaN for an empty tensor.
+    pub fn mean(&self) -> T {
+        self.sum() / T::from_usize(self.len())
+    }
+
+    /// Averages over all `axes` at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if an axis is out of bounds or repeated.
+    pub fn mean_axes(&self, axes: &[usize], keepdims: bool) -> Result<Tensor<T>, TensorError> {
+        let (shape, data, count) = self.fold_axes(axes, keepdims, T::zero(), |acc, x| acc + x)?;
+        let count = T::from_usize(count);
+        Tensor::new(data.into_iter().map(|sum| sum / count).collect(), shape)
+    }
+
+    /// Returns the population variance of all elements (`ddof = 0`), or NaN for
+    /// an empty tensor.
+    pub fn var(&self) -> T {
+        let (count, _, m2) = self
+            .iter()
+            .fold((0, T::zero(), T::zero()), |acc, &x| welford(acc, x));
+        variance(count, m2, 0)
+    }
+
+    /// Returns the population standard deviation of all elements.
+    pub fn std(&self) -> T {
+        self.var().sqrt()
+    }
+
+    /// Returns the variance along `axis`, dividing the squared deviations by
+    /// `n - ddof` where `n` is the length of `axis`; lanes with `n <= ddof`
+    /// yield NaN.
+    ///
+    /// Deviations are accumulated with Welford's algorithm, so the result stays
+    /// accurate for data with a large offset from zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let x = Tensor::new(vec![1e9 + 1.0, 1e9 + 2.0, 1e9 + 3.0, 1.0, 1.0, 1.0], vec![2, 3]).unwrap();
+    /// assert_eq!(x.var_axis(1, 1).unwrap().as_slice(), Some(&[1.0, 0.0][..]));
+    /// ```
+    pub fn var_axis(&self, axis: usize, ddof: usize) -> Result<Tensor<T>, TensorError> {
+        let (shape, data, _) =
+            self.fold_axes(&[axis], false, (0, T::zero(), T::zero()), welford)?;
+        Tensor::new(
+            data.into_iter()
+                .map(|(count, _, m2)| variance(count, m2, ddof))
+                .collect(),
+            shape,
+        )
+    }
+
+    /// Returns the standard deviation along `axis`, the square root of
+    /// [`Tensor::var_axis`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn std_axis(&self, axis: usize, ddof: usize) -> Result<Tensor<T>, TensorError> {
+        let mut result = self.var_axis(axis, ddof)?;
+        result.sqrt_mut();
+        Ok(result)
+    }
+}
+
+/// Folds `x` into a running `(count, mean, sum of squared deviations)`.
+fn welford<T: Float>((count, mean, m2): (usize, T, T), x: T) -> (usize, T, T) {
+    let count = count + 1;
+    let delta = x - mean;
+    let mean = mean + delta / T::from_usize(count);
+    (count, mean, m2 + delta * (x - mean))
+}
+
+/// Returns `m2 / (count - ddof)`, or NaN when `count <= ddof`.
+fn variance<T: Float>(count: usize, m2: T, ddof: usize) -> T {
+    if count > ddof {
+        m2 / T::from_usize(count - ddof)
+    } else {
+        T::from_f64(f64::NAN)
+    }
+}
+
+/// The norm computed by [`Tensor::norm`] and [`Tensor::norm_axis`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Norm {
+    /// Sum of absolute values for vectors; maximum absolute column sum for matrices.
+    L1,
+    /// Euclidean length; vectors only.
+    L2,
+    /// Maximum absolute value for vectors; maximum absolute row sum for matrices.
+    Inf,
+    /// Square root of the sum of squares of all entries; matrices only.
+    Frobenius,
+    /// Maximum absolute entry; matrices only.
+    Max,
+}
+
+/// Folds `x` into a running vector norm of kind `ord`, before any final
+/// square root; NaN is propagated by the maximum.
+fn norm_step<T: Float>(ord: Norm, acc: T, x: T) -> T {
+    let x = x.abs();
+    match ord {
+        Norm::L2 | Norm::Frobenius => acc + x * x,
+        Norm::Inf | Norm::Max if x > acc || x.is_nan() => x,
+        Norm::Inf | Norm::Max => acc,
+        Norm::L1 => acc + x,
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns the norm `ord` of a vector or matrix, following NumPy's
+    /// `linalg.norm`.
+    ///
+    /// For a 1D tensor `ord` must be a vector norm ([`Norm::L1`], [`Norm::L2`]
+    /// or [`Norm::Inf`]). For a 2D tensor, [`Norm::L1`] and [`Norm::Inf`] are
+    /// the induced matrix norms, and [`Norm::Frobenius`] and [`Norm::Max`] are
+    /// taken over all entries; the spectral norm is not supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` is neither 1D nor 2D and
+    /// `TensorError::ValueError` if `ord` does not apply to its dimension.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::reduction::Norm;
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let v = Tensor::new(vec![3.0, -4.0], vec![2]).unwrap();
+    /// assert_eq!(v.norm(Norm::L2).unwrap(), 5.0);
+    ///
+    /// let m = Tensor::new(vec![1.0, -2.0, 3.0, 4.0], vec![2, 2]).unwrap();
+    /// assert_eq!(m.norm(Norm::L1).unwrap(), 6.0);
+    /// assert_eq!(m.norm(Norm::Inf).unwrap(), 7.0);
+    /// ```
+    pub fn norm(&self, ord: Norm) -> Result<T, TensorError> {
+        match (self.ndim(), ord) {
+            (1, Norm::L1 | Norm::L2 | Norm::Inf) | (2, Norm::Frobenius | Norm::Max) => {
+                let total = self
+                    .iter()
+                    .fold(T::zero(), |acc, &x| norm_step(ord, acc, x));
+                Ok(if ord == Norm::L2 || ord == Norm::Frobenius {
+                    total.sqrt()
+                } else {
+                    total
+                })
+            }
+            (2, Norm::L1 | Norm::Inf) => {
+                // Sum absolute values along the other axis, then take the largest.
+                let axis = if ord == Norm::L1 { 0 } else { 1 };
+                let sums = self.norm_axis(Norm::L1, axis)?;
+                Ok(sums
+                    .iter()
+                    .fold(T::zero(), |acc, &x| norm_step(Norm::Max, acc, x)))
+            }
+            (1 | 2, _) => Err(TensorError::ValueError(format!(
+                "{:?} norm is not supported for {}D tensors",
+                ord,
+                self.ndim()
+            ))),
+            _ => Err(TensorError::ShapeError(format!(
+                "norm needs a 1D or 2D tensor, got shape {:?}",
+                self.shape
+            ))),
+        }
+    }
+
+    /// Returns the vector norm `ord` of every lane along `axis`, dropping that
+    /// axis, e.g. the length of every row of a batch of embeddings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds and
+    /// `TensorError::ValueError` if `ord` is not a vector norm.
+    pub fn norm_axis(&self, ord: Norm, axis: usize) -> Result<Tensor<T>, TensorError> {
+        if !matches!(ord, Norm::L1 | Norm::L2 | Norm::Inf) {
+            return Err(TensorError::ValueError(format!(
+                "{:?} is not a vector norm",
+                ord
+            )));
+        }
+        let (shape, data, _) =
+            self.fold_axes(&[axis], false, T::zero(), |acc, x| norm_step(ord, acc, x))?;
+        let mut result = Tensor::new(data, shape)?;
+        if ord == Norm::L2 {
+            result.sqrt_mut();
+        }
+        Ok(result)
+    }
+}
+
+impl<T: Copy + PartialOrd> Tensor<T> {
+    /// Returns the minimum values along `axis` together with their indices.
+    ///
+    /// Both results are computed in a single pass over the tensor and have the
+    /// shape of `self` with `axis` removed. When several elements tie for the
+    /// minimum, the index of the first one is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds, or
+    /// `TensorError::ShapeError` if the axis has length zero.
+    pub fn min_with_index(&self, axis: usize) -> Result<(Tensor<T>, Tensor<usize>), TensorError> {
+        self.select_along(axis, |candidate, best| candidate < best)
+    }
+
+    /// Returns the maximum values along `axis` together with their indices.
+    ///
+    /// Both results are computed in a single pass over the tensor and have the
+    /// shape of `self` with `axis` removed. When several elements tie for the
+    /// maximum, the index of the first one is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds, or
+    /// `TensorError::ShapeError` if the axis has length zero.
+    pub fn max_with_index(&self, axis: usize) -> Result<(Tensor<T>, Tensor<usize>), TensorError> {
+        self.select_along(axis, |candidate, best| candidate > best)
+    }
+
+    /// Returns the maximum over all `axes` at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if an axis is out of bounds or repeated,
+    /// or `TensorError::ShapeError` if any reduced axis has length zero.
+    pub fn max_axes(&self, axes: &[usize], keepdims: bool) -> Result<Tensor<T>, TensorError> {
+        self.extreme_axes(axes, keepdims, |candidate, best| candidate > best)
+    }
+
+    /// Returns the minimum over all `axes` at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if an axis is out of bounds or repeated,
+    /// or `TensorError::ShapeError` if any reduced axis has length zero.
+    pub fn min_axes(&self, axes: &[usize], keepdims: bool) -> Result<Tensor<T>, TensorError> {
+        self.extreme_axes(axes, keepdims, |candidate, best| candidate < best)
+    }
+
+    fn extreme_axes<F>(
+        &self,
+        axes: &[usize],
+        keepdims: bool,
+        better: F,
+    ) -> Result<Tensor<T>, TensorError>
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let (shape, data, count) = self.fold_axes(axes, keepdims, None, |best, x| match best {
+            Some(b) if !better(&x, &b) => Some(b),
+            _ => Some(x),
+        })?;
+        if count == 0 {
+            return Err(TensorError::ShapeError(
+                "cannot take the extreme of an empty reduction".to_string(),
+            ));
+        }
+
+        Tensor::new(data.into_iter().map(Option::unwrap).collect(), shape)
+    }
+
+    /// Walks every lane along `axis`, keeping the element for which `better`
+    /// holds against the current best.
+    fn select_along<F>(
+        &self,
+        axis: usize,
+        better: F,
+    ) -> Result<(Tensor<T>, Tensor<usize>), TensorError>
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let (out_shape, starts) = self.lanes(axis)?;
+        let len = self.shape[axis];
+        let stride = self.strides[axis];
+        if len == 0 {
+            return Err(TensorError::ShapeError(format!(
+                "cannot reduce along axis {} of length zero",
+                axis
+            )));
+        }
+
+        let mut values = Vec::with_capacity(starts.len());
+        let mut indices = Vec::with_capacity(starts.len());
+        for start in starts {
+            let mut best = self.data[start];
+            let mut best_index = 0;
+            for k in 1..len {
+                let candidate = self.data[start + k * stride];
+                if better(&candidate, &best) {
+                    best = candidate;
+                    best_index = k;
+                }
+            }
+            values.push(best);
+            indices.push(best_index);
+        }
+
+        Ok((
+            Tensor::new(values, out_shape.clone())?,
+            Tensor::new(indices, out_shape)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_max_with_index() {
+        let tensor = Tensor::new(vec![3, 1, 4, 1, 5, 9], vec![2, 3]).unwrap();
+
+        let (values, indices) = tensor.max_with_index(1).unwrap();
+        assert_eq!(values.data, vec![4, 9]);
+        assert_eq!(indices.data, vec![2, 2]);
+
+        let (values, indices) = tensor.min_with_index(0).unwrap();
+        assert_eq!(values.shape, &[3]);
+        assert_eq!(values.data, vec![1, 1, 4]);
+        assert_eq!(indices.data, vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn test_min_with_index_first_tie() {
+        let tensor = Tensor::new(vec![2.0, 1.0, 1.0], vec![3]).unwrap();
+        let (values, indices) = tensor.min_with_index(0).unwrap();
+
+        assert_eq!(values.shape, &[] as &[usize]);
+        assert_eq!(values.data, vec![1.0]);
+        assert_eq!(indices.data, vec![1]);
+    }
+
+    #[test]
+    fn test_sum_and_mean_axes() {
+        let tensor = Tensor::new((0..24).map(|x| x as f64).collect(), vec![2, 3, 4]).unwrap();
+
+        let sum = tensor.sum_axes(&[0, 2], false).unwrap();
+        let mean = tensor.mean_axes(&[2, 0], true).unwrap();
+
+        assert_eq!(sum.shape, &[3]);
+        assert_eq!(sum.data, vec![60.0, 92.0, 124.0]);
+        assert_eq!(mean.shape, &[1, 3, 1]);
+        assert_eq!(mean.data, vec![7.5, 11.5, 15.5]);
+        assert_eq!(tensor.sum(), 276.0);
+        assert_eq!(tensor.mean(), 11.5);
+    }
+
+    #[test]
+    fn test_prod_and_extremes_axes() {
+        let tensor = Tensor::new(vec![3, -1, 4, 1, 5, -9], vec![2, 3]).unwrap();
+
+        assert_eq!(tensor.prod_axes(&[1], false).unwrap().data, vec![-12, -45]);
+        assert_eq!(tensor.max_axes(&[0, 1], false).unwrap().data, vec![5]);
+        assert_eq!(tensor.min_axes(&[0], true).unwrap().data, vec![1, -1, -9]);
+        assert_eq!(tensor.sum_axes(&[], false).unwrap(), tensor);
+        assert_eq!(tensor.prod(), 540);
+    }
+
+    #[test]
+    fn test_axes_errors() {
+        let tensor = Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap();
+        let empty: Tensor<i32> = Tensor::new(vec![], vec![0, 2]).unwrap();
+
+        assert!(matches!(
+            tensor.sum_axes(&[2], false),
+            Err(TensorError::AxisError(_))
+        ));
+        assert!(matches!(
+            tensor.sum_axes(&[1, 1], false),
+            Err(TensorError::AxisError(_))
+        ));
+        assert!(matches!(
+            empty.max_axes(&[0], false),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_with_index_errors() {
+        let tensor = Tensor::new(vec![1, 2], vec![2]).unwrap();
+        let empty: Tensor<i32> = Tensor::new(vec![], vec![2, 0]).unwrap();
+
+        assert!(matches!(
+            tensor.max_with_index(1),
+            Err(TensorError::AxisError(_))
+        ));
+        assert!(matches!(
+            empty.min_with_index(1),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_cumsum_and_cumprod_along_axes() {
+        let tensor = Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![2, 3]).unwrap();
+
+        assert_eq!(tensor.cumsum(1).unwrap().data, vec![1, 3, 6, 4, 9, 15]);
+        assert_eq!(tensor.cumsum(0).unwrap().data, vec![1, 2, 3, 5, 7, 9]);
+        assert_eq!(tensor.cumprod(1).unwrap().data, vec![1, 2, 6, 4, 20, 120]);
+        assert!(matches!(tensor.cumsum(2), Err(TensorError::AxisError(_))));
+    }
+
+    #[test]
+    fn test_cumsum_mut_on_strided_tensor() {
+        let mut tensor = Tensor::new(vec![1, 2, 3, 4], vec![2, 2])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+
+        tensor.cumsum_mut(1).unwrap();
+        tensor.cumprod_mut(0).unwrap();
+
+        // Logical [[1, 3], [2, 4]] -> [[1, 4], [2, 6]] -> [[1, 4], [2, 24]].
+        assert_eq!(
+            tensor.iter().copied().collect::<Vec<_>>(),
+            vec![1, 4, 2, 24]
+        );
+    }
+
+    #[test]
+    fn test_var_and_std() {
+        let tensor = Tensor::new(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0], vec![2, 4]).unwrap();
+
+        assert_eq!(tensor.var(), 4.0);
+        assert_eq!(tensor.std(), 2.0);
+        let expected = Tensor::new(vec![0.75, 2.75], vec![2]).unwrap();
+        assert!(
+            tensor
+                .var_axis(1, 0)
+                .unwrap()
+                .max_abs_diff(&expected)
+                .unwrap()
+                < 1e-12
+        );
+        let expected = Tensor::new(vec![1.0, 11.0 / 3.0], vec![2]).unwrap();
+        assert!(
+            tensor
+                .var_axis(1, 1)
+                .unwrap()
+                .max_abs_diff(&expected)
+                .unwrap()
+                < 1e-12
+        );
+        assert_eq!(
+            tensor.std_axis(0, 0).unwrap().as_slice(),
+            Some(&[1.5, 0.5, 1.5, 2.5][..])
+        );
+        assert!(tensor.var_axis(0, 2).unwrap().iter().all(|v| v.is_nan()));
+        assert!(tensor.var_axis(2, 0).is_err());
+        assert!(Tensor::<f64>::new(vec![], vec![0]).unwrap().var().is_nan());
+    }
+
+    #[test]
+    fn test_var_is_stable_under_large_offsets() {
+        let tensor =
+            Tensor::new(vec![1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0], vec![4]).unwrap();
+
+        assert_eq!(tensor.var(), 22.5);
+        assert_eq!(tensor.var_axis(0, 1).unwrap().as_slice(), Some(&[30.0][..]));
+    }
+
+    #[test]
+    fn test_vector_and_matrix_norms() {
+        let v = Tensor::new(vec![1.0, -2.0, 2.0], vec![3]).unwrap();
+        assert_eq!(v.norm(Norm::L1).unwrap(), 5.0);
+        assert_eq!(v.norm(Norm::L2).unwrap(), 3.0);
+        assert_eq!(v.norm(Norm::Inf).unwrap(), 2.0);
+        assert!(matches!(
+            v.norm(Norm::Frobenius),
+            Err(TensorError::ValueError(_))
+        ));
+
+        let m = Tensor::new(vec![1.0, -2.0, 0.0, 3.0, 4.0, -12.0], vec![2, 3]).unwrap();
+        assert_eq!(m.norm(Norm::L1).unwrap(), 12.0);
+        assert_eq!(m.norm(Norm::Inf).unwrap(), 19.0);
+        assert_eq!(m.norm(Norm::Max).unwrap(), 12.0);
+        assert_eq!(m.norm(Norm::Frobenius).unwrap(), 174f64.sqrt());
+        assert!(m.norm(Norm::L2).is_err());
+        assert!(matches!(
+            Tensor::new(vec![1.0; 8], vec![2, 2, 2])
+                .unwrap()
+                .norm(Norm::L1),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(
+            Tensor::new(vec![1.0, f64::NAN], vec![2])
+                .unwrap()
+                .norm(Norm::Inf)
+                .unwrap()
+                .is_nan()
+        );
+    }
+
+    #[test]
+    fn test_norm_axis_on_batched_rows() {
+        let rows = Tensor::new(vec![3.0, 4.0, 0.0, -5.0, 12.0, 0.0], vec![2, 3]).unwrap();
+
+        assert_eq!(rows.norm_axis(Norm::L2, 1).unwrap().data, vec![5.0, 13.0]);
+        assert_eq!(
+            rows.norm_axis(Norm::L1, 0).unwrap().data,
+            vec![8.0, 16.0, 0.0]
+        );
+        assert_eq!(rows.norm_axis(Norm::Inf, 1).unwrap().data, vec![4.0, 12.0]);
+        assert!(rows.norm_axis(Norm::Max, 1).is_err());
+        assert!(rows.norm_axis(Norm::L2, 2).is_err());
+    }
+
+    #[test]
+    fn test_trace() {
+        let m = Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![3, 2]).unwrap();
+
+        assert_eq!(m.trace().unwrap(), 5);
+        assert_eq!(m.permute(&[1, 0]).unwrap().trace().unwrap(), 5);
+        assert!(Tensor::new(vec![1.0], vec![1]).unwrap().trace().is_err());
+    }
+
+    #[test]
+    fn test_plan_traversal_follows_memory_and_merges_axes() {
+        // A full reduction of a contiguous tensor is one flat walk.
+        let plan = plan_traversal(&[2, 3, 4], &[12, 4, 1], &[0, 0, 0], &[true; 3]);
+        assert_eq!(plan, (vec![24], vec![1], vec![0]));
+
+        // Walking a transposed [3, 4, 2] buffer in memory order puts the
+        // reduced axis, which has the smallest stride, innermost.
+        let plan = plan_traversal(&[2, 4, 3], &[1, 2, 8], &[0, 3, 1], &[true, false, false]);
+        assert_eq!(plan, (vec![3, 4, 2], vec![8, 2, 1], vec![1, 3, 0]));
+
+        // Reduced axes never swap, even against their strides.
+        let plan = plan_traversal(&[2, 3], &[1, 2], &[0, 0], &[true, true]);
+        assert_eq!(plan, (vec![2, 3], vec![1, 2], vec![0, 0]));
+    }
+
+    #[test]
+    fn test_axis_reductions_on_permuted_tensors() {
+        let base = Tensor::from_fn(vec![3, 4, 5], |i| {
+            ((i[0] * 7 + i[1] * 3 + i[2]) % 11) as f64 / 3.0 - 1.0
+        });
+        let mut with_nan = base.clone();
+        with_nan.data[17] = f64::NAN;
+
+        for axes in [[2, 0, 1], [1, 2, 0], [2, 1, 0]] {
+            let permuted = base.permute(&axes).unwrap();
+            let contiguous = permuted.to_contiguous();
+            for reduce in [vec![0], vec![1, 2], vec![0, 2], vec![0, 1, 2]] {
+                let sum = permuted.sum_axes(&reduce, false).unwrap();
+                assert_eq!(sum.data, contiguous.sum_axes(&reduce, false).unwrap().data);
+                let max = permuted.max_axes(&reduce, true).unwrap();
+                assert_eq!(max, contiguous.max_axes(&reduce, true).unwrap());
+            }
+
+            // Extremes depend on where NaN falls in each group's order.
+            let permuted = with_nan.permute(&axes).unwrap();
+            let expected = permuted.to_contiguous().max_axes(&[0, 2], false).unwrap();
+            let max = permuted.max_axes(&[0, 2], false).unwrap();
+            assert!(
+                max.iter()
+                    .zip(expected.iter())
+                    .all(|(a, b)| a == b || a.is_nan() && b.is_nan())
+            );
+        }
+    }
+}
@@ -0,0 +1,453 @@
+//! Conditional selection of elements and boolean masking.
+
+use std::ops::Range;
+
+use crate::error::TensorError;
+use crate::ops::broadcast_shapes;
+use crate::tensor::{Offsets, Tensor};
+
+/// Builds a tensor from several `(condition, values)` branches in one traversal.
+///
+/// Every condition and value tensor is broadcast to a common shape. At each
+/// position the result takes the value of the first branch whose condition is
+/// `true`, or `default` if none is, like NumPy's `select`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `branches` is empty, and
+/// `TensorError::ShapeError` if the tensors cannot be broadcast together.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::select::piecewise;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let income = Tensor::new(vec![5.0, 20.0, 80.0], vec![3]).unwrap();
+/// let low = income.lt_scalar(10.0);
+/// let mid = income.lt_scalar(50.0);
+/// let rate = piecewise(
+///     &[
+///         (&low, &Tensor::new(vec![0.0], vec![1]).unwrap()),
+///         (&mid, &Tensor::new(vec![0.2], vec![1]).unwrap()),
+///     ],
+///     0.4,
+/// )
+/// .unwrap();
+/// assert_eq!(rate.as_slice(), Some(&[0.0, 0.2, 0.4][..]));
+/// ```
+pub fn piecewise<T: Copy>(
+    branches: &[(&Tensor<bool>, &Tensor<T>)],
+    default: T,
+) -> Result<Tensor<T>, TensorError> {
+    let Some(((first, _), _)) = branches.split_first() else {
+        return Err(TensorError::ValueError(
+            "piecewise requires at least one branch".to_string(),
+        ));
+    };
+
+    let mut shape = first.shape.clone();
+    for (condition, values) in branches {
+        shape = broadcast_shapes(&shape, &condition.shape)?;
+        shape = broadcast_shapes(&shape, &values.shape)?;
+    }
+
+    let strides = branches
+        .iter()
+        .map(|(condition, values)| {
+            Ok((
+                condition.broadcast_strides(&shape)?,
+                values.broadcast_strides(&shape)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, TensorError>>()?;
+    let mut offsets: Vec<_> = strides
+        .iter()
+        .map(|(condition, values)| {
+            (
+                Offsets::new(&shape, condition),
+                Offsets::new(&shape, values),
+            )
+        })
+        .collect();
+
+    let len = shape.iter().product();
+    let mut data = Vec::with_capacity(len);
+    for _ in 0..len {
+        let mut chosen = None;
+        for ((condition, values), (conditions, positions)) in branches.iter().zip(&mut offsets) {
+            // Every iterator advances on every element to stay in lockstep.
+            let (i, j) = (conditions.next().unwrap(), positions.next().unwrap());
+            if chosen.is_none() && condition.data[i] {
+                chosen = Some(values.data[j]);
+            }
+        }
+        data.push(chosen.unwrap_or(default));
+    }
+
+    Tensor::new(data, shape)
+}
+
+impl<T: Copy> Tensor<T> {
+    /// Chooses elements from `a` where `cond` is `true` and from `b` elsewhere,
+    /// broadcasting all three to a common shape like `numpy.where`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the shapes cannot be broadcast together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let x = Tensor::new(vec![-1.0, 2.0, -3.0], vec![3]).unwrap();
+    /// let zero = Tensor::new(vec![0.0], vec![1]).unwrap();
+    /// let relu = Tensor::where_(&x.gt_scalar(0.0), &x, &zero).unwrap();
+    /// assert_eq!(relu.as_slice(), Some(&[0.0, 2.0, 0.0][..]));
+    /// ```
+    pub fn where_(
+        cond: &Tensor<bool>,
+        a: &Tensor<T>,
+        b: &Tensor<T>,
+    ) -> Result<Tensor<T>, TensorError> {
+        let shape = broadcast_shapes(&broadcast_shapes(&cond.shape, &a.shape)?, &b.shape)?;
+        let cond_strides = cond.broadcast_strides(&shape)?;
+        let a_strides = a.broadcast_strides(&shape)?;
+        let b_strides = b.broadcast_strides(&shape)?;
+        let data = Offsets::new(&shape, &cond_strides)
+            .zip(Offsets::new(&shape, &a_strides))
+            .zip(Offsets::new(&shape, &b_strides))
+            .map(|((c, i), j)| if cond.data[c] { a.data[i] } else { b.data[j] })
+            .collect();
+
+        Tensor::new(data, shape)
+    }
+
+    /// Returns the elements of `self` where `mask` is `true`, as a 1D tensor in
+    /// logical order.
+    ///
+    /// `mask` is broadcast to the shape of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `mask` cannot be broadcast to the shape of `self`.
+    pub fn masked_select(&self, mask: &Tensor<bool>) -> Result<Tensor<T>, TensorError> {
+        let mask_strides = mask.broadcast_strides(&self.shape)?;
+        let data: Vec<T> = self
+            .offsets()
+            .zip(Offsets::new(&self.shape, &mask_strides))
+            .filter(|&(_, m)| mask.data[m])
+            .map(|(i, _)| self.data[i])
+            .collect();
+        let len = data.len();
+        Tensor::new(data, vec![len])
+    }
+
+    /// Sets every element of `self` where `mask` is `true` to `value`.
+    ///
+    /// `mask` is broadcast to the shape of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `mask` cannot be broadcast to the shape of `self`.
+    pub fn masked_fill(&mut self, mask: &Tensor<bool>, value: T) -> Result<(), TensorError> {
+        self.zip_mut_with(mask, |x, selected| if selected { value } else { x })
+    }
+
+    /// Returns how many elements of `self` `mask` selects, once broadcast to the
+    /// shape of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `mask` cannot be broadcast to the shape of `self`.
+    pub fn masked_count(&self, mask: &Tensor<bool>) -> Result<usize, TensorError> {
+        let mask_strides = mask.broadcast_strides(&self.shape)?;
+        Ok(Offsets::new(&self.shape, &mask_strides)
+            .filter(|&m| mask.data[m])
+            .count())
+    }
+
+    /// Assigns `src` to the region of `self` selected by half-open `ranges`,
+    /// like NumPy's `a[1:3, :] = b`.
+    ///
+    /// `ranges[i]` selects along axis `i`; axes beyond the given ranges are
+    /// selected in full. `src` is broadcast to the shape of the region, so a
+    /// row can fill every selected row and a single-element tensor fills the
+    /// whole region.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if there are more ranges than axes, a
+    /// range is reversed or out of bounds, or `src` does not broadcast to the
+    /// region. `self` is unchanged on error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let mut a = Tensor::new(vec![0; 12], vec![4, 3]).unwrap();
+    /// let row = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+    /// a.slice_assign(&[1..3, 0..3], &row).unwrap();
+    /// a.slice_assign(&[3..4, 1..3], &Tensor::new(vec![9], vec![]).unwrap()).unwrap();
+    /// assert_eq!(a.as_slice(), Some(&[0, 0, 0, 1, 2, 3, 1, 2, 3, 0, 9, 9][..]));
+    /// ```
+    pub fn slice_assign(
+        &mut self,
+        ranges: &[Range<usize>],
+        src: &Tensor<T>,
+    ) -> Result<(), TensorError> {
+        let (base, shape) = self.region(ranges)?;
+        let src_strides = src.broadcast_strides(&shape)?;
+        let targets = Offsets::new(&shape, &self.strides);
+        let sources = Offsets::new(&shape, &src_strides);
+        for (target, source) in targets.zip(sources) {
+            self.data[base + target] = src.data[source];
+        }
+        Ok(())
+    }
+
+    /// Sets every element of the region of `self` selected by half-open
+    /// `ranges` to `value`; see [`Tensor::slice_assign`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if there are more ranges than axes or a
+    /// range is reversed or out of bounds.
+    pub fn slice_fill(&mut self, ranges: &[Range<usize>], value: T) -> Result<(), TensorError> {
+        let (base, shape) = self.region(ranges)?;
+        for target in Offsets::new(&shape, &self.strides) {
+            self.data[base + target] = value;
+        }
+        Ok(())
+    }
+
+    /// Returns the buffer offset of the first element of the region selected by
+    /// `ranges`, and the region's shape.
+    fn region(&self, ranges: &[Range<usize>]) -> Result<(usize, Vec<usize>), TensorError> {
+        if ranges.len() > self.shape.len() {
+            return Err(TensorError::ShapeError(format!(
+                "{} ranges given for a tensor of shape {:?}",
+                ranges.len(),
+                self.shape
+            )));
+        }
+        let mut base = 0;
+        let mut shape = self.shape.clone();
+        for (axis, range) in ranges.iter().enumerate() {
+            if range.start > range.end || range.end > self.shape[axis] {
+                return Err(TensorError::ShapeError(format!(
+                    "range {:?} is invalid for axis {} of length {}",
+                    range, axis, self.shape[axis]
+                )));
+            }
+            base += range.start * self.strides[axis];
+            shape[axis] = range.len();
+        }
+        Ok((base, shape))
+    }
+
+    /// Returns the `offset`-th diagonal of a 2D tensor as a 1D tensor.
+    ///
+    /// `offset = 0` is the main diagonal, positive values refer to diagonals
+    /// above it and negative values to diagonals below it, as in [`eye_rect`].
+    /// Diagonals lying entirely outside the matrix are empty.
+    ///
+    /// [`eye_rect`]: crate::creation::eye_rect
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` is not 2-dimensional.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let m = Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![2, 3]).unwrap();
+    /// assert_eq!(m.diagonal(0).unwrap().as_slice(), Some(&[1, 5][..]));
+    /// assert_eq!(m.diagonal(1).unwrap().as_slice(), Some(&[2, 6][..]));
+    /// assert_eq!(m.diagonal(-1).unwrap().as_slice(), Some(&[4][..]));
+    /// ```
+    pub fn diagonal(&self, offset: isize) -> Result<Tensor<T>, TensorError> {
+        let (rows, cols) = match self.shape[..] {
+            [rows, cols] => (rows, cols),
+            _ => {
+                return Err(TensorError::ShapeError(format!(
+                    "diagonal needs a 2D tensor, got shape {:?}",
+                    self.shape
+                )));
+            }
+        };
+        let (row, col) = if offset >= 0 {
+            (0, offset.unsigned_abs())
+        } else {
+            (offset.unsigned_abs(), 0)
+        };
+        let len = rows.saturating_sub(row).min(cols.saturating_sub(col));
+        let data = (0..len)
+            .map(|k| self.data[(row + k) * self.strides[0] + (col + k) * self.strides[1]])
+            .collect();
+        Tensor::new(data, vec![len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piecewise_first_true_branch_wins() {
+        let x = Tensor::new(vec![-2, -1, 0, 1, 2, 3], vec![2, 3]).unwrap();
+        let negative = x.lt_scalar(0);
+        let small = x.le_scalar(1);
+        let doubled = x.map(|v| v * 2);
+        let ten = Tensor::new(vec![10], vec![1]).unwrap();
+
+        let result = piecewise(&[(&negative, &doubled), (&small, &ten)], -1).unwrap();
+
+        assert_eq!(result.shape(), &[2, 3]);
+        assert_eq!(result.as_slice(), Some(&[-4, -2, 10, 10, -1, -1][..]));
+    }
+
+    #[test]
+    fn test_piecewise_broadcasts_and_validates() {
+        let column = Tensor::new(vec![true, false], vec![2, 1]).unwrap();
+        let row = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+
+        let result = piecewise(&[(&column, &row)], 0).unwrap();
+        assert_eq!(result.as_slice(), Some(&[1, 2, 3, 0, 0, 0][..]));
+
+        // A [2] choice broadcasts against the [2, 1] condition to [2, 2].
+        let pair = Tensor::new(vec![1, 2], vec![2]).unwrap();
+        let broadcast = piecewise(&[(&column, &pair)], 0).unwrap();
+        assert_eq!(broadcast.shape(), &[2, 2]);
+        assert_eq!(broadcast.as_slice(), Some(&[1, 2, 0, 0][..]));
+        let mismatched = Tensor::new(vec![true; 3], vec![3]).unwrap();
+        assert!(matches!(
+            piecewise(&[(&mismatched, &pair)], 0),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(matches!(
+            piecewise::<i32>(&[], 0),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+
+    #[test]
+    fn test_masked_select_and_count() {
+        let tensor = Tensor::new(vec![1.0, 9.0, 2.0, 8.0], vec![2, 2])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+        let outliers = tensor.gt_scalar(5.0);
+
+        let result = tensor.masked_select(&outliers).unwrap();
+
+        assert_eq!(result.shape(), &[2]);
+        assert_eq!(result.as_slice(), Some(&[9.0, 8.0][..]));
+        assert_eq!(tensor.masked_count(&outliers).unwrap(), 2);
+        let column = Tensor::new(vec![true, false], vec![2, 1]).unwrap();
+        assert_eq!(tensor.masked_count(&column).unwrap(), 2);
+        assert!(
+            tensor
+                .masked_select(&Tensor::new(vec![true; 3], vec![3]).unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_masked_fill_broadcasts_mask() {
+        let mut scores = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], vec![2, 3]).unwrap();
+        let causal = Tensor::new(vec![false, true, true], vec![3]).unwrap();
+
+        scores.masked_fill(&causal, f64::NEG_INFINITY).unwrap();
+
+        assert_eq!(
+            scores
+                .masked_count(&scores.eq_scalar(f64::NEG_INFINITY))
+                .unwrap(),
+            4
+        );
+        assert_eq!(scores.get(&[1, 0]), Some(&4.0));
+        assert!(
+            scores
+                .masked_fill(&Tensor::new(vec![true; 2], vec![2]).unwrap(), 0.0)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_where_broadcasts_all_arguments() {
+        let cond = Tensor::new(vec![true, false], vec![2, 1]).unwrap();
+        let a = Tensor::new(vec![1, 2, 3], vec![3]).unwrap();
+        let b = Tensor::new(vec![0], vec![]).unwrap();
+
+        let result = Tensor::where_(&cond, &a, &b).unwrap();
+
+        assert_eq!(result.shape(), &[2, 3]);
+        assert_eq!(result.as_slice(), Some(&[1, 2, 3, 0, 0, 0][..]));
+        let wrong = Tensor::new(vec![1, 2], vec![2]).unwrap();
+        assert!(matches!(
+            Tensor::where_(&cond, &a, &wrong),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_diagonal_of_strided_matrix() {
+        let m = Tensor::from_fn(vec![3, 4], |i| i[0] * 10 + i[1]);
+        let t = m.permute(&[1, 0]).unwrap();
+
+        assert_eq!(t.diagonal(0).unwrap().as_slice(), Some(&[0, 11, 22][..]));
+        assert_eq!(t.diagonal(-1).unwrap().as_slice(), Some(&[1, 12, 23][..]));
+        assert_eq!(t.diagonal(2).unwrap().len(), 1);
+        assert_eq!(t.diagonal(3).unwrap().len(), 0);
+        assert_eq!(t.diagonal(-9).unwrap().len(), 0);
+        assert!(matches!(
+            Tensor::new(vec![1], vec![1]).unwrap().diagonal(0),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_slice_assign_broadcasts_into_region() {
+        let mut a = Tensor::from_fn(vec![3, 4], |i| (i[0] * 10 + i[1]) as i32);
+        let column = Tensor::new(vec![-1, -2], vec![2, 1]).unwrap();
+
+        a.slice_assign(&[1..3, 0..2], &column).unwrap();
+
+        assert_eq!(
+            a.as_slice(),
+            Some(&[0, 1, 2, 3, -1, -1, 12, 13, -2, -2, 22, 23][..])
+        );
+
+        // Assigning through a transposed (strided) tensor writes the right cells.
+        let mut t = a.permute(&[1, 0]).unwrap();
+        t.slice_fill(&[3..4, 0..3], 7).unwrap();
+        assert_eq!(
+            t.permute(&[1, 0]).unwrap().diagonal(3).unwrap().as_slice(),
+            Some(&[7][..])
+        );
+        assert_eq!(t.get(&[3, 2]), Some(&7));
+        t.slice_fill(&[0..0, 0..3], 5).unwrap();
+        assert!(!t.iter().any(|&x| x == 5));
+    }
+
+    #[test]
+    fn test_slice_assign_rejects_bad_regions() {
+        let mut a = Tensor::new(vec![0; 6], vec![2, 3]).unwrap();
+        let row = Tensor::new(vec![1, 2], vec![2]).unwrap();
+
+        assert!(matches!(
+            a.slice_assign(&[0..2, 0..3], &row),
+            Err(TensorError::ShapeError(_))
+        ));
+        assert!(a.slice_fill(&[0..3, 0..3], 1).is_err());
+        let (start, end) = (2, 1);
+        assert!(a.slice_fill(&[0..2, start..end], 1).is_err());
+        assert!(a.slice_fill(&[0..1, 0..1, 0..1], 1).is_err());
+        assert!(a.iter().all(|&x| x == 0));
+    }
+}
@@ -0,0 +1,71 @@
+//! `serde` support for `Tensor`, available with the `serde` feature.
+//!
+//! A tensor is represented as a struct with a `shape` and a flat `data` sequence
+//! in row-major order. Deserialization validates that the data length matches
+//! the shape.
+
+use serde::de::Error as _;
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::tensor::Tensor;
+
+impl<T: Serialize> Serialize for Tensor<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Tensor", 2)?;
+        state.serialize_field("shape", self.shape())?;
+        state.serialize_field("data", &Elements(self))?;
+        state.end()
+    }
+}
+
+/// Serializes the elements of a tensor in logical order, independent of strides.
+struct Elements<'a, T>(&'a Tensor<T>);
+
+impl<T: Serialize> Serialize for Elements<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for value in self.0.iter() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Tensor")]
+struct TensorRepr<T> {
+    shape: Vec<usize>,
+    data: Vec<T>,
+}
+
+impl<'de, T: Deserialize<'de> + Copy> Deserialize<'de> for Tensor<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = TensorRepr::deserialize(deserializer)?;
+        Tensor::new(repr.data, repr.shape).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let tensor = Tensor::new(vec![1.5, 2.0, -3.0, 4.25], vec![2, 2]).unwrap();
+
+        let json = serde_json::to_string(&tensor).unwrap();
+        let restored: Tensor<f64> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(json, r#"{"shape":[2,2],"data":[1.5,2.0,-3.0,4.25]}"#);
+        assert_eq!(restored, tensor);
+    }
+
+    #[test]
+    fn test_deserialize_rejects_mismatched_length() {
+        let result = serde_json::from_str::<Tensor<i32>>(r#"{"shape":[2,2],"data":[1,2,3]}"#);
+
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("ShapeError"));
+    }
+}
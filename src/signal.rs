@@ -0,0 +1,496 @@
+//! Signal processing: linear filtering, filter design and peak detection.
+//!
+//! Filters run along the last axis; every leading axis is treated as a batch of
+//! independent signals. Peak detection works on a single 1D signal.
+
+use crate::creation::vector_elements;
+use crate::error::TensorError;
+use crate::fft::Complex;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Normalized filter coefficients, padded to a common length.
+struct Coefficients<T> {
+    b: Vec<T>,
+    a: Vec<T>,
+}
+
+impl<T: Float> Coefficients<T> {
+    /// Validates `b` and `a`, divides both by `a[0]` and pads them with zeros to
+    /// the same length.
+    fn new(b: &Tensor<T>, a: &Tensor<T>) -> Result<Self, TensorError> {
+        let mut b = vector_elements(b, "numerator coefficients")?;
+        let mut a = vector_elements(a, "denominator coefficients")?;
+        if b.is_empty() || a.is_empty() {
+            return Err(TensorError::ValueError(
+                "filter coefficients must not be empty".to_string(),
+            ));
+        }
+        let lead = a[0];
+        if lead == T::zero() {
+            return Err(TensorError::ValueError(
+                "the first denominator coefficient must be non-zero".to_string(),
+            ));
+        }
+
+        let order = b.len().max(a.len());
+        b.resize(order, T::zero());
+        a.resize(order, T::zero());
+        for value in b.iter_mut().chain(a.iter_mut()) {
+            *value = *value / lead;
+        }
+        Ok(Self { b, a })
+    }
+
+    /// Runs the filter over `signal` in place, in direct form II transposed,
+    /// starting from the delay-line state `state`.
+    fn apply(&self, signal: &mut [T], state: &mut [T]) {
+        let last = state.len();
+        for sample in signal.iter_mut() {
+            let x = *sample;
+            let y = self.b[0] * x + state.first().copied().unwrap_or(T::zero());
+            for k in 0..last {
+                let next = if k + 1 < last {
+                    state[k + 1]
+                } else {
+                    T::zero()
+                };
+                state[k] = self.b[k + 1] * x + next - self.a[k + 1] * y;
+            }
+            *sample = y;
+        }
+    }
+
+    /// Returns the delay-line state of the filter after a long run of unit
+    /// input, so that a constant signal produces no start-up transient.
+    fn steady_state(&self) -> Vec<T> {
+        let gain = self.b.iter().fold(T::zero(), |acc, &b| acc + b)
+            / self.a.iter().fold(T::zero(), |acc, &a| acc + a);
+        let mut state = vec![T::zero(); self.b.len() - 1];
+        let mut carry = T::zero();
+        for k in (0..state.len()).rev() {
+            carry = carry + self.b[k + 1] - self.a[k + 1] * gain;
+            state[k] = carry;
+        }
+        state
+    }
+}
+
+/// Applies `f` to every lane along the last axis of a contiguous copy of `x`.
+fn map_lanes<T: Float>(
+    x: &Tensor<T>,
+    mut f: impl FnMut(&mut [T]) -> Result<(), TensorError>,
+) -> Result<Tensor<T>, TensorError> {
+    let Some(&len) = x.shape().last() else {
+        return Err(TensorError::ShapeError(
+            "cannot filter a 0-dimensional tensor".to_string(),
+        ));
+    };
+
+    let mut result = x.to_contiguous();
+    if len > 0 {
+        for lane in result.data.chunks_mut(len) {
+            f(lane)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Filters `x` along its last axis with the rational transfer function `b / a`.
+///
+/// This is the direct form II transposed difference equation
+/// `a[0] y[n] = b[0] x[n] + b[1] x[n-1] + ... - a[1] y[n-1] - ...`, started from
+/// rest, matching SciPy's `lfilter`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `b` or `a` is not 1D or `x` is
+/// 0-dimensional, and `TensorError::ValueError` if a coefficient list is empty or
+/// `a[0]` is zero.
+pub fn lfilter<T: Float>(
+    b: &Tensor<T>,
+    a: &Tensor<T>,
+    x: &Tensor<T>,
+) -> Result<Tensor<T>, TensorError> {
+    let filter = Coefficients::new(b, a)?;
+    map_lanes(x, |lane| {
+        filter.apply(lane, &mut vec![T::zero(); filter.b.len() - 1]);
+        Ok(())
+    })
+}
+
+/// Filters `x` forwards and then backwards along its last axis, giving a
+/// zero-phase result with the squared magnitude response of `b / a`.
+///
+/// Like SciPy's `filtfilt`, each signal is first extended at both ends by an
+/// odd reflection of `3 * max(b.len(), a.len())` samples, and both passes start
+/// from the filter's steady state to suppress edge transients.
+///
+/// # Errors
+///
+/// Returns the errors of [`lfilter`], and `TensorError::ValueError` if the last
+/// axis of `x` is not longer than the padding.
+pub fn filtfilt<T: Float>(
+    b: &Tensor<T>,
+    a: &Tensor<T>,
+    x: &Tensor<T>,
+) -> Result<Tensor<T>, TensorError> {
+    let filter = Coefficients::new(b, a)?;
+    let pad = 3 * filter.b.len();
+    let steady = filter.steady_state();
+
+    map_lanes(x, |lane| {
+        let n = lane.len();
+        if n <= pad {
+            return Err(TensorError::ValueError(format!(
+                "filtfilt needs signals longer than {} samples, got {}",
+                pad, n
+            )));
+        }
+
+        let two = T::one() + T::one();
+        let (first, last) = (lane[0], lane[n - 1]);
+        let mut extended: Vec<T> = (1..=pad).rev().map(|i| two * first - lane[i]).collect();
+        extended.extend_from_slice(lane);
+        extended.extend((1..=pad).map(|i| two * last - lane[n - 1 - i]));
+
+        for _ in 0..2 {
+            let start = extended[0];
+            let mut state: Vec<T> = steady.iter().map(|&s| s * start).collect();
+            filter.apply(&mut extended, &mut state);
+            extended.reverse();
+        }
+
+        lane.copy_from_slice(&extended[pad..pad + n]);
+        Ok(())
+    })
+}
+
+/// Expands the monic polynomial with the given roots, highest power first.
+fn poly(roots: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let mut coefficients = vec![Complex::new(1.0, 0.0)];
+    for &root in roots {
+        coefficients.push(Complex::new(0.0, 0.0));
+        for i in (1..coefficients.len()).rev() {
+            coefficients[i] = coefficients[i].sub(root.mul(coefficients[i - 1]));
+        }
+    }
+    coefficients
+}
+
+/// Designs a digital Butterworth low-pass filter of the given `order`,
+/// returning the `(b, a)` coefficients for [`lfilter`] and [`filtfilt`].
+///
+/// `cutoff` is the -3 dB frequency as a fraction of the Nyquist frequency. The
+/// analog prototype is mapped with the bilinear transform after pre-warping, as
+/// in SciPy's `butter(order, cutoff)`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ValueError` if `order` is zero or `cutoff` is not in
+/// the open interval `(0, 1)`.
+pub fn butterworth<T: Float>(
+    order: usize,
+    cutoff: f64,
+) -> Result<(Tensor<T>, Tensor<T>), TensorError> {
+    if order == 0 {
+        return Err(TensorError::ValueError(
+            "butterworth order must be at least 1".to_string(),
+        ));
+    }
+    if !(cutoff > 0.0 && cutoff < 1.0) {
+        return Err(TensorError::ValueError(format!(
+            "butterworth cutoff must be in (0, 1), got {}",
+            cutoff
+        )));
+    }
+
+    // Pre-warped analog cutoff for a bilinear transform at a sample rate of 2.
+    let fs2 = 4.0;
+    let warped = fs2 * (std::f64::consts::PI * cutoff / 2.0).tan();
+    let n = order as f64;
+    let analog: Vec<Complex<f64>> = (0..order)
+        .map(|k| {
+            let angle = std::f64::consts::PI * (2.0 * k as f64 + 1.0 - n) / (2.0 * n);
+            Complex::new(-warped * angle.cos(), -warped * angle.sin())
+        })
+        .collect();
+
+    let fs2 = Complex::new(fs2, 0.0);
+    let poles: Vec<Complex<f64>> = analog.iter().map(|&p| fs2.add(p).div(fs2.sub(p))).collect();
+    let denominator = analog
+        .iter()
+        .fold(Complex::new(1.0, 0.0), |acc, &p| acc.mul(fs2.sub(p)));
+    let gain = warped.powi(order as i32) * Complex::new(1.0, 0.0).div(denominator).re;
+
+    let zeros = vec![Complex::new(-1.0, 0.0); order];
+    let b = poly(&zeros)
+        .iter()
+        .map(|c| T::from_f64(gain * c.re))
+        .collect();
+    let a = poly(&poles).iter().map(|c| T::from_f64(c.re)).collect();
+    Ok((
+        Tensor::new(b, vec![order + 1])?,
+        Tensor::new(a, vec![order + 1])?,
+    ))
+}
+
+/// Peaks found by [`find_peaks`], with one entry per peak in every tensor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Peaks<T> {
+    /// Positions of the peaks, in increasing order.
+    pub indices: Tensor<usize>,
+    /// Values of the signal at the peaks.
+    pub heights: Tensor<T>,
+    /// How far each peak rises above the higher of its two surrounding minima.
+    pub prominences: Tensor<T>,
+}
+
+/// Finds local maxima of the 1D signal `x`.
+///
+/// A peak is a sample larger than both neighbours; a flat plateau counts once,
+/// at its middle sample. Candidates are then filtered, in this order, by:
+///
+/// - `height`: the peak value must be at least this large;
+/// - `distance`: peaks closer than this many samples to a higher kept peak are
+///   dropped;
+/// - `prominence`: the peak must rise at least this far above its bases.
+///
+/// This follows SciPy's `find_peaks`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `x` is not 1D and
+/// `TensorError::ValueError` if `distance` is zero.
+pub fn find_peaks<T: Float>(
+    x: &Tensor<T>,
+    height: Option<T>,
+    distance: Option<usize>,
+    prominence: Option<T>,
+) -> Result<Peaks<T>, TensorError> {
+    let x = vector_elements(x, "find_peaks input")?;
+    if distance == Some(0) {
+        return Err(TensorError::ValueError(
+            "peak distance must be at least 1".to_string(),
+        ));
+    }
+
+    let mut peaks = local_maxima(&x);
+    if let Some(height) = height {
+        peaks.retain(|&i| x[i] >= height);
+    }
+    if let Some(distance) = distance {
+        peaks = enforce_distance(&x, &peaks, distance);
+    }
+    let mut prominences: Vec<T> = peaks.iter().map(|&i| peak_prominence(&x, i)).collect();
+    if let Some(prominence) = prominence {
+        let (kept, kept_prominences) = peaks
+            .iter()
+            .zip(&prominences)
+            .filter(|&(_, &p)| p >= prominence)
+            .unzip();
+        (peaks, prominences) = (kept, kept_prominences);
+    }
+
+    let n = peaks.len();
+    let heights = peaks.iter().map(|&i| x[i]).collect();
+    Ok(Peaks {
+        indices: Tensor::new(peaks, vec![n])?,
+        heights: Tensor::new(heights, vec![n])?,
+        prominences: Tensor::new(prominences, vec![n])?,
+    })
+}
+
+/// Returns every local maximum, taking the middle of flat plateaus.
+fn local_maxima<T: Float>(x: &[T]) -> Vec<usize> {
+    let mut peaks = Vec::new();
+    let mut i = 1;
+    while i + 1 < x.len() {
+        if x[i - 1] < x[i] {
+            let mut ahead = i + 1;
+            while ahead + 1 < x.len() && x[ahead] == x[i] {
+                ahead += 1;
+            }
+            if x[ahead] < x[i] {
+                peaks.push((i + ahead - 1) / 2);
+                i = ahead;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    peaks
+}
+
+/// Keeps the highest peaks such that no two are closer than `distance` samples.
+fn enforce_distance<T: Float>(x: &[T], peaks: &[usize], distance: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..peaks.len()).collect();
+    // Highest first; ties keep the later peak first, as SciPy does.
+    order.sort_by(|&a, &b| {
+        x[peaks[b]]
+            .partial_cmp(&x[peaks[a]])
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.cmp(&a))
+    });
+
+    let mut keep = vec![true; peaks.len()];
+    for &k in &order {
+        if !keep[k] {
+            continue;
+        }
+        for j in (0..k).rev().take_while(|&j| peaks[k] - peaks[j] < distance) {
+            keep[j] = false;
+        }
+        for j in (k + 1..peaks.len()).take_while(|&j| peaks[j] - peaks[k] < distance) {
+            keep[j] = false;
+        }
+    }
+    peaks
+        .iter()
+        .zip(keep)
+        .filter_map(|(&i, kept)| kept.then_some(i))
+        .collect()
+}
+
+/// Returns the prominence of the peak at `peak`: its height above the higher
+/// of the lowest points reached on each side before meeting a higher sample.
+fn peak_prominence<T: Float>(x: &[T], peak: usize) -> T {
+    let value = x[peak];
+    let lowest = |range: &mut dyn Iterator<Item = usize>| {
+        let mut minimum = value;
+        for i in range {
+            if x[i] > value {
+                break;
+            }
+            if x[i] < minimum {
+                minimum = x[i];
+            }
+        }
+        minimum
+    };
+
+    let left = lowest(&mut (0..peak).rev());
+    let right = lowest(&mut (peak + 1..x.len()));
+    value - if left > right { left } else { right }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lfilter_fir_and_iir() {
+        let x = Tensor::new(vec![1.0, 0.0, 0.0, 0.0, 2.0, 0.0], vec![2, 3]).unwrap();
+
+        let moving = lfilter(
+            &Tensor::new(vec![0.5, 0.5], vec![2]).unwrap(),
+            &Tensor::new(vec![1.0], vec![1]).unwrap(),
+            &x,
+        )
+        .unwrap();
+        assert_eq!(moving.as_slice(), Some(&[0.5, 0.5, 0.0, 0.0, 1.0, 1.0][..]));
+
+        // y[n] = x[n] + 0.5 y[n-1], scaled by a[0] = 2.
+        let decay = lfilter(
+            &Tensor::new(vec![2.0], vec![1]).unwrap(),
+            &Tensor::new(vec![2.0, -1.0], vec![2]).unwrap(),
+            &x,
+        )
+        .unwrap();
+        assert_eq!(decay.as_slice(), Some(&[1.0, 0.5, 0.25, 0.0, 2.0, 1.0][..]));
+    }
+
+    #[test]
+    fn test_lfilter_rejects_bad_coefficients() {
+        let x = Tensor::new(vec![1.0, 2.0], vec![2]).unwrap();
+        let b = Tensor::new(vec![1.0], vec![1]).unwrap();
+
+        let zero = Tensor::new(vec![0.0, 1.0], vec![2]).unwrap();
+        assert!(matches!(
+            lfilter(&b, &zero, &x),
+            Err(TensorError::ValueError(_))
+        ));
+        let empty = Tensor::new(vec![], vec![0]).unwrap();
+        assert!(lfilter(&empty, &b, &x).is_err());
+        let scalar = Tensor::new(vec![1.0], vec![]).unwrap();
+        assert!(matches!(
+            lfilter(&b, &b, &scalar),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_butterworth_coefficients() {
+        let (b, a) = butterworth::<f64>(2, 0.5).unwrap();
+        let expected_b =
+            Tensor::new(vec![0.292_893_22, 0.585_786_44, 0.292_893_22], vec![3]).unwrap();
+        let expected_a = Tensor::new(vec![1.0, 0.0, 0.171_572_88], vec![3]).unwrap();
+        assert!(b.max_abs_diff(&expected_b).unwrap() < 1e-8);
+        assert!(a.max_abs_diff(&expected_a).unwrap() < 1e-8);
+
+        // Unit gain at DC for any order and cutoff.
+        let (b, a) = butterworth::<f64>(5, 0.13).unwrap();
+        assert!((b.sum() / a.sum() - 1.0).abs() < 1e-9);
+
+        assert!(butterworth::<f64>(0, 0.5).is_err());
+        assert!(butterworth::<f32>(3, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_filtfilt_is_zero_phase() {
+        let (b, a) = butterworth::<f64>(4, 0.2).unwrap();
+        let slow = Tensor::from_fn(vec![2, 200], |index| {
+            let t = index[1] as f64;
+            if index[0] == 0 { 3.0 } else { (t * 0.02).sin() }
+        });
+
+        let result = filtfilt(&b, &a, &slow).unwrap();
+
+        assert_eq!(result.shape(), &[2, 200]);
+        // A constant passes unchanged and a slow sine is neither delayed nor attenuated.
+        assert!(result.max_abs_diff(&slow).unwrap() < 1e-3);
+        let short = Tensor::new(vec![1.0; 10], vec![10]).unwrap();
+        assert!(matches!(
+            filtfilt(&b, &a, &short),
+            Err(TensorError::ValueError(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_peaks_with_plateaus() {
+        let x = Tensor::new(
+            vec![0.0, 2.0, 1.0, 3.0, 3.0, 3.0, 0.0, 1.0, 1.0, 4.0],
+            vec![10],
+        )
+        .unwrap();
+
+        let peaks = find_peaks(&x, None, None, None).unwrap();
+
+        // The plateau at 3..=5 reports its middle sample; edges are never peaks.
+        assert_eq!(peaks.indices.as_slice(), Some(&[1, 4][..]));
+        assert_eq!(peaks.heights.as_slice(), Some(&[2.0, 3.0][..]));
+        assert_eq!(peaks.prominences.as_slice(), Some(&[1.0, 3.0][..]));
+    }
+
+    #[test]
+    fn test_find_peaks_filters() {
+        let x = Tensor::new(vec![0.0, 5.0, 4.0, 6.0, 0.0, 1.0, 0.5, 2.0, 0.0], vec![9]).unwrap();
+
+        let tall = find_peaks(&x, Some(1.5), None, None).unwrap();
+        assert_eq!(tall.indices.as_slice(), Some(&[1, 3, 7][..]));
+
+        let spaced = find_peaks(&x, None, Some(3), None).unwrap();
+        assert_eq!(spaced.indices.as_slice(), Some(&[3, 7][..]));
+
+        let prominent = find_peaks(&x, None, None, Some(1.5)).unwrap();
+        assert_eq!(prominent.indices.as_slice(), Some(&[3, 7][..]));
+        assert_eq!(prominent.prominences.as_slice(), Some(&[6.0, 2.0][..]));
+
+        assert!(find_peaks(&x, None, Some(0), None).is_err());
+        let matrix = Tensor::new(vec![0.0; 4], vec![2, 2]).unwrap();
+        assert!(matches!(
+            find_peaks(&matrix, None, None, None),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+}
@@ -0,0 +1,666 @@
+//! Sorting, argsorting, top-k selection, unique values and quantiles.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Orders `a` and `b` ascending, placing NaN and other unordered values last.
+pub(crate) fn total_cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    match a.partial_cmp(b) {
+        Some(ordering) => ordering,
+        None => {
+            let a_unordered = a.partial_cmp(a).is_none();
+            let b_unordered = b.partial_cmp(b).is_none();
+            a_unordered.cmp(&b_unordered)
+        }
+    }
+}
+
+/// A candidate kept by [`Tensor::topk`], ordered so that the heap's maximum is
+/// the worst candidate still kept.
+struct Candidate<T> {
+    value: T,
+    index: usize,
+    largest: bool,
+}
+
+impl<T: PartialOrd> Ord for Candidate<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_value = if self.largest {
+            total_cmp(&other.value, &self.value)
+        } else {
+            total_cmp(&self.value, &other.value)
+        };
+        // Among equal values the earlier index is better.
+        by_value.then(self.index.cmp(&other.index))
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for Candidate<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> PartialEq for Candidate<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd> Eq for Candidate<T> {}
+
+impl<T: Copy> Tensor<T> {
+    /// Sorts every lane along `axis` in place with the comparator `compare`.
+    ///
+    /// The sort is stable: equal elements keep their relative order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn sort_mut_by<F>(&mut self, axis: usize, mut compare: F) -> Result<(), TensorError>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let (_, starts) = self.lanes(axis)?;
+        let len = self.shape[axis];
+        let stride = self.strides[axis];
+
+        let mut lane = Vec::with_capacity(len);
+        for start in starts {
+            lane.clear();
+            lane.extend((0..len).map(|k| self.data[start + k * stride]));
+            lane.sort_by(&mut compare);
+            for (k, &value) in lane.iter().enumerate() {
+                self.data[start + k * stride] = value;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy of the tensor with every lane along `axis` sorted by
+    /// `compare`. The sort is stable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn sort_by<F>(&self, axis: usize, compare: F) -> Result<Tensor<T>, TensorError>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut sorted = self.to_contiguous();
+        sorted.sort_mut_by(axis, compare)?;
+        Ok(sorted)
+    }
+}
+
+impl<T: Copy + PartialOrd> Tensor<T> {
+    /// Returns a copy of the tensor with every lane along `axis` sorted in
+    /// ascending order. NaN values sort to the end, as in NumPy.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let t = Tensor::new(vec![3, 1, 2, 9, 7, 8], vec![2, 3]).unwrap();
+    /// let sorted = t.sort(1).unwrap();
+    /// assert_eq!(sorted.as_slice(), Some(&[1, 2, 3, 7, 8, 9][..]));
+    /// ```
+    pub fn sort(&self, axis: usize) -> Result<Tensor<T>, TensorError> {
+        self.sort_by(axis, total_cmp)
+    }
+
+    /// Returns the indices that sort every lane along `axis` in ascending order.
+    ///
+    /// The result has the shape of `self`; entry `k` of each lane is the position
+    /// within that lane of its `k`-th smallest element. The sort is stable, so
+    /// ties keep their original order, and NaN values sort to the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let scores = Tensor::new(vec![0.3, 0.9, 0.1], vec![3]).unwrap();
+    /// assert_eq!(scores.argsort(0).unwrap().as_slice(), Some(&[2, 0, 1][..]));
+    /// ```
+    pub fn argsort(&self, axis: usize) -> Result<Tensor<usize>, TensorError> {
+        let (_, starts) = self.lanes(axis)?;
+        let len = self.shape[axis];
+        let stride = self.strides[axis];
+
+        let mut result = Tensor::new(vec![0; self.len()], self.shape.clone())?;
+        let (_, targets) = result.lanes(axis)?;
+        let target_stride = result.strides[axis];
+
+        let mut order = Vec::with_capacity(len);
+        for (start, target) in starts.into_iter().zip(targets) {
+            order.clear();
+            order.extend(0..len);
+            order.sort_by(|&a, &b| {
+                total_cmp(
+                    &self.data[start + a * stride],
+                    &self.data[start + b * stride],
+                )
+            });
+            for (k, &index) in order.iter().enumerate() {
+                result.data[target + k * target_stride] = index;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the `k` largest elements of every lane along `axis`, or the `k`
+    /// smallest if `largest` is `false`, together with their positions in the lane.
+    ///
+    /// Both results have the shape of `self` with `axis` shortened to `k`, and
+    /// each lane is ordered best first; ties favour the earlier position. Lanes
+    /// are scanned once with a heap of `k` candidates rather than fully sorted.
+    /// NaN compares greater than every number.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds and
+    /// `TensorError::ValueError` if `k` exceeds the length of `axis`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let scores = Tensor::new(vec![0.1, 0.7, 0.3, 0.9], vec![4]).unwrap();
+    /// let (values, indices) = scores.topk(2, 0, true).unwrap();
+    /// assert_eq!(values.as_slice(), Some(&[0.9, 0.7][..]));
+    /// assert_eq!(indices.as_slice(), Some(&[3, 1][..]));
+    /// ```
+    pub fn topk(
+        &self,
+        k: usize,
+        axis: usize,
+        largest: bool,
+    ) -> Result<(Tensor<T>, Tensor<usize>), TensorError> {
+        let (_, starts) = self.lanes(axis)?;
+        let len = self.shape[axis];
+        let stride = self.strides[axis];
+        if k > len {
+            return Err(TensorError::ValueError(format!(
+                "topk k = {} exceeds the length {} of axis {}",
+                k, len, axis
+            )));
+        }
+
+        let mut shape = self.shape.clone();
+        shape[axis] = k;
+        let count = starts.len() * k;
+        let mut indices = Tensor::new(vec![0; count], shape.clone())?;
+        let (_, targets) = indices.lanes(axis)?;
+        let target_stride = indices.strides[axis];
+        let mut values = match starts.first() {
+            Some(&first) if count > 0 => vec![self.data[first]; count],
+            _ => Vec::new(),
+        };
+
+        for (start, target) in starts.into_iter().zip(targets) {
+            let mut heap = BinaryHeap::with_capacity(k + 1);
+            for index in 0..len {
+                heap.push(Candidate {
+                    value: self.data[start + index * stride],
+                    index,
+                    largest,
+                });
+                if heap.len() > k {
+                    heap.pop();
+                }
+            }
+            for (rank, candidate) in heap.into_sorted_vec().into_iter().enumerate() {
+                values[target + rank * target_stride] = candidate.value;
+                indices.data[target + rank * target_stride] = candidate.index;
+            }
+        }
+
+        Ok((Tensor::new(values, shape)?, indices))
+    }
+
+    /// Sorts every lane along `axis` in ascending order in place. NaN values
+    /// sort to the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn sort_mut(&mut self, axis: usize) -> Result<(), TensorError> {
+        self.sort_mut_by(axis, total_cmp)
+    }
+}
+
+/// The distinct values of a tensor, returned by [`Tensor::unique`] and
+/// [`Tensor::unique_tol`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unique<T> {
+    /// The distinct values in ascending order, as a 1D tensor.
+    pub values: Tensor<T>,
+    /// How many elements fell into each entry of `values`.
+    pub counts: Tensor<usize>,
+    /// For every element of the original tensor, the position of its value in
+    /// `values`; this has the shape of the original tensor.
+    pub inverse: Tensor<usize>,
+}
+
+impl<T: Copy> Tensor<T> {
+    /// Groups the elements sorted by `compare`, starting a new group whenever
+    /// `same(first, current)` is `false` for the group's first element.
+    fn unique_by<C, S>(&self, compare: C, same: S) -> Unique<T>
+    where
+        C: Fn(&T, &T) -> Ordering,
+        S: Fn(&T, &T) -> bool,
+    {
+        let mut order: Vec<(T, usize)> = self.iter().copied().zip(0..).collect();
+        order.sort_by(|a, b| compare(&a.0, &b.0));
+
+        let mut values: Vec<T> = Vec::new();
+        let mut counts: Vec<usize> = Vec::new();
+        let mut inverse = vec![0; order.len()];
+        for (value, position) in order {
+            match values.last() {
+                Some(first) if same(first, &value) => *counts.last_mut().unwrap() += 1,
+                _ => {
+                    values.push(value);
+                    counts.push(1);
+                }
+            }
+            inverse[position] = values.len() - 1;
+        }
+
+        let n = values.len();
+        Unique {
+            values: Tensor::new(values, vec![n]).unwrap(),
+            counts: Tensor::new(counts, vec![n]).unwrap(),
+            inverse: Tensor::new(inverse, self.shape.clone()).unwrap(),
+        }
+    }
+}
+
+impl<T: Copy + Ord> Tensor<T> {
+    /// Returns the sorted distinct values of the tensor with their counts and
+    /// the inverse mapping from every element to its value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let labels = Tensor::new(vec![3, 1, 3, 2], vec![4]).unwrap();
+    /// let unique = labels.unique();
+    /// assert_eq!(unique.values.as_slice(), Some(&[1, 2, 3][..]));
+    /// assert_eq!(unique.counts.as_slice(), Some(&[1, 1, 2][..]));
+    /// assert_eq!(unique.inverse.as_slice(), Some(&[2, 0, 2, 1][..]));
+    /// ```
+    pub fn unique(&self) -> Unique<T> {
+        self.unique_by(T::cmp, |a, b| a == b)
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Like [`Tensor::unique`], but treats values within `tol` of the smallest
+    /// value of a group as equal to it.
+    ///
+    /// Groups are formed greedily in ascending order and represented by their
+    /// smallest member. NaN values sort last and each forms its own group.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `tol` is negative or NaN.
+    pub fn unique_tol(&self, tol: T) -> Result<Unique<T>, TensorError> {
+        if matches!(tol.partial_cmp(&T::zero()), None | Some(Ordering::Less)) {
+            return Err(TensorError::ValueError(
+                "unique tolerance must be non-negative".to_string(),
+            ));
+        }
+        Ok(self.unique_by(total_cmp, |first, value| *value - *first <= tol))
+    }
+}
+
+/// How [`Tensor::quantile`] picks a value when the requested position falls
+/// between two order statistics `lower <= higher`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Interpolates linearly between `lower` and `higher`.
+    Linear,
+    /// Takes the closer of the two, rounding ties to the even position as NumPy does.
+    Nearest,
+    /// Takes `lower`.
+    Lower,
+    /// Takes `higher`.
+    Higher,
+}
+
+/// Returns the `q`-th quantile of `values`, reordering them in the process.
+///
+/// Uses quickselect rather than a full sort, and returns NaN if `values` is
+/// empty or contains NaN.
+fn select_quantile<T: Float>(values: &mut [T], q: f64, interpolation: Interpolation) -> T {
+    if values.is_empty() || values.iter().any(|v| v.is_nan()) {
+        return T::from_f64(f64::NAN);
+    }
+
+    let position = q * (values.len() - 1) as f64;
+    let below = position.floor() as usize;
+    let fraction = position - below as f64;
+    let index = match interpolation {
+        Interpolation::Lower => below,
+        Interpolation::Higher => position.ceil() as usize,
+        Interpolation::Nearest => {
+            if fraction > 0.5 || (fraction == 0.5 && below % 2 == 1) {
+                below + 1
+            } else {
+                below
+            }
+        }
+        Interpolation::Linear => {
+            let (_, &mut lower, above) = values.select_nth_unstable_by(below, total_cmp);
+            if fraction == 0.0 {
+                return lower;
+            }
+            // Everything after the selected element is at least as large, so
+            // the next order statistic is the smallest of them.
+            let higher = above.iter().copied().min_by(total_cmp).unwrap_or(lower);
+            return lower + (higher - lower) * T::from_f64(fraction);
+        }
+    };
+    *values.select_nth_unstable_by(index, total_cmp).1
+}
+
+/// Checks that the quantile `q` lies in `[0, 1]`.
+fn check_quantile(q: f64) -> Result<(), TensorError> {
+    if (0.0..=1.0).contains(&q) {
+        Ok(())
+    } else {
+        Err(TensorError::ValueError(format!(
+            "quantile must be in [0, 1], got {}",
+            q
+        )))
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns the `q`-th quantile of all elements, for `q` in `[0, 1]`.
+    ///
+    /// The quantile sits at position `q * (n - 1)` of the sorted elements, with
+    /// `interpolation` deciding between the neighbouring order statistics when
+    /// that position is fractional. The result is NaN for an empty tensor or
+    /// one containing NaN.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `q` is outside `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::sorting::Interpolation;
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let x = Tensor::new(vec![4.0, 1.0, 3.0, 2.0], vec![4]).unwrap();
+    /// assert_eq!(x.quantile(0.5, Interpolation::Linear).unwrap(), 2.5);
+    /// assert_eq!(x.quantile(0.5, Interpolation::Lower).unwrap(), 2.0);
+    /// assert_eq!(x.quantile(0.5, Interpolation::Higher).unwrap(), 3.0);
+    /// ```
+    pub fn quantile(&self, q: f64, interpolation: Interpolation) -> Result<T, TensorError> {
+        check_quantile(q)?;
+        let mut values: Vec<T> = self.iter().copied().collect();
+        Ok(select_quantile(&mut values, q, interpolation))
+    }
+
+    /// Returns the `q`-th quantile along `axis`, dropping that axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `q` is outside `[0, 1]` and
+    /// `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn quantile_axis(
+        &self,
+        q: f64,
+        axis: usize,
+        interpolation: Interpolation,
+    ) -> Result<Tensor<T>, TensorError> {
+        check_quantile(q)?;
+        let (shape, starts) = self.lanes(axis)?;
+        let len = self.shape[axis];
+        let stride = self.strides[axis];
+
+        let mut lane = Vec::with_capacity(len);
+        let data = starts
+            .into_iter()
+            .map(|start| {
+                lane.clear();
+                lane.extend((0..len).map(|k| self.data[start + k * stride]));
+                select_quantile(&mut lane, q, interpolation)
+            })
+            .collect();
+        Tensor::new(data, shape)
+    }
+
+    /// Returns the `p`-th percentile of all elements, for `p` in `[0, 100]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `p` is outside `[0, 100]`.
+    pub fn percentile(&self, p: f64, interpolation: Interpolation) -> Result<T, TensorError> {
+        self.quantile(p / 100.0, interpolation)
+    }
+
+    /// Returns the `p`-th percentile along `axis`, dropping that axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `p` is outside `[0, 100]` and
+    /// `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn percentile_axis(
+        &self,
+        p: f64,
+        axis: usize,
+        interpolation: Interpolation,
+    ) -> Result<Tensor<T>, TensorError> {
+        self.quantile_axis(p / 100.0, axis, interpolation)
+    }
+
+    /// Returns the median of all elements, averaging the two middle values
+    /// when there is an even number of them. The result is NaN for an empty
+    /// tensor or one containing NaN.
+    pub fn median(&self) -> T {
+        let mut values: Vec<T> = self.iter().copied().collect();
+        select_quantile(&mut values, 0.5, Interpolation::Linear)
+    }
+
+    /// Returns the median along `axis`, dropping that axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::AxisError` if `axis` is out of bounds.
+    pub fn median_axis(&self, axis: usize) -> Result<Tensor<T>, TensorError> {
+        self.quantile_axis(0.5, axis, Interpolation::Linear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_along_each_axis() {
+        let tensor = Tensor::new(vec![3, 1, 2, 0, 5, 4], vec![2, 3]).unwrap();
+
+        assert_eq!(tensor.sort(0).unwrap().data, vec![0, 1, 2, 3, 5, 4]);
+        assert_eq!(tensor.sort(1).unwrap().data, vec![1, 2, 3, 0, 4, 5]);
+        assert!(matches!(tensor.sort(2), Err(TensorError::AxisError(_))));
+    }
+
+    #[test]
+    fn test_sort_places_nan_last() {
+        let tensor = Tensor::new(vec![2.0, f64::NAN, -1.0, 0.5], vec![4]).unwrap();
+
+        let sorted = tensor.sort(0).unwrap();
+
+        assert_eq!(&sorted.data[..3], &[-1.0, 0.5, 2.0]);
+        assert!(sorted.data[3].is_nan());
+    }
+
+    #[test]
+    fn test_sort_by_and_sort_mut_on_strided_tensor() {
+        let mut tensor = Tensor::new(vec![1, 4, 2, 3], vec![2, 2])
+            .unwrap()
+            .permute(&[1, 0])
+            .unwrap();
+
+        let descending = tensor.sort_by(1, |a, b| b.cmp(a)).unwrap();
+        assert_eq!(descending.data, vec![2, 1, 4, 3]);
+
+        tensor.sort_mut(0).unwrap();
+        assert_eq!(tensor.iter().copied().collect::<Vec<_>>(), vec![1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn test_argsort_is_stable_along_each_axis() {
+        let tensor = Tensor::new(vec![2, 1, 2, 0, 3, 1], vec![2, 3]).unwrap();
+
+        assert_eq!(tensor.argsort(1).unwrap().data, vec![1, 0, 2, 0, 2, 1]);
+        assert_eq!(tensor.argsort(0).unwrap().data, vec![1, 0, 1, 0, 1, 0]);
+
+        let strided = tensor.permute(&[1, 0]).unwrap();
+        let indices = strided.argsort(1).unwrap();
+        assert_eq!(indices.shape(), &[3, 2]);
+        assert_eq!(indices.data, vec![1, 0, 0, 1, 1, 0]);
+        assert!(tensor.argsort(2).is_err());
+    }
+
+    #[test]
+    fn test_topk_largest_and_smallest() {
+        let tensor = Tensor::new(vec![4, 1, 4, 9, 2, 7, 3, 8], vec![2, 4]).unwrap();
+
+        let (values, indices) = tensor.topk(2, 1, true).unwrap();
+        assert_eq!(values.shape(), &[2, 2]);
+        assert_eq!(values.data, vec![9, 4, 8, 7]);
+        // Of the tied 4s at positions 0 and 2, the earlier one is kept.
+        assert_eq!(indices.data, vec![3, 0, 3, 1]);
+
+        let (values, indices) = tensor.topk(1, 0, false).unwrap();
+        assert_eq!(values.shape(), &[1, 4]);
+        assert_eq!(values.data, vec![2, 1, 3, 8]);
+        assert_eq!(indices.data, vec![1, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_topk_edge_cases() {
+        let tensor = Tensor::new(vec![1.0, f64::NAN, 3.0], vec![3]).unwrap();
+
+        let (values, _) = tensor.topk(1, 0, true).unwrap();
+        assert!(values.data[0].is_nan());
+        let (values, indices) = tensor.topk(0, 0, true).unwrap();
+        assert_eq!(values.shape(), &[0]);
+        assert_eq!(indices.len(), 0);
+        assert!(matches!(
+            tensor.topk(4, 0, true),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            tensor.topk(1, 1, true),
+            Err(TensorError::AxisError(_))
+        ));
+    }
+
+    #[test]
+    fn test_unique_with_counts_and_inverse() {
+        let tensor = Tensor::new(vec![5, 1, 5, 2, 1, 5], vec![2, 3]).unwrap();
+
+        let unique = tensor.unique();
+
+        assert_eq!(unique.values.data, vec![1, 2, 5]);
+        assert_eq!(unique.counts.data, vec![2, 1, 3]);
+        assert_eq!(unique.inverse.shape(), &[2, 3]);
+        assert_eq!(unique.inverse.data, vec![2, 0, 2, 1, 0, 2]);
+        assert_eq!(
+            Tensor::<i32>::new(vec![], vec![0])
+                .unwrap()
+                .unique()
+                .values
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_unique_tol_groups_close_floats() {
+        let tensor = Tensor::new(vec![1.0, 1.05, 2.0, 0.98, f64::NAN], vec![5]).unwrap();
+
+        let unique = tensor.unique_tol(0.1).unwrap();
+
+        assert_eq!(&unique.values.data[..2], &[0.98, 2.0]);
+        assert!(unique.values.data[2].is_nan());
+        assert_eq!(unique.counts.data, vec![3, 1, 1]);
+        assert_eq!(unique.inverse.data, vec![0, 0, 1, 0, 2]);
+        assert!(tensor.unique_tol(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_quantile_interpolation_modes() {
+        let tensor = Tensor::new(vec![7.0, 1.0, 5.0, 3.0, 9.0], vec![5]).unwrap();
+
+        // Position 0.3 * 4 = 1.2 lies between the order statistics 3 and 5.
+        let at = |interpolation| tensor.quantile(0.3, interpolation).unwrap();
+        assert!((at(Interpolation::Linear) - 3.4).abs() < 1e-12);
+        assert_eq!(at(Interpolation::Lower), 3.0);
+        assert_eq!(at(Interpolation::Higher), 5.0);
+        assert_eq!(at(Interpolation::Nearest), 3.0);
+        // Position 0.625 * 4 = 2.5 is a tie and rounds to the even position 2.
+        assert_eq!(tensor.quantile(0.625, Interpolation::Nearest).unwrap(), 5.0);
+        assert_eq!(tensor.quantile(1.0, Interpolation::Linear).unwrap(), 9.0);
+        assert_eq!(tensor.percentile(25.0, Interpolation::Linear).unwrap(), 3.0);
+        assert_eq!(tensor.median(), 5.0);
+        assert!(tensor.quantile(1.5, Interpolation::Linear).is_err());
+        assert!(tensor.percentile(-1.0, Interpolation::Linear).is_err());
+    }
+
+    #[test]
+    fn test_median_and_quantile_along_axis() {
+        let tensor = Tensor::new(vec![3.0, 1.0, 2.0, 8.0, 4.0, 6.0, 0.0, 5.0], vec![2, 4]).unwrap();
+
+        assert_eq!(tensor.median_axis(1).unwrap().data, vec![2.5, 4.5]);
+        assert_eq!(
+            tensor.median_axis(0).unwrap().data,
+            vec![3.5, 3.5, 1.0, 6.5]
+        );
+        assert_eq!(
+            tensor
+                .percentile_axis(100.0, 1, Interpolation::Lower)
+                .unwrap()
+                .data,
+            vec![8.0, 6.0]
+        );
+        assert_eq!(tensor.median(), 3.5);
+        assert!(tensor.median_axis(2).is_err());
+
+        let with_nan = Tensor::new(vec![1.0, f64::NAN, 2.0, 3.0], vec![2, 2]).unwrap();
+        let medians = with_nan.median_axis(1).unwrap();
+        assert!(medians.data[0].is_nan());
+        assert_eq!(medians.data[1], 2.5);
+        assert!(
+            Tensor::<f32>::new(vec![], vec![0])
+                .unwrap()
+                .median()
+                .is_nan()
+        );
+    }
+}
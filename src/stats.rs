@@ -0,0 +1,1200 @@
+//! Statistical estimators and information-theoretic measures.
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(feature = "rand")]
+use rand::seq::SliceRandom;
+
+use crate::error::TensorError;
+use crate::histogram::histogram2d;
+use crate::num::Float;
+use crate::sorting::total_cmp;
+use crate::special::{chi2_sf, kolmogorov_sf, student_t_sf2};
+use crate::tensor::Tensor;
+
+/// A streaming estimate of the mean vector and covariance matrix of
+/// `dim`-dimensional observations.
+///
+/// Observations arrive in batches of shape `[n, dim]` and are folded in with
+/// Chan et al.'s pairwise update, a batched form of Welford's algorithm, so the
+/// full dataset never needs to be held in memory. Estimators built on separate
+/// shards can be combined with [`RunningCovariance::merge`].
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::stats::RunningCovariance;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let mut running = RunningCovariance::<f64>::new(2);
+/// running.update(&Tensor::new(vec![1.0, 2.0, 3.0, 6.0], vec![2, 2]).unwrap()).unwrap();
+/// running.update(&Tensor::new(vec![5.0, 10.0], vec![1, 2]).unwrap()).unwrap();
+///
+/// assert_eq!(running.mean().as_slice(), Some(&[3.0, 6.0][..]));
+/// assert_eq!(running.covariance(1).unwrap().as_slice(), Some(&[4.0, 8.0, 8.0, 16.0][..]));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunningCovariance<T> {
+    dim: usize,
+    count: usize,
+    mean: Vec<T>,
+    /// Sum of outer products of deviations from the mean, row-major `[dim, dim]`.
+    comoment: Vec<T>,
+}
+
+impl<T: Float> RunningCovariance<T> {
+    /// Creates an estimator for `dim`-dimensional observations with no data yet.
+    pub fn new(dim: usize) -> Self {
+        Self {
+            dim,
+            count: 0,
+            mean: vec![T::zero(); dim],
+            comoment: vec![T::zero(); dim * dim],
+        }
+    }
+
+    /// Returns the number of observations seen so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the mean of the observations seen so far, zero if there are none.
+    pub fn mean(&self) -> Tensor<T> {
+        Tensor::new(self.mean.clone(), vec![self.dim]).unwrap()
+    }
+
+    /// Returns the `[dim, dim]` covariance matrix, dividing by `count - ddof`.
+    ///
+    /// Use `ddof = 1` for the unbiased sample covariance and `ddof = 0` for the
+    /// population covariance.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ValueError` if `count <= ddof`.
+    pub fn covariance(&self, ddof: usize) -> Result<Tensor<T>, TensorError> {
+        if self.count <= ddof {
+            return Err(TensorError::ValueError(format!(
+                "covariance with ddof {} needs more than {} observations, got {}",
+                ddof, ddof, self.count
+            )));
+        }
+
+        let divisor = T::from_usize(self.count - ddof);
+        let data = self.comoment.iter().map(|&c| c / divisor).collect();
+        Tensor::new(data, vec![self.dim, self.dim])
+    }
+
+    /// Folds a batch of observations of shape `[n, dim]` into the estimate.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `batch` is not 2D with `dim` columns.
+    pub fn update(&mut self, batch: &Tensor<T>) -> Result<(), TensorError> {
+        let n = match batch.shape() {
+            [n, dim] if *dim == self.dim => *n,
+            shape => {
+                return Err(TensorError::ShapeError(format!(
+                    "expected a batch of shape [n, {}], got {:?}",
+                    self.dim, shape
+                )));
+            }
+        };
+        if n == 0 {
+            return Ok(());
+        }
+
+        let rows: Vec<T> = batch.iter().copied().collect();
+        let mut mean = vec![T::zero(); self.dim];
+        for row in rows.chunks(self.dim) {
+            for (m, &x) in mean.iter_mut().zip(row) {
+                *m = *m + x;
+            }
+        }
+        let count = T::from_usize(n);
+        for m in mean.iter_mut() {
+            *m = *m / count;
+        }
+
+        let mut comoment = vec![T::zero(); self.dim * self.dim];
+        for row in rows.chunks(self.dim) {
+            accumulate_outer(&mut comoment, row, row, &mean, T::one());
+        }
+
+        self.combine(n, &mean, &comoment);
+        Ok(())
+    }
+
+    /// Combines the observations summarized by `other` into `self`, as if every
+    /// batch given to either had been given to `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the two estimators have different
+    /// dimensions.
+    pub fn merge(&mut self, other: &RunningCovariance<T>) -> Result<(), TensorError> {
+        if other.dim != self.dim {
+            return Err(TensorError::ShapeError(format!(
+                "cannot merge a {}-dimensional covariance into a {}-dimensional one",
+                other.dim, self.dim
+            )));
+        }
+
+        self.combine(other.count, &other.mean, &other.comoment);
+        Ok(())
+    }
+
+    /// Merges a summary of `count` observations with the given mean and
+    /// co-moment matrix.
+    fn combine(&mut self, count: usize, mean: &[T], comoment: &[T]) {
+        if count == 0 {
+            return;
+        }
+
+        let total = self.count + count;
+        let weight = T::from_usize(self.count) * T::from_usize(count) / T::from_usize(total);
+        let delta: Vec<T> = mean.iter().zip(&self.mean).map(|(&b, &a)| b - a).collect();
+        let zero = vec![T::zero(); self.dim];
+
+        for (c, &other) in self.comoment.iter_mut().zip(comoment) {
+            *c = *c + other;
+        }
+        accumulate_outer(&mut self.comoment, &delta, &delta, &zero, weight);
+
+        let share = T::from_usize(count) / T::from_usize(total);
+        for (m, &d) in self.mean.iter_mut().zip(&delta) {
+            *m = *m + d * share;
+        }
+        self.count = total;
+    }
+}
+
+/// Adds `scale * (a - center)(b - center)^T` to the row-major matrix `target`.
+fn accumulate_outer<T: Float>(target: &mut [T], a: &[T], b: &[T], center: &[T], scale: T) {
+    let dim = center.len();
+    for i in 0..dim {
+        let left = (a[i] - center[i]) * scale;
+        for j in 0..dim {
+            target[i * dim + j] = target[i * dim + j] + left * (b[j] - center[j]);
+        }
+    }
+}
+
+impl<T: Float> Tensor<T> {
+    /// Returns the covariance matrix of the variables of a 2D tensor.
+    ///
+    /// With `rowvar` each row is a variable and each column an observation, as
+    /// in NumPy's default; otherwise columns are variables. Deviations from the
+    /// mean are summed and divided by `n - ddof` for `n` observations, so
+    /// `ddof = 1` gives the unbiased estimate; with `n <= ddof` every entry is
+    /// NaN.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` is not 2D.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let x = Tensor::new(vec![0.0, 1.0, 2.0, 2.0, 1.0, 0.0], vec![2, 3]).unwrap();
+    /// let c = x.cov(1, true).unwrap();
+    /// assert_eq!(c.as_slice(), Some(&[1.0, -1.0, -1.0, 1.0][..]));
+    /// ```
+    pub fn cov(&self, ddof: usize, rowvar: bool) -> Result<Tensor<T>, TensorError> {
+        if self.ndim() != 2 {
+            return Err(TensorError::ShapeError(format!(
+                "cov needs a 2D tensor, got shape {:?}",
+                self.shape()
+            )));
+        }
+        let variables = if rowvar {
+            self.clone()
+        } else {
+            self.permute(&[1, 0])?
+        };
+        let (k, n) = (variables.shape()[0], variables.shape()[1]);
+
+        let mean = variables.mean_axes(&[1], true)?;
+        let centered: Vec<T> = variables
+            .zip_with(&mean, |x, m| x - m)?
+            .iter()
+            .copied()
+            .collect();
+        let scale = if n > ddof {
+            T::from_usize(n - ddof).recip()
+        } else {
+            T::from_f64(f64::NAN)
+        };
+
+        let mut data = vec![T::zero(); k * k];
+        for i in 0..k {
+            let row_i = &centered[i * n..(i + 1) * n];
+            for j in i..k {
+                let row_j = &centered[j * n..(j + 1) * n];
+                let dot = row_i
+                    .iter()
+                    .zip(row_j)
+                    .fold(T::zero(), |acc, (&a, &b)| acc + a * b);
+                data[i * k + j] = dot * scale;
+                data[j * k + i] = dot * scale;
+            }
+        }
+        Tensor::new(data, vec![k, k])
+    }
+
+    /// Returns the Pearson correlation coefficients of the variables of a 2D
+    /// tensor, with rows as variables when `rowvar` is set, as in [`Tensor::cov`].
+    ///
+    /// Entries are clipped to `[-1, 1]`; a variable with zero variance has NaN
+    /// correlations.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if `self` is not 2D.
+    pub fn corrcoef(&self, rowvar: bool) -> Result<Tensor<T>, TensorError> {
+        let cov = self.cov(1, rowvar)?;
+        let k = cov.shape()[0];
+        let std: Vec<T> = (0..k).map(|i| cov.data[i * k + i].sqrt()).collect();
+        let (lo, hi) = (-T::one(), T::one());
+        let data = cov
+            .data
+            .iter()
+            .enumerate()
+            .map(|(index, &c)| {
+                let r = c / (std[index / k] * std[index % k]);
+                if r < lo {
+                    lo
+                } else if r > hi {
+                    hi
+                } else {
+                    r
+                }
+            })
+            .collect();
+        Tensor::new(data, vec![k, k])
+    }
+}
+
+/// Checks that `values` are non-negative with a positive total, which is returned.
+fn distribution_total<T: Float>(values: &[T], what: &str) -> Result<T, TensorError> {
+    let mut total = T::zero();
+    for &v in values {
+        if v.is_nan() || v < T::zero() {
+            return Err(TensorError::ValueError(format!(
+                "{} must contain only non-negative values",
+                what
+            )));
+        }
+        total = total + v;
+    }
+    if total == T::zero() {
+        return Err(TensorError::ValueError(format!(
+            "{} must have a positive total",
+            what
+        )));
+    }
+    Ok(total)
+}
+
+/// Returns `sum(p * ln(p / q))` over already normalized distributions, with
+/// `0 * ln(0 / q) = 0`.
+fn relative_entropy<T: Float>(p: &[T], q: &[T]) -> T {
+    p.iter().zip(q).fold(T::zero(), |acc, (&p, &q)| {
+        if p == T::zero() {
+            acc
+        } else {
+            acc + p * (p / q).ln()
+        }
+    })
+}
+
+/// Returns the Shannon entropy, in nats, of the distribution along every lane
+/// of `axis`.
+///
+/// Each lane is normalized to sum to one first, so unnormalized weights or
+/// counts are accepted, as in SciPy's `entropy`. The result drops `axis`.
+///
+/// # Errors
+///
+/// Returns `TensorError::AxisError` if `axis` is out of bounds and
+/// `TensorError::ValueError` if a lane has a negative or NaN entry or sums to zero.
+pub fn entropy<T: Float>(p: &Tensor<T>, axis: usize) -> Result<Tensor<T>, TensorError> {
+    let (shape, starts) = p.lanes(axis)?;
+    let len = p.shape()[axis];
+    let stride = p.strides()[axis];
+
+    let mut data = Vec::with_capacity(starts.len());
+    let mut lane = Vec::with_capacity(len);
+    for start in starts {
+        lane.clear();
+        lane.extend((0..len).map(|k| p.data[start + k * stride]));
+        let total = distribution_total(&lane, "entropy input")?;
+        let h = lane.iter().fold(T::zero(), |acc, &x| {
+            let x = x / total;
+            if x == T::zero() {
+                acc
+            } else {
+                acc - x * x.ln()
+            }
+        });
+        data.push(h);
+    }
+
+    Tensor::new(data, shape)
+}
+
+/// Returns the Kullback-Leibler divergence `KL(p || q)`, in nats.
+///
+/// `p` and `q` are normalized to sum to one. The result is infinite if `q` is
+/// zero anywhere `p` is not.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if the shapes differ and
+/// `TensorError::ValueError` if either has a negative or NaN entry or sums to zero.
+pub fn kl_div<T: Float>(p: &Tensor<T>, q: &Tensor<T>) -> Result<T, TensorError> {
+    let (p, q) = normalized_pair(p, q)?;
+    Ok(relative_entropy(&p, &q))
+}
+
+/// Returns the Jensen-Shannon divergence between `p` and `q`, in nats.
+///
+/// This is the mean KL divergence of `p` and `q` from their average. It is
+/// symmetric and bounded by `ln 2`.
+///
+/// # Errors
+///
+/// See [`kl_div`].
+pub fn js_div<T: Float>(p: &Tensor<T>, q: &Tensor<T>) -> Result<T, TensorError> {
+    let (p, q) = normalized_pair(p, q)?;
+    let half = T::from_f64(0.5);
+    let m: Vec<T> = p.iter().zip(&q).map(|(&a, &b)| (a + b) * half).collect();
+    Ok(half * (relative_entropy(&p, &m) + relative_entropy(&q, &m)))
+}
+
+/// Validates two distributions of the same shape and normalizes both.
+fn normalized_pair<T: Float>(
+    p: &Tensor<T>,
+    q: &Tensor<T>,
+) -> Result<(Vec<T>, Vec<T>), TensorError> {
+    if p.shape() != q.shape() {
+        return Err(TensorError::ShapeError(format!(
+            "distributions must have the same shape, got {:?} and {:?}",
+            p.shape(),
+            q.shape()
+        )));
+    }
+    let mut p: Vec<T> = p.iter().copied().collect();
+    let mut q: Vec<T> = q.iter().copied().collect();
+    let p_total = distribution_total(&p, "p")?;
+    let q_total = distribution_total(&q, "q")?;
+    p.iter_mut().for_each(|x| *x = *x / p_total);
+    q.iter_mut().for_each(|x| *x = *x / q_total);
+    Ok((p, q))
+}
+
+/// Estimates the mutual information, in nats, between the paired samples `x`
+/// and `y` from their joint histogram with `bins` bins along each axis.
+///
+/// The estimate is the plug-in `sum p(x, y) ln(p(x, y) / (p(x) p(y)))` over the
+/// bins of [`histogram2d`], which spans the range of each variable.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `x` and `y` are not 1D tensors of equal
+/// length, and `TensorError::ValueError` if `bins` is zero or no sample pair is
+/// free of NaN.
+pub fn mutual_info<T: Float>(x: &Tensor<T>, y: &Tensor<T>, bins: usize) -> Result<T, TensorError> {
+    let (counts, _) = histogram2d(x, y, (bins, bins), None)?;
+    let counts: Vec<T> = counts.iter().map(|&c| T::from_usize(c)).collect();
+    let total = distribution_total(&counts, "joint histogram")?;
+
+    let joint: Vec<T> = counts.iter().map(|&c| c / total).collect();
+    let mut px = vec![T::zero(); bins];
+    let mut py = vec![T::zero(); bins];
+    for (i, row) in joint.chunks(bins).enumerate() {
+        for (j, &p) in row.iter().enumerate() {
+            px[i] = px[i] + p;
+            py[j] = py[j] + p;
+        }
+    }
+
+    let independent: Vec<T> = (0..bins * bins)
+        .map(|k| px[k / bins] * py[k % bins])
+        .collect();
+    Ok(relative_entropy(&joint, &independent))
+}
+
+/// The outcome of a hypothesis test evaluated along an axis, with one entry
+/// per lane.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestResult<T> {
+    /// The test statistic of every lane.
+    pub statistic: Tensor<T>,
+    /// The p-value of every lane.
+    pub pvalue: Tensor<T>,
+}
+
+/// Returns the shape without `axis` and every lane along `axis` of `tensor`.
+fn lanes_along<T: Copy>(
+    tensor: &Tensor<T>,
+    axis: usize,
+) -> Result<(Vec<usize>, Vec<Vec<T>>), TensorError> {
+    let (shape, starts) = tensor.lanes(axis)?;
+    let len = tensor.shape()[axis];
+    let stride = tensor.strides()[axis];
+    let lanes = starts
+        .into_iter()
+        .map(|start| (0..len).map(|k| tensor.data[start + k * stride]).collect())
+        .collect();
+    Ok((shape, lanes))
+}
+
+/// Matching lanes of two samples, one pair per position of the other axes.
+type LanePairs<T> = Vec<(Vec<T>, Vec<T>)>;
+
+/// Returns the lanes along `axis` of two samples whose other axes must agree.
+fn paired_lanes<T: Copy>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    axis: usize,
+    what: &str,
+) -> Result<(Vec<usize>, LanePairs<T>), TensorError> {
+    let (shape, a_lanes) = lanes_along(a, axis)?;
+    let (b_shape, b_lanes) = lanes_along(b, axis)?;
+    if shape != b_shape {
+        return Err(TensorError::ShapeError(format!(
+            "{} samples of shapes {:?} and {:?} differ outside axis {}",
+            what,
+            a.shape(),
+            b.shape(),
+            axis
+        )));
+    }
+    Ok((shape, a_lanes.into_iter().zip(b_lanes).collect()))
+}
+
+/// Collects `(statistic, pvalue)` pairs into a [`TestResult`] of `shape`.
+fn test_result<T: Float>(
+    shape: Vec<usize>,
+    outcomes: impl Iterator<Item = (T, T)>,
+) -> Result<TestResult<T>, TensorError> {
+    let (statistic, pvalue): (Vec<T>, Vec<T>) = outcomes.unzip();
+    Ok(TestResult {
+        statistic: Tensor::new(statistic, shape.clone())?,
+        pvalue: Tensor::new(pvalue, shape)?,
+    })
+}
+
+/// Returns the mean and the sample variance (`ddof = 1`) of `values`.
+fn mean_and_var<T: Float>(values: &[T]) -> (T, T) {
+    let n = T::from_usize(values.len());
+    let mean = values.iter().fold(T::zero(), |acc, &x| acc + x) / n;
+    let squares = values.iter().fold(T::zero(), |acc, &x| {
+        let d = x - mean;
+        acc + d * d
+    });
+    (mean, squares / (n - T::one()))
+}
+
+/// Two-sample t-test for equal means of the independent samples `a` and `b`
+/// along `axis`, with a two-sided p-value.
+///
+/// With `equal_var` the samples share a pooled variance (Student's test);
+/// otherwise the Welch-Satterthwaite degrees of freedom are used (Welch's
+/// test). The samples may have different lengths along `axis`, but all other
+/// axes must match. Lanes with fewer than two samples yield NaN.
+///
+/// # Errors
+///
+/// Returns `TensorError::AxisError` if `axis` is out of bounds for either
+/// sample and `TensorError::ShapeError` if their other axes differ.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::stats::ttest_ind;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let a = Tensor::new(vec![1.0f64, 2.0, 3.0, 4.0, 5.0], vec![5]).unwrap();
+/// let b = Tensor::new(vec![3.0, 4.0, 5.0, 6.0, 7.0], vec![5]).unwrap();
+/// let result = ttest_ind(&a, &b, 0, true).unwrap();
+/// assert_eq!(result.statistic.as_slice(), Some(&[-2.0][..]));
+/// assert!((result.pvalue.as_slice().unwrap()[0] - 0.0805).abs() < 1e-4);
+/// ```
+pub fn ttest_ind<T: Float>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    axis: usize,
+    equal_var: bool,
+) -> Result<TestResult<T>, TensorError> {
+    let (shape, pairs) = paired_lanes(a, b, axis, "ttest_ind")?;
+    let one = T::one();
+    test_result(
+        shape,
+        pairs.iter().map(|(x, y)| {
+            let (n1, n2) = (T::from_usize(x.len()), T::from_usize(y.len()));
+            let (m1, v1) = mean_and_var(x);
+            let (m2, v2) = mean_and_var(y);
+            let (se, df) = if equal_var {
+                let df = n1 + n2 - T::from_usize(2);
+                let pooled = ((n1 - one) * v1 + (n2 - one) * v2) / df;
+                ((pooled * (n1.recip() + n2.recip())).sqrt(), df)
+            } else {
+                let (s1, s2) = (v1 / n1, v2 / n2);
+                let df = (s1 + s2) * (s1 + s2) / (s1 * s1 / (n1 - one) + s2 * s2 / (n2 - one));
+                ((s1 + s2).sqrt(), df)
+            };
+            let t = (m1 - m2) / se;
+            (t, student_t_sf2(t, df))
+        }),
+    )
+}
+
+/// Paired t-test for a zero mean difference between the related samples `a`
+/// and `b` along `axis`, with a two-sided p-value.
+///
+/// Lanes with fewer than two pairs yield NaN.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if the shapes differ and
+/// `TensorError::AxisError` if `axis` is out of bounds.
+pub fn ttest_rel<T: Float>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    axis: usize,
+) -> Result<TestResult<T>, TensorError> {
+    if a.shape() != b.shape() {
+        return Err(TensorError::ShapeError(format!(
+            "ttest_rel needs samples of equal shape, got {:?} and {:?}",
+            a.shape(),
+            b.shape()
+        )));
+    }
+    let (shape, pairs) = paired_lanes(a, b, axis, "ttest_rel")?;
+    test_result(
+        shape,
+        pairs.iter().map(|(x, y)| {
+            let differences: Vec<T> = x.iter().zip(y).map(|(&x, &y)| x - y).collect();
+            let n = T::from_usize(differences.len());
+            let (mean, var) = mean_and_var(&differences);
+            let t = mean / (var / n).sqrt();
+            (t, student_t_sf2(t, n - T::one()))
+        }),
+    )
+}
+
+/// Pearson's chi-squared goodness-of-fit test of the counts along `axis`.
+///
+/// The statistic is `sum((observed - expected)^2 / expected)` with `k - 1`
+/// degrees of freedom for `k` categories. `expected` defaults to equally
+/// likely categories, i.e. the mean of each lane. Lanes with fewer than two
+/// categories have a NaN p-value.
+///
+/// # Errors
+///
+/// Returns `TensorError::AxisError` if `axis` is out of bounds and
+/// `TensorError::ShapeError` if `expected` does not have the shape of
+/// `observed`.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::stats::chi2_test;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let observed = Tensor::new(vec![10.0f64, 10.0, 20.0], vec![3]).unwrap();
+/// let result = chi2_test(&observed, None, 0).unwrap();
+/// assert!((result.statistic.as_slice().unwrap()[0] - 5.0).abs() < 1e-12);
+/// // With two degrees of freedom the p-value is exp(-statistic / 2).
+/// assert!((result.pvalue.as_slice().unwrap()[0] - (-2.5f64).exp()).abs() < 1e-12);
+/// ```
+pub fn chi2_test<T: Float>(
+    observed: &Tensor<T>,
+    expected: Option<&Tensor<T>>,
+    axis: usize,
+) -> Result<TestResult<T>, TensorError> {
+    let (shape, observed_lanes) = lanes_along(observed, axis)?;
+    let expected_lanes = match expected {
+        Some(expected) if expected.shape() != observed.shape() => {
+            return Err(TensorError::ShapeError(format!(
+                "expected frequencies of shape {:?} do not match observed {:?}",
+                expected.shape(),
+                observed.shape()
+            )));
+        }
+        Some(expected) => lanes_along(expected, axis)?.1,
+        None => observed_lanes
+            .iter()
+            .map(|lane| {
+                let mean =
+                    lane.iter().fold(T::zero(), |acc, &x| acc + x) / T::from_usize(lane.len());
+                vec![mean; lane.len()]
+            })
+            .collect(),
+    };
+
+    test_result(
+        shape,
+        observed_lanes.iter().zip(&expected_lanes).map(|(o, e)| {
+            let statistic = o.iter().zip(e).fold(T::zero(), |acc, (&o, &e)| {
+                let d = o - e;
+                acc + d * d / e
+            });
+            let pvalue = match o.len() {
+                0 | 1 => T::from_f64(f64::NAN),
+                k => chi2_sf(statistic, T::from_usize(k - 1)),
+            };
+            (statistic, pvalue)
+        }),
+    )
+}
+
+/// Two-sample Kolmogorov-Smirnov test of whether `a` and `b` along `axis`
+/// come from the same continuous distribution.
+///
+/// The statistic is the largest distance between the two empirical CDFs, and
+/// the p-value uses the asymptotic Kolmogorov distribution with Stephens'
+/// small-sample correction, so it is approximate for very short lanes. The
+/// samples may have different lengths along `axis`; empty lanes yield NaN.
+///
+/// # Errors
+///
+/// Returns `TensorError::AxisError` if `axis` is out of bounds for either
+/// sample and `TensorError::ShapeError` if their other axes differ.
+pub fn ks_test<T: Float>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    axis: usize,
+) -> Result<TestResult<T>, TensorError> {
+    let (shape, pairs) = paired_lanes(a, b, axis, "ks_test")?;
+    test_result(
+        shape,
+        pairs.into_iter().map(|(mut x, mut y)| {
+            if x.is_empty() || y.is_empty() {
+                let nan = T::from_f64(f64::NAN);
+                return (nan, nan);
+            }
+            x.sort_by(total_cmp);
+            y.sort_by(total_cmp);
+
+            let (n1, n2) = (T::from_usize(x.len()), T::from_usize(y.len()));
+            let (mut i, mut j) = (0, 0);
+            let mut statistic = T::zero();
+            while i < x.len() && j < y.len() {
+                let value = if total_cmp(&x[i], &y[j]).is_le() {
+                    x[i]
+                } else {
+                    y[j]
+                };
+                while i < x.len() && total_cmp(&x[i], &value).is_le() {
+                    i += 1;
+                }
+                while j < y.len() && total_cmp(&y[j], &value).is_le() {
+                    j += 1;
+                }
+                let gap = (T::from_usize(i) / n1 - T::from_usize(j) / n2).abs();
+                if gap > statistic {
+                    statistic = gap;
+                }
+            }
+
+            let en = (n1 * n2 / (n1 + n2)).sqrt();
+            let lambda = (en + T::from_f64(0.12) + T::from_f64(0.11) / en) * statistic;
+            (statistic, kolmogorov_sf(lambda))
+        }),
+    )
+}
+
+/// Splits `data` along its first axis, returning the row-major elements, the
+/// shape of one row and the number of elements per row.
+#[cfg(feature = "rand")]
+fn sample_rows<T: Copy>(
+    data: &Tensor<T>,
+    what: &str,
+) -> Result<(Vec<T>, Vec<usize>, usize), TensorError> {
+    match data.shape().split_first() {
+        Some((&n, row_shape)) if n > 0 => {
+            let row_shape = row_shape.to_vec();
+            let row_len = row_shape.iter().product();
+            Ok((data.iter().copied().collect(), row_shape, row_len))
+        }
+        _ => Err(TensorError::ShapeError(format!(
+            "{} needs at least one sample along the first axis, got shape {:?}",
+            what,
+            data.shape()
+        ))),
+    }
+}
+
+/// Stacks the rows of `values` at `indices` into a tensor whose first axis has
+/// length `indices.len()`.
+#[cfg(feature = "rand")]
+fn gather_rows<T: Copy>(
+    values: &[T],
+    row_shape: &[usize],
+    row_len: usize,
+    indices: &[usize],
+) -> Result<Tensor<T>, TensorError> {
+    let mut data = Vec::with_capacity(indices.len() * row_len);
+    for &i in indices {
+        data.extend_from_slice(&values[i * row_len..(i + 1) * row_len]);
+    }
+    let mut shape = vec![indices.len()];
+    shape.extend_from_slice(row_shape);
+    Tensor::new(data, shape)
+}
+
+/// Returns the bootstrap distribution of `statistic`, available with the
+/// `rand` feature.
+///
+/// Each of the `n_resamples` resamples draws as many samples as `data` has
+/// along its first axis, with replacement, and keeps the row shape, so `data`
+/// may hold scalar observations (`[n]`) or vector ones (`[n, d]`). The result
+/// is the 1D tensor of the statistic evaluated on every resample.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if `data` has no samples along its first
+/// axis.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::random::seeded_rng;
+/// use tiny_tensor::stats::bootstrap;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let data = Tensor::new(vec![2.0, 4.0, 6.0, 8.0], vec![4]).unwrap();
+/// let means = bootstrap(&data, |t| t.iter().sum::<f64>() / 4.0, 100, &mut seeded_rng(7)).unwrap();
+/// assert_eq!(means.shape(), &[100]);
+/// assert!(means.iter().all(|&m| (2.0..=8.0).contains(&m)));
+/// ```
+#[cfg(feature = "rand")]
+pub fn bootstrap<T, U, F, R>(
+    data: &Tensor<T>,
+    mut statistic: F,
+    n_resamples: usize,
+    rng: &mut R,
+) -> Result<Tensor<U>, TensorError>
+where
+    T: Copy,
+    U: Copy,
+    F: FnMut(&Tensor<T>) -> U,
+    R: Rng + ?Sized,
+{
+    let (values, row_shape, row_len) = sample_rows(data, "bootstrap")?;
+    let n = data.shape()[0];
+
+    let mut indices = vec![0; n];
+    let mut results = Vec::with_capacity(n_resamples);
+    for _ in 0..n_resamples {
+        for index in &mut indices {
+            *index = rng.gen_range(0..n);
+        }
+        results.push(statistic(&gather_rows(
+            &values, &row_shape, row_len, &indices,
+        )?));
+    }
+
+    Tensor::new(results, vec![n_resamples])
+}
+
+/// Returns the two-sided p-value of a permutation test for a difference
+/// between the samples `a` and `b`, available with the `rand` feature.
+///
+/// The samples, split along their first axis, are pooled and randomly
+/// reassigned to groups of the original sizes `n` times. The p-value is the
+/// fraction of reassignments whose `|statistic|` is at least the observed one,
+/// counting the observed split itself, i.e. `(hits + 1) / (n + 1)`, so it is
+/// never zero.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if either sample is empty or their row
+/// shapes differ.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::random::seeded_rng;
+/// use tiny_tensor::stats::permutation_test;
+/// use tiny_tensor::tensor::Tensor;
+///
+/// let mean = |t: &Tensor<f64>| t.iter().sum::<f64>() / t.len() as f64;
+/// let a = Tensor::new(vec![10.0, 11.0, 12.0, 13.0], vec![4]).unwrap();
+/// let b = Tensor::new(vec![0.0, 1.0, 2.0, 3.0], vec![4]).unwrap();
+/// let p = permutation_test(&a, &b, |x, y| mean(x) - mean(y), 999, &mut seeded_rng(0)).unwrap();
+/// assert!(p < 0.05);
+/// ```
+#[cfg(feature = "rand")]
+pub fn permutation_test<T, S, F, R>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    mut statistic: F,
+    n: usize,
+    rng: &mut R,
+) -> Result<S, TensorError>
+where
+    T: Copy,
+    S: Float,
+    F: FnMut(&Tensor<T>, &Tensor<T>) -> S,
+    R: Rng + ?Sized,
+{
+    let (mut pooled, row_shape, row_len) = sample_rows(a, "permutation_test")?;
+    let (b_values, b_row_shape, _) = sample_rows(b, "permutation_test")?;
+    if row_shape != b_row_shape {
+        return Err(TensorError::ShapeError(format!(
+            "samples with rows of shape {:?} and {:?} cannot be pooled",
+            row_shape, b_row_shape
+        )));
+    }
+    pooled.extend(b_values);
+
+    let n_a = a.shape()[0];
+    let mut indices: Vec<usize> = (0..n_a + b.shape()[0]).collect();
+    let observed = statistic(a, b).abs();
+    let mut hits = 0;
+    for _ in 0..n {
+        indices.shuffle(rng);
+        let left = gather_rows(&pooled, &row_shape, row_len, &indices[..n_a])?;
+        let right = gather_rows(&pooled, &row_shape, row_len, &indices[n_a..])?;
+        if statistic(&left, &right).abs() >= observed {
+            hits += 1;
+        }
+    }
+
+    Ok(S::from_usize(hits + 1) / S::from_usize(n + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_covariance_matches_batch_estimate() {
+        let data = Tensor::new(
+            vec![1.0, 2.0, 2.0, 1.0, 4.0, 5.0, 0.0, -1.0, 3.0, 3.0],
+            vec![5, 2],
+        )
+        .unwrap();
+        let mut whole = RunningCovariance::new(2);
+        whole.update(&data).unwrap();
+
+        let mut streamed = RunningCovariance::new(2);
+        for i in 0..5 {
+            let row = Tensor::new(
+                data.as_slice().unwrap()[2 * i..2 * i + 2].to_vec(),
+                vec![1, 2],
+            )
+            .unwrap();
+            streamed.update(&row).unwrap();
+        }
+
+        assert_eq!(streamed.count(), 5);
+        assert!(
+            streamed
+                .mean()
+                .max_abs_diff(&Tensor::new(vec![2.0, 2.0], vec![2]).unwrap())
+                .unwrap()
+                < 1e-12
+        );
+        // Sample covariance: var(x) = 2.5, var(y) = 5, cov(x, y) = 3.25.
+        let expected = Tensor::new(vec![2.5, 3.25, 3.25, 5.0], vec![2, 2]).unwrap();
+        assert!(
+            streamed
+                .covariance(1)
+                .unwrap()
+                .max_abs_diff(&expected)
+                .unwrap()
+                < 1e-12
+        );
+        assert!(
+            whole
+                .covariance(1)
+                .unwrap()
+                .max_abs_diff(&expected)
+                .unwrap()
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_running_covariance_merge() {
+        let mut left = RunningCovariance::new(1);
+        left.update(&Tensor::new(vec![1.0, 2.0, 3.0], vec![3, 1]).unwrap())
+            .unwrap();
+        let mut right = RunningCovariance::new(1);
+        right
+            .update(&Tensor::new(vec![10.0], vec![1, 1]).unwrap())
+            .unwrap();
+
+        left.merge(&right).unwrap();
+        left.merge(&RunningCovariance::new(1)).unwrap();
+
+        assert_eq!(left.count(), 4);
+        assert_eq!(left.mean().as_slice(), Some(&[4.0][..]));
+        // Population variance of [1, 2, 3, 10] is 12.5.
+        assert!((left.covariance(0).unwrap().as_slice().unwrap()[0] - 12.5).abs() < 1e-12);
+        assert!(left.merge(&RunningCovariance::new(2)).is_err());
+    }
+
+    #[test]
+    fn test_running_covariance_errors() {
+        let mut running = RunningCovariance::<f32>::new(3);
+
+        assert!(matches!(
+            running.covariance(1),
+            Err(TensorError::ValueError(_))
+        ));
+        assert!(matches!(
+            running.update(&Tensor::new(vec![1.0, 2.0], vec![1, 2]).unwrap()),
+            Err(TensorError::ShapeError(_))
+        ));
+        running
+            .update(&Tensor::new(vec![], vec![0, 3]).unwrap())
+            .unwrap();
+        assert_eq!(running.count(), 0);
+    }
+
+    #[test]
+    fn test_entropy_along_axis() {
+        let p = Tensor::new(vec![1.0, 1.0, 1.0, 1.0, 2.0, 0.0, 0.0, 0.0], vec![2, 4]).unwrap();
+
+        let h = entropy(&p, 1).unwrap();
+
+        assert_eq!(h.shape(), &[2]);
+        assert!((h.as_slice().unwrap()[0] - 4f64.ln()).abs() < 1e-12);
+        assert_eq!(h.as_slice().unwrap()[1], 0.0);
+        assert_eq!(entropy(&p, 0).unwrap().shape(), &[4]);
+        assert!(entropy(&Tensor::new(vec![0.0, 0.0], vec![2]).unwrap(), 0).is_err());
+        assert!(entropy(&Tensor::new(vec![-1.0, 2.0], vec![2]).unwrap(), 0).is_err());
+    }
+
+    #[test]
+    fn test_kl_and_js_divergence() {
+        let p = Tensor::new(vec![0.5, 0.5], vec![2]).unwrap();
+        let q = Tensor::new(vec![1.0, 3.0], vec![2]).unwrap();
+
+        let expected = 0.5 * (0.5f64 / 0.25).ln() + 0.5 * (0.5f64 / 0.75).ln();
+        assert!((kl_div(&p, &q).unwrap() - expected).abs() < 1e-12);
+        assert_eq!(kl_div(&p, &p).unwrap(), 0.0);
+
+        let disjoint: Tensor<f64> = Tensor::new(vec![1.0, 0.0], vec![2]).unwrap();
+        let other = Tensor::new(vec![0.0, 1.0], vec![2]).unwrap();
+        assert!(kl_div(&disjoint, &other).unwrap().is_infinite());
+        assert!((js_div(&disjoint, &other).unwrap() - 2f64.ln()).abs() < 1e-12);
+        assert!((js_div(&p, &q).unwrap() - js_div(&q, &p).unwrap()).abs() < 1e-12);
+        assert!(matches!(
+            kl_div(&p, &Tensor::new(vec![1.0; 3], vec![3]).unwrap()),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_mutual_info() {
+        let x = Tensor::new(vec![0.0, 0.0, 1.0, 1.0], vec![4]).unwrap();
+        let independent = Tensor::new(vec![0.0, 1.0, 0.0, 1.0], vec![4]).unwrap();
+
+        assert!((mutual_info(&x, &x, 2).unwrap() - 2f64.ln()).abs() < 1e-12);
+        assert!(mutual_info(&x, &independent, 2).unwrap().abs() < 1e-12);
+        assert!(mutual_info(&x, &independent, 0).is_err());
+    }
+
+    #[test]
+    fn test_cov_with_rows_or_columns_as_variables() {
+        let x = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 6.0, 9.0], vec![2, 4]).unwrap();
+
+        let by_rows = x.cov(1, true).unwrap();
+        let expected = Tensor::new(
+            vec![5.0 / 3.0, 11.5 / 3.0, 11.5 / 3.0, 26.75 / 3.0],
+            vec![2, 2],
+        )
+        .unwrap();
+        assert!(by_rows.max_abs_diff(&expected).unwrap() < 1e-12);
+
+        let by_columns = x.permute(&[1, 0]).unwrap().cov(0, false).unwrap();
+        assert!(by_columns.max_abs_diff(&by_rows.map(|v| v * 0.75)).unwrap() < 1e-12);
+        assert!(x.cov(4, true).unwrap().iter().all(|v| v.is_nan()));
+        assert!(matches!(
+            Tensor::new(vec![1.0; 3], vec![3]).unwrap().cov(1, true),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_corrcoef() {
+        let x = Tensor::new(
+            vec![
+                1.0, 2.0, 3.0, 4.0, -2.0, -4.0, -6.0, -8.0, 1.0, 3.0, 2.0, 4.0,
+            ],
+            vec![3, 4],
+        )
+        .unwrap();
+
+        let r = x.corrcoef(true).unwrap();
+
+        assert_eq!(r.get(&[0, 0]), Some(&1.0));
+        assert_eq!(r.get(&[0, 1]), Some(&-1.0));
+        assert!((r.get(&[0, 2]).unwrap() - 0.8).abs() < 1e-12);
+        assert_eq!(r.get(&[2, 0]), r.get(&[0, 2]));
+
+        let constant = Tensor::new(vec![1.0, 1.0, 1.0, 1.0, 2.0, 3.0], vec![2, 3]).unwrap();
+        assert!(
+            constant
+                .corrcoef(true)
+                .unwrap()
+                .get(&[0, 1])
+                .unwrap()
+                .is_nan()
+        );
+    }
+
+    #[test]
+    fn test_ttests_match_reference_values() {
+        let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], vec![4]).unwrap();
+        let b = Tensor::new(vec![2.0, 4.0, 6.0, 8.0, 10.0], vec![5]).unwrap();
+
+        let welch = ttest_ind(&a, &b, 0, false).unwrap();
+        assert!((welch.statistic.as_slice().unwrap()[0] + 2.251_436_323_159_37).abs() < 1e-12);
+        assert!((welch.pvalue.as_slice().unwrap()[0] - 0.069_133_593_192_392_4).abs() < 1e-10);
+
+        let x = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0], vec![5]).unwrap();
+        let y = Tensor::new(vec![1.5, 2.1, 3.9, 4.2, 6.0], vec![5]).unwrap();
+        let paired = ttest_rel(&x, &y, 0).unwrap();
+        assert!((paired.statistic.as_slice().unwrap()[0] + 2.990_783_388_357_242).abs() < 1e-10);
+        assert!((paired.pvalue.as_slice().unwrap()[0] - 0.040_306_920_594_900_05).abs() < 1e-10);
+
+        assert!(matches!(
+            ttest_rel(&a, &b, 0),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_ttest_ind_along_axis() {
+        // Two columns: identical samples, then a shift by two.
+        let a = Tensor::new(
+            vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 5.0, 5.0],
+            vec![5, 2],
+        )
+        .unwrap();
+        let b = Tensor::new(
+            vec![1.0, 3.0, 2.0, 4.0, 3.0, 5.0, 4.0, 6.0, 5.0, 7.0],
+            vec![5, 2],
+        )
+        .unwrap();
+
+        let result = ttest_ind(&a, &b, 0, true).unwrap();
+
+        assert_eq!(result.statistic.as_slice(), Some(&[0.0, -2.0][..]));
+        assert!((result.pvalue.as_slice().unwrap()[0] - 1.0).abs() < 1e-12);
+        assert!((result.pvalue.as_slice().unwrap()[1] - 0.080_516_237_957_262_65).abs() < 1e-10);
+        assert!(ttest_ind(&a, &Tensor::new(vec![1.0; 3], vec![1, 3]).unwrap(), 0, true).is_err());
+    }
+
+    #[test]
+    fn test_chi2_test_with_expected_frequencies() {
+        let observed = Tensor::new(vec![18.0, 22.0, 20.0, 10.0, 10.0, 10.0], vec![2, 3]).unwrap();
+        let expected = Tensor::new(vec![20.0, 20.0, 20.0, 5.0, 10.0, 15.0], vec![2, 3]).unwrap();
+
+        let result = chi2_test(&observed, Some(&expected), 1).unwrap();
+
+        let statistic = result.statistic.as_slice().unwrap();
+        assert!((statistic[0] - 0.4).abs() < 1e-12);
+        assert!((statistic[1] - (5.0 + 25.0 / 15.0)).abs() < 1e-12);
+        assert!((result.pvalue.as_slice().unwrap()[0] - (-0.2f64).exp()).abs() < 1e-12);
+        assert!(
+            chi2_test(
+                &observed,
+                Some(&Tensor::new(vec![1.0; 3], vec![3]).unwrap()),
+                1
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_ks_test() {
+        let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0, 5.0], vec![5]).unwrap();
+        let b = Tensor::new(vec![6.0, 7.0, 8.0, 9.0, 10.0], vec![5]).unwrap();
+        let separated = ks_test(&a, &b, 0).unwrap();
+        assert_eq!(separated.statistic.as_slice(), Some(&[1.0][..]));
+        assert!((separated.pvalue.as_slice().unwrap()[0] - 0.003_781_354_059_370_1).abs() < 1e-10);
+
+        let x = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], vec![4]).unwrap();
+        let y = Tensor::new(vec![3.0, 4.0, 5.0], vec![3]).unwrap();
+        let overlapping = ks_test(&x, &y, 0).unwrap();
+        assert_eq!(overlapping.statistic.as_slice(), Some(&[0.5][..]));
+        assert!((overlapping.pvalue.as_slice().unwrap()[0] - 0.615_965_784_519_994).abs() < 1e-10);
+
+        assert_eq!(
+            ks_test(&a, &a, 0).unwrap().pvalue.as_slice(),
+            Some(&[1.0][..])
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_bootstrap_resamples_rows() {
+        use crate::random::seeded_rng;
+
+        let data = Tensor::new(vec![1.0, 10.0, 2.0, 20.0, 3.0, 30.0], vec![3, 2]).unwrap();
+        let rows = bootstrap(
+            &data,
+            |sample| {
+                assert_eq!(sample.shape(), &[3, 2]);
+                // Rows stay intact, so the second column is always ten times the first.
+                let v: Vec<f64> = sample.iter().copied().collect();
+                v.chunks(2).all(|row| row[1] == 10.0 * row[0])
+            },
+            50,
+            &mut seeded_rng(1),
+        )
+        .unwrap();
+        assert!(rows.iter().all(|&intact| intact));
+
+        let constant = Tensor::new(vec![5.0; 4], vec![4]).unwrap();
+        let sums = bootstrap(&constant, |t| t.iter().sum::<f64>(), 10, &mut seeded_rng(2)).unwrap();
+        assert_eq!(sums.as_slice(), Some(&[20.0; 10][..]));
+        assert!(
+            bootstrap(
+                &Tensor::<f64>::new(vec![], vec![0]).unwrap(),
+                |_| 0,
+                1,
+                &mut seeded_rng(0)
+            )
+            .is_err()
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_permutation_test_p_values() {
+        use crate::random::seeded_rng;
+
+        let mean = |t: &Tensor<f64>| t.iter().sum::<f64>() / t.len() as f64;
+        let a = Tensor::new(vec![1.0, 2.0, 3.0, 4.0], vec![4]).unwrap();
+        let shifted = a.map(|x| x + 100.0);
+
+        let p = permutation_test(
+            &a,
+            &shifted,
+            |x, y| mean(x) - mean(y),
+            199,
+            &mut seeded_rng(3),
+        )
+        .unwrap();
+        // Only the 2 of 70 splits that keep the groups apart are as extreme.
+        assert!(p < 0.1);
+
+        let p: f64 =
+            permutation_test(&a, &a, |x, y| mean(x) - mean(y), 99, &mut seeded_rng(3)).unwrap();
+        assert_eq!(p, 1.0);
+
+        let wide = Tensor::new(vec![1.0; 4], vec![2, 2]).unwrap();
+        assert!(permutation_test(&a, &wide, |_, _| 0.0, 9, &mut seeded_rng(0)).is_err());
+    }
+}
@@ -1,6 +1,3 @@
-use std::fmt;
-use std::fmt::{Debug, Display, Formatter};
-
 use crate::error::TensorError;
 
 /// An N-dimensional array.
@@ -8,7 +5,10 @@ use crate::error::TensorError;
 /// `Tensor<T>` is the central data structure of the library, providing a contiguous,
 /// row-major memory layout for elements of type `T`. It supports an arbitrary
 /// number of dimensions.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Equality compares shapes and elements in logical order, not memory layout,
+/// so a transposed view equals its [`Tensor::to_contiguous`] copy.
+#[derive(Clone, Debug)]
 pub struct Tensor<T> {
     /// A flat vector holding the array's data in a contiguous block.
     pub(crate) data: Vec<T>,
@@ -19,6 +19,12 @@ pub struct Tensor<T> {
     pub(crate) strides: Vec<usize>,
 }
 
+impl<T: PartialEq> PartialEq for Tensor<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && self.iter().eq(other.iter())
+    }
+}
+
 impl<T: Copy + Clone> Tensor<T> {
     /// Creates a new `Tensor` from a flat data buffer and a shape.
     ///
@@ -49,10 +55,48 @@ impl<T: Copy + Clone> Tensor<T> {
         })
     }
 
+    /// Creates a new `Tensor` of the given shape by calling `f` with the
+    /// multi-dimensional index of every element, in row-major order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiny_tensor::tensor::Tensor;
+    ///
+    /// let t = Tensor::from_fn(vec![2, 3], |index| index[0] * 10 + index[1]);
+    /// assert_eq!(t.as_slice(), Some(&[0, 1, 2, 10, 11, 12][..]));
+    /// ```
+    pub fn from_fn<F>(shape: Vec<usize>, mut f: F) -> Self
+    where
+        F: FnMut(&[usize]) -> T,
+    {
+        let num_elements: usize = shape.iter().product();
+        let mut data = Vec::with_capacity(num_elements);
+        let mut index = vec![0; shape.len()];
+        for _ in 0..num_elements {
+            data.push(f(&index));
+            for axis in (0..shape.len()).rev() {
+                index[axis] += 1;
+                if index[axis] < shape[axis] {
+                    break;
+                }
+                index[axis] = 0;
+            }
+        }
+
+        let strides = Self::calculate_strides(&shape);
+
+        Self {
+            data,
+            shape,
+            strides,
+        }
+    }
+
     /// Calculates row-major strides for a given shape.
     pub(crate) fn calculate_strides(shape: &[usize]) -> Vec<usize> {
         let mut strides = vec![1; shape.len()];
-        for i in (0..shape.len() - 1).rev() {
+        for i in (0..shape.len().saturating_sub(1)).rev() {
             strides[i] = strides[i + 1] * shape[i + 1];
         }
 
@@ -60,51 +104,220 @@ impl<T: Copy + Clone> Tensor<T> {
     }
 }
 
-/// Helper function for pretty-printing tensors.
-fn format_recursive<T: Debug>(
-    f: &mut Formatter<'_>,
-    data: &[T],
-    shape: &[usize],
-    strides: &[usize],
-    level: usize,
-) -> fmt::Result {
-    if shape.is_empty() {
-        return write!(f, "{:?}", data[0]);
-    }
-
-    let indent = " ".repeat(level * 2);
-    writeln!(f, "[")?;
-
-    let elements_in_dim = shape[0];
-    for i in 0..elements_in_dim {
-        write!(f, "{}  ", indent)?;
-        let offset = i * strides[0];
-        if shape.len() > 1 {
-            format_recursive(f, &data[offset..], &shape[1..], &strides[1..], level + 1)?;
+impl<T> Tensor<T> {
+    /// Returns the shape of the tensor.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns the strides of the tensor, measured in elements.
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    /// Returns the number of dimensions of the tensor.
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Returns the total number of elements in the tensor.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the tensor holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns a reference to the element at the multi-dimensional `index`, or
+    /// `None` if the index has the wrong length or is out of bounds.
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        self.offset_of(index).map(|offset| &self.data[offset])
+    }
+
+    /// Returns a mutable reference to the element at the multi-dimensional `index`,
+    /// or `None` if the index has the wrong length or is out of bounds.
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut T> {
+        self.offset_of(index).map(|offset| &mut self.data[offset])
+    }
+
+    /// Converts a multi-dimensional index into a buffer offset.
+    fn offset_of(&self, index: &[usize]) -> Option<usize> {
+        if index.len() != self.shape.len() {
+            return None;
+        }
+
+        let mut offset = 0;
+        for ((&i, &dim), &stride) in index.iter().zip(&self.shape).zip(&self.strides) {
+            if i >= dim {
+                return None;
+            }
+            offset += i * stride;
+        }
+
+        Some(offset)
+    }
+
+    /// Returns `true` if the elements are laid out in standard row-major order.
+    ///
+    /// Strides of axes with length 1 are ignored, since they are never used to
+    /// step through the buffer.
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = 1;
+        for (&dim, &stride) in self.shape.iter().zip(&self.strides).rev() {
+            if dim != 1 && stride != expected {
+                return false;
+            }
+            expected *= dim;
+        }
+
+        true
+    }
+
+    /// Returns the underlying buffer as a slice in row-major order.
+    ///
+    /// Returns `None` if the tensor is not contiguous, since the buffer order
+    /// would then differ from the logical element order.
+    ///
+    /// `Tensor` deliberately does not implement `AsRef<[T]>`: that conversion
+    /// cannot fail, so for a strided tensor such as a transpose it would have to
+    /// hand out the buffer in storage order, silently disagreeing with
+    /// [`Tensor::iter`]. Use this method, falling back to
+    /// [`Tensor::to_contiguous`] when it returns `None`.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        if self.is_contiguous() {
+            Some(&self.data)
         } else {
-            write!(f, "{:?}", data[offset])?;
+            None
         }
-        if i < elements_in_dim - 1 {
-            writeln!(f, ",")?;
+    }
+
+    /// Returns the underlying buffer as a mutable slice in row-major order.
+    ///
+    /// Returns `None` if the tensor is not contiguous.
+    pub fn as_slice_mut(&mut self) -> Option<&mut [T]> {
+        if self.is_contiguous() {
+            Some(&mut self.data)
         } else {
-            writeln!(f)?;
+            None
         }
     }
 
-    write!(f, "{}]", indent)
+    /// Returns the underlying buffer as a slice in whatever order the elements
+    /// are stored in memory.
+    ///
+    /// This succeeds for any tensor whose elements occupy the buffer without
+    /// gaps, e.g. a transposed matrix, and is useful for order-independent
+    /// operations such as summing every element. Returns `None` otherwise.
+    pub fn as_slice_memory_order(&self) -> Option<&[T]> {
+        let mut axes: Vec<usize> = (0..self.shape.len())
+            .filter(|&axis| self.shape[axis] != 1)
+            .collect();
+        axes.sort_by_key(|&axis| self.strides[axis]);
+
+        let mut expected = 1;
+        for axis in axes {
+            if self.strides[axis] != expected {
+                return None;
+            }
+            expected *= self.shape[axis];
+        }
+
+        Some(&self.data)
+    }
 }
 
-impl<T: Debug> Display for Tensor<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        if self.shape.is_empty() {
-            return writeln!(f, "[]");
+/// An iterator over the buffer offsets of every element of a strided layout, in
+/// row-major logical order.
+pub(crate) struct Offsets<'a> {
+    shape: &'a [usize],
+    strides: &'a [usize],
+    index: Vec<usize>,
+    offset: usize,
+    remaining: usize,
+}
+
+impl<'a> Offsets<'a> {
+    pub(crate) fn new(shape: &'a [usize], strides: &'a [usize]) -> Self {
+        Self {
+            shape,
+            strides,
+            index: vec![0; shape.len()],
+            offset: 0,
+            remaining: shape.iter().product(),
+        }
+    }
+}
+
+impl Iterator for Offsets<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
         }
+        self.remaining -= 1;
+        let current = self.offset;
 
-        if self.shape.iter().any(|&dim| dim == 0) {
-            return writeln!(f, "[]");
+        for axis in (0..self.shape.len()).rev() {
+            self.index[axis] += 1;
+            self.offset += self.strides[axis];
+            if self.index[axis] < self.shape[axis] {
+                break;
+            }
+            self.offset -= self.index[axis] * self.strides[axis];
+            self.index[axis] = 0;
         }
 
-        format_recursive(f, &self.data, &self.shape, &self.strides, 0)
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> Tensor<T> {
+    /// Returns an iterator over the buffer offsets of all elements in logical order.
+    pub(crate) fn offsets(&self) -> Offsets<'_> {
+        Offsets::new(&self.shape, &self.strides)
+    }
+
+    /// Returns an iterator over references to the elements in row-major logical
+    /// order, regardless of how they are laid out in memory.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.offsets().map(move |offset| &self.data[offset])
+    }
+
+    /// Validates `axis` against the number of dimensions of the tensor.
+    pub(crate) fn check_axis(&self, axis: usize) -> Result<(), TensorError> {
+        if axis >= self.shape.len() {
+            return Err(TensorError::AxisError(format!(
+                "axis {} is out of bounds for a tensor with {} dimensions",
+                axis,
+                self.shape.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Splits the tensor into the 1D lanes running along `axis`.
+    ///
+    /// Returns the shape that remains once `axis` is removed, together with the
+    /// starting offset of every lane in row-major order of that shape. Element `k`
+    /// of a lane starting at `start` lives at `start + k * self.strides[axis]`.
+    pub(crate) fn lanes(&self, axis: usize) -> Result<(Vec<usize>, Vec<usize>), TensorError> {
+        self.check_axis(axis)?;
+
+        let mut outer_shape = self.shape.clone();
+        let mut outer_strides = self.strides.clone();
+        outer_shape.remove(axis);
+        outer_strides.remove(axis);
+        let starts = Offsets::new(&outer_shape, &outer_strides).collect();
+
+        Ok((outer_shape, starts))
     }
 }
 
@@ -127,4 +340,90 @@ mod tests {
 
         assert!(matches!(result, Err(TensorError::ShapeError(_))))
     }
+
+    #[test]
+    fn test_from_fn() {
+        let result = Tensor::from_fn(vec![2, 2, 2], |index| (index[0], index[1], index[2]));
+        let scalar = Tensor::from_fn(vec![], |index| index.len());
+
+        assert_eq!(result.data[5], (1, 0, 1));
+        assert_eq!(result.strides, &[4, 2, 1]);
+        assert_eq!(scalar.data, vec![0]);
+    }
+
+    #[test]
+    fn test_get_and_get_mut() {
+        let mut tensor = Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![2, 3]).unwrap();
+
+        assert_eq!(tensor.get(&[1, 2]), Some(&6));
+        assert_eq!(tensor.get(&[2, 0]), None);
+        assert_eq!(tensor.get(&[0]), None);
+
+        *tensor.get_mut(&[0, 1]).unwrap() = 20;
+        assert_eq!(tensor.data, vec![1, 20, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_offsets_follow_strides() {
+        let tensor = Tensor {
+            data: vec![1, 4, 2, 5, 3, 6],
+            shape: vec![2, 3],
+            strides: vec![1, 2],
+        };
+
+        let offsets: Vec<usize> = tensor.offsets().collect();
+        assert_eq!(offsets, vec![0, 2, 4, 1, 3, 5]);
+
+        let values: Vec<i32> = tensor.iter().copied().collect();
+        assert_eq!(values, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_as_slice_contiguous() {
+        let mut tensor = Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![2, 3]).unwrap();
+
+        assert!(tensor.is_contiguous());
+        assert_eq!(tensor.as_slice(), Some(&[1, 2, 3, 4, 5, 6][..]));
+
+        tensor.as_slice_mut().unwrap()[0] = 10;
+        assert_eq!(tensor.data[0], 10);
+    }
+
+    #[test]
+    fn test_as_slice_non_contiguous() {
+        // A 2x3 matrix stored in column-major order, i.e. the transpose of a 3x2 buffer.
+        let tensor = Tensor {
+            data: vec![1, 4, 2, 5, 3, 6],
+            shape: vec![2, 3],
+            strides: vec![1, 2],
+        };
+
+        assert!(!tensor.is_contiguous());
+        assert_eq!(tensor.as_slice(), None);
+        assert_eq!(
+            tensor.as_slice_memory_order(),
+            Some(&[1, 4, 2, 5, 3, 6][..])
+        );
+    }
+
+    #[test]
+    fn test_eq_ignores_memory_layout() {
+        let tensor = Tensor {
+            data: vec![1, 4, 2, 5, 3, 6],
+            shape: vec![2, 3],
+            strides: vec![1, 2],
+        };
+        let contiguous = tensor.to_contiguous();
+
+        assert_eq!(tensor, contiguous);
+        assert_eq!(contiguous.as_slice(), Some(&[1, 2, 3, 4, 5, 6][..]));
+        assert_ne!(
+            tensor,
+            Tensor::new(vec![1, 4, 2, 5, 3, 6], vec![2, 3]).unwrap()
+        );
+        assert_ne!(
+            tensor,
+            Tensor::new(vec![1, 2, 3, 4, 5, 6], vec![3, 2]).unwrap()
+        );
+    }
 }
@@ -0,0 +1,221 @@
+//! Approximate comparison of tensors and assertion macros for tests.
+
+use std::fmt::Debug;
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+impl<T: Float> Tensor<T> {
+    /// Returns `true` if both tensors have the same shape and every pair of
+    /// elements satisfies `|a - b| <= atol + rtol * |b|`, as in NumPy.
+    ///
+    /// NaN never compares close to anything, including another NaN.
+    pub fn allclose(&self, other: &Tensor<T>, rtol: T, atol: T) -> bool {
+        self.shape == other.shape
+            && first_mismatch(self, other, |a, b| is_close(a, b, rtol, atol)).is_none()
+    }
+
+    /// Returns the largest absolute difference between corresponding elements,
+    /// or zero for empty tensors.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TensorError::ShapeError` if the shapes differ.
+    pub fn max_abs_diff(&self, other: &Tensor<T>) -> Result<T, TensorError> {
+        if self.shape != other.shape {
+            return Err(TensorError::ShapeError(format!(
+                "cannot compare tensors of shapes {:?} and {:?}",
+                self.shape, other.shape
+            )));
+        }
+
+        Ok(self
+            .iter()
+            .zip(other.iter())
+            .map(|(&a, &b)| (a - b).abs())
+            .fold(T::zero(), |max, diff| {
+                if diff > max || diff.is_nan() {
+                    diff
+                } else {
+                    max
+                }
+            }))
+    }
+}
+
+fn is_close<T: Float>(a: T, b: T, rtol: T, atol: T) -> bool {
+    (a - b).abs() <= atol + rtol * b.abs()
+}
+
+/// Returns the multi-index and values of the first pair of elements, in
+/// logical order, for which `matches` fails.
+fn first_mismatch<T: Copy, F>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    matches: F,
+) -> Option<(Vec<usize>, T, T)>
+where
+    F: Fn(T, T) -> bool,
+{
+    let position = a.iter().zip(b.iter()).position(|(&x, &y)| !matches(x, y))?;
+
+    let mut index = vec![0; a.ndim()];
+    let mut rest = position;
+    for axis in (0..a.ndim()).rev() {
+        index[axis] = rest % a.shape[axis];
+        rest /= a.shape[axis];
+    }
+
+    let (x, y) = (*a.get(&index)?, *b.get(&index)?);
+    Some((index, x, y))
+}
+
+/// Panics with a description of the first difference between `a` and `b`.
+///
+/// Used by [`assert_tensor_eq!`](crate::assert_tensor_eq); not part of the public API.
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_tensor_eq<T: Copy + PartialEq + Debug>(a: &Tensor<T>, b: &Tensor<T>) {
+    if a.shape != b.shape {
+        panic!(
+            "assertion failed: tensor shapes differ\n  left: {:?}\n right: {:?}",
+            a.shape, b.shape
+        );
+    }
+    if let Some((index, x, y)) = first_mismatch(a, b, |x, y| x == y) {
+        panic!(
+            "assertion failed: tensors differ at index {:?}\n  left: {:?}\n right: {:?}",
+            index, x, y
+        );
+    }
+}
+
+/// Panics with a description of the first pair of elements of `a` and `b`
+/// that are not close.
+///
+/// Used by [`assert_allclose!`](crate::assert_allclose); not part of the public API.
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_allclose<T: Float + Debug>(a: &Tensor<T>, b: &Tensor<T>, rtol: T, atol: T) {
+    if a.shape != b.shape {
+        panic!(
+            "assertion failed: tensor shapes differ\n  left: {:?}\n right: {:?}",
+            a.shape, b.shape
+        );
+    }
+    if let Some((index, x, y)) = first_mismatch(a, b, |x, y| is_close(x, y, rtol, atol)) {
+        panic!(
+            "assertion failed: tensors are not close at index {:?} (rtol = {:?}, atol = {:?})\n  left: {:?}\n right: {:?}",
+            index, rtol, atol, x, y
+        );
+    }
+}
+
+/// Asserts that two tensors have the same shape and exactly equal elements.
+///
+/// On failure, the panic message reports the first mismatching index and the
+/// values found there.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::{assert_tensor_eq, tensor};
+///
+/// assert_tensor_eq!(tensor![[1, 2], [3, 4]], tensor![[1, 2], [3, 4]]);
+/// ```
+#[macro_export]
+macro_rules! assert_tensor_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::testing::__assert_tensor_eq(&$left, &$right)
+    };
+}
+
+/// Asserts that two float tensors have the same shape and approximately equal
+/// elements, using [`Tensor::allclose`] semantics.
+///
+/// The tolerances default to NumPy's `rtol = 1e-5` and `atol = 1e-8`, and can
+/// be given explicitly as `assert_allclose!(a, b, rtol, atol)`. On failure, the
+/// panic message reports the first mismatching index and the values found there.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::{assert_allclose, tensor};
+///
+/// assert_allclose!(tensor![0.1 + 0.2, 1.0], tensor![0.3, 1.0]);
+/// assert_allclose!(tensor![1.0, 2.0], tensor![1.05, 2.0], 0.0, 0.1);
+/// ```
+#[macro_export]
+macro_rules! assert_allclose {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::testing::__assert_allclose(&$left, &$right, 1e-5, 1e-8)
+    };
+    ($left:expr, $right:expr, $rtol:expr, $atol:expr $(,)?) => {
+        $crate::testing::__assert_allclose(&$left, &$right, $rtol, $atol)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allclose() {
+        let a = Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap();
+        let b = Tensor::new(vec![1.0, 2.000001, 3.0], vec![3]).unwrap();
+        let reshaped = Tensor::new(vec![1.0, 2.0, 3.0], vec![1, 3]).unwrap();
+        let nan = Tensor::new(vec![f64::NAN, 2.0, 3.0], vec![3]).unwrap();
+
+        assert!(a.allclose(&b, 1e-5, 1e-8));
+        assert!(!a.allclose(&b, 0.0, 1e-8));
+        assert!(!a.allclose(&reshaped, 1e-5, 1e-8));
+        assert!(!nan.allclose(&nan, 1e-5, 1e-8));
+    }
+
+    #[test]
+    fn test_max_abs_diff() {
+        let a = Tensor::new(vec![1.0f32, -2.0, 3.0], vec![3]).unwrap();
+        let b = Tensor::new(vec![1.5f32, -4.0, 3.0], vec![3]).unwrap();
+
+        assert_eq!(a.max_abs_diff(&b).unwrap(), 2.0);
+        assert!(matches!(
+            a.max_abs_diff(&Tensor::new(vec![1.0], vec![1]).unwrap()),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_assert_macros_pass() {
+        let a = Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap();
+        assert_tensor_eq!(a, a.clone());
+        assert_allclose!(
+            Tensor::new(vec![0.1 + 0.2], vec![1]).unwrap(),
+            Tensor::new(vec![0.3], vec![1]).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "tensors differ at index [1, 0]\n  left: 3\n right: 5")]
+    fn test_assert_tensor_eq_reports_index() {
+        let a = Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap();
+        let b = Tensor::new(vec![1, 2, 5, 4], vec![2, 2]).unwrap();
+        assert_tensor_eq!(a, b);
+    }
+
+    #[test]
+    #[should_panic(expected = "not close at index [2]")]
+    fn test_assert_allclose_reports_index() {
+        let a = Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap();
+        let b = Tensor::new(vec![1.0, 2.0, 3.1], vec![3]).unwrap();
+        assert_allclose!(a, b);
+    }
+
+    #[test]
+    #[should_panic(expected = "shapes differ")]
+    fn test_assert_tensor_eq_shape_mismatch() {
+        let a = Tensor::new(vec![1, 2], vec![2]).unwrap();
+        let b = Tensor::new(vec![1, 2], vec![1, 2]).unwrap();
+        assert_tensor_eq!(a, b);
+    }
+}
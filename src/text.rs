@@ -0,0 +1,196 @@
+//! Comparisons between sequences of token IDs.
+
+use crate::error::TensorError;
+use crate::tensor::Tensor;
+
+/// Returns the Levenshtein distance between two token sequences: the smallest
+/// number of insertions, deletions and substitutions turning `a` into `b`.
+///
+/// Uses a single row of the dynamic programming table, so it takes
+/// `O(len(a) len(b))` time and `O(len(b))` memory.
+fn levenshtein<T: PartialEq>(a: &[T], b: &[T], row: &mut Vec<usize>) -> usize {
+    row.clear();
+    row.extend(0..=b.len());
+    for (i, x) in a.iter().enumerate() {
+        // `diagonal` holds the previous row's entry for column j - 1.
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(diagonal + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Returns the tokens of each row of a `[batch, len]` tensor, keeping only the
+/// positions where `mask` is `true` when a mask is given.
+fn masked_rows<T: Clone>(
+    batch: &Tensor<T>,
+    mask: Option<&Tensor<bool>>,
+    what: &str,
+) -> Result<Vec<Vec<T>>, TensorError> {
+    let len = match batch.shape() {
+        [_, len] => *len,
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "{} must be a [batch, len] tensor, got shape {:?}",
+                what, shape
+            )));
+        }
+    };
+    let tokens: Vec<T> = batch.iter().cloned().collect();
+    let keep: Vec<bool> = match mask {
+        Some(mask) if mask.shape() != batch.shape() => {
+            return Err(TensorError::ShapeError(format!(
+                "mask of shape {:?} does not match {} of shape {:?}",
+                mask.shape(),
+                what,
+                batch.shape()
+            )));
+        }
+        Some(mask) => mask.iter().copied().collect(),
+        None => vec![true; tokens.len()],
+    };
+    if len == 0 {
+        return Ok(vec![Vec::new(); batch.shape()[0]]);
+    }
+    Ok(tokens
+        .chunks(len)
+        .zip(keep.chunks(len))
+        .map(|(row, keep)| {
+            row.iter()
+                .zip(keep)
+                .filter(|&(_, &k)| k)
+                .map(|(t, _)| t.clone())
+                .collect()
+        })
+        .collect())
+}
+
+/// Returns the Levenshtein distance between the 1D token sequences `a` and
+/// `b`: the smallest number of single-token insertions, deletions and
+/// substitutions that turn `a` into `b`.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if either sequence is not 1D.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::tensor::Tensor;
+/// use tiny_tensor::text::edit_distance;
+///
+/// // "kitten" and "sitting" as byte IDs.
+/// let a = Tensor::new(b"kitten".to_vec(), vec![6]).unwrap();
+/// let b = Tensor::new(b"sitting".to_vec(), vec![7]).unwrap();
+/// assert_eq!(edit_distance(&a, &b).unwrap(), 3);
+/// ```
+pub fn edit_distance<T: PartialEq + Clone>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+) -> Result<usize, TensorError> {
+    for (sequence, what) in [(a, "first sequence"), (b, "second sequence")] {
+        if sequence.ndim() != 1 {
+            return Err(TensorError::ShapeError(format!(
+                "{} must be 1D, got shape {:?}",
+                what,
+                sequence.shape()
+            )));
+        }
+    }
+    let a: Vec<T> = a.iter().cloned().collect();
+    let b: Vec<T> = b.iter().cloned().collect();
+    Ok(levenshtein(&a, &b, &mut Vec::new()))
+}
+
+/// Returns the `[n, m]` matrix of Levenshtein distances between every row of
+/// the padded `[n, len_a]` batch `batch_a` and every row of the `[m, len_b]`
+/// batch `batch_b`.
+///
+/// A mask, with the shape of its batch, marks the real tokens with `true`;
+/// masked-out positions (typically padding) are dropped before comparing. Rows
+/// are compared in full when no mask is given.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if a batch is not 2D or a mask does not
+/// have the shape of its batch.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::tensor::Tensor;
+/// use tiny_tensor::text::pairwise_edit_distance;
+///
+/// // Two queries padded with 0, against one unpadded target.
+/// let queries = Tensor::new(vec![1, 2, 3, 1, 3, 0], vec![2, 3]).unwrap();
+/// let mask = Tensor::new(vec![true, true, true, true, true, false], vec![2, 3]).unwrap();
+/// let targets = Tensor::new(vec![1, 2, 3], vec![1, 3]).unwrap();
+///
+/// let distances = pairwise_edit_distance(&queries, Some(&mask), &targets, None).unwrap();
+/// assert_eq!(distances.as_slice(), Some(&[0, 1][..]));
+/// ```
+pub fn pairwise_edit_distance<T: PartialEq + Clone>(
+    batch_a: &Tensor<T>,
+    mask_a: Option<&Tensor<bool>>,
+    batch_b: &Tensor<T>,
+    mask_b: Option<&Tensor<bool>>,
+) -> Result<Tensor<usize>, TensorError> {
+    let rows_a = masked_rows(batch_a, mask_a, "first batch")?;
+    let rows_b = masked_rows(batch_b, mask_b, "second batch")?;
+    let mut row = Vec::new();
+    let mut distances = Vec::with_capacity(rows_a.len() * rows_b.len());
+    for a in &rows_a {
+        for b in &rows_b {
+            distances.push(levenshtein(a, b, &mut row));
+        }
+    }
+    Tensor::new(distances, vec![rows_a.len(), rows_b.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        let seq = |s: &str| Tensor::new(s.bytes().collect(), vec![s.len()]).unwrap();
+
+        assert_eq!(edit_distance(&seq("flaw"), &seq("lawn")).unwrap(), 2);
+        assert_eq!(edit_distance(&seq(""), &seq("abc")).unwrap(), 3);
+        assert_eq!(edit_distance(&seq("abc"), &seq("abc")).unwrap(), 0);
+        assert_eq!(
+            edit_distance(&seq("intention"), &seq("execution")).unwrap(),
+            5
+        );
+
+        let matrix = Tensor::new(vec![1, 2, 3, 4], vec![2, 2]).unwrap();
+        assert!(matches!(
+            edit_distance(&matrix, &matrix),
+            Err(TensorError::ShapeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_pairwise_edit_distance_with_masks() {
+        let a = Tensor::new(vec![5, 6, 7, 9, 9, 9], vec![2, 3]).unwrap();
+        // Padding may sit anywhere; only the unmasked tokens count.
+        let mask_a = Tensor::new(vec![true, false, true, false, false, false], vec![2, 3]).unwrap();
+        let b = Tensor::new(vec![5, 7, 6, 7], vec![2, 2]).unwrap();
+
+        let distances = pairwise_edit_distance(&a, Some(&mask_a), &b, None).unwrap();
+        assert_eq!(distances.shape(), &[2, 2]);
+        assert_eq!(distances.as_slice(), Some(&[0, 1, 2, 2][..]));
+
+        let unmasked = pairwise_edit_distance(&a, None, &b, None).unwrap();
+        assert_eq!(unmasked.as_slice(), Some(&[1, 1, 3, 3][..]));
+
+        let wrong_mask = Tensor::new(vec![true; 3], vec![1, 3]).unwrap();
+        assert!(pairwise_edit_distance(&a, Some(&wrong_mask), &b, None).is_err());
+        let flat = Tensor::new(vec![5, 6, 7], vec![3]).unwrap();
+        assert!(pairwise_edit_distance(&flat, None, &b, None).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+//! Comparisons between time series.
+
+use crate::error::TensorError;
+use crate::num::Float;
+use crate::tensor::Tensor;
+
+/// Returns the samples of a sequence of shape `[len]` or `[len, features]` as
+/// a row-major buffer, with the number of features per sample.
+fn samples<T: Float>(sequence: &Tensor<T>, what: &str) -> Result<(Vec<T>, usize), TensorError> {
+    let features = match sequence.shape() {
+        [len] if *len > 0 => 1,
+        [len, features] if *len > 0 => *features,
+        shape => {
+            return Err(TensorError::ShapeError(format!(
+                "{} must be a non-empty sequence of shape [len] or [len, features], got {:?}",
+                what, shape
+            )));
+        }
+    };
+    Ok((sequence.iter().copied().collect(), features))
+}
+
+/// Fills the dynamic time warping cost matrix of `a` and `b` within a
+/// Sakoe-Chiba band, returning the total cost and, if `keep` is set, the full
+/// row-major `[n, m]` matrix of accumulated costs (infinite outside the band).
+fn accumulate<T: Float>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    window: Option<usize>,
+    keep: bool,
+) -> Result<(T, Option<Vec<T>>), TensorError> {
+    let (x, features) = samples(a, "first sequence")?;
+    let (y, y_features) = samples(b, "second sequence")?;
+    if features != y_features {
+        return Err(TensorError::ShapeError(format!(
+            "sequences with {} and {} features cannot be aligned",
+            features, y_features
+        )));
+    }
+    let (n, m) = (a.shape()[0], b.shape()[0]);
+    // The band must be at least as wide as the length difference for the last
+    // cells to be reachable, and never needs to be wider than the longer sequence.
+    let window = window.map_or(n.max(m), |w| w.clamp(n.abs_diff(m), n.max(m)));
+    let band = |i: usize| i.saturating_sub(window)..(i + window + 1).min(m);
+
+    let local = |i: usize, j: usize| {
+        let pairs = x[i * features..(i + 1) * features]
+            .iter()
+            .zip(&y[j * features..(j + 1) * features]);
+        if features == 1 {
+            pairs.fold(T::zero(), |acc, (&p, &q)| acc + (p - q).abs())
+        } else {
+            pairs
+                .fold(T::zero(), |acc, (&p, &q)| acc + (p - q) * (p - q))
+                .sqrt()
+        }
+    };
+
+    let infinity = T::from_f64(f64::INFINITY);
+    let mut full = if keep {
+        vec![infinity; n * m]
+    } else {
+        Vec::new()
+    };
+    let mut previous = vec![infinity; m];
+    let mut current = vec![infinity; m];
+    for i in 0..n {
+        // `current` still holds row i - 2, which is infinite outside its band,
+        // so clearing that band alone keeps the work proportional to the band.
+        if i >= 2 {
+            current[band(i - 2)].fill(infinity);
+        }
+        for j in band(i) {
+            let best = match (i, j) {
+                (0, 0) => T::zero(),
+                (0, _) => current[j - 1],
+                (_, 0) => previous[j],
+                _ => {
+                    let step = if previous[j] < current[j - 1] {
+                        previous[j]
+                    } else {
+                        current[j - 1]
+                    };
+                    if previous[j - 1] < step {
+                        previous[j - 1]
+                    } else {
+                        step
+                    }
+                }
+            };
+            current[j] = best + local(i, j);
+        }
+        if keep {
+            full[i * m..(i + 1) * m].copy_from_slice(&current);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    Ok((previous[m - 1], keep.then_some(full)))
+}
+
+/// Returns the dynamic time warping distance between the sequences `a` and
+/// `b`.
+///
+/// Sequences have shape `[len]` or `[len, features]` and may differ in
+/// length. The local cost between two samples is their absolute difference,
+/// or Euclidean distance with several features, and the result is the
+/// smallest total local cost over all monotone alignments of the two
+/// sequences. With `window`, samples more than `window` steps apart (widened
+/// to the length difference if needed) are never aligned, which restricts the
+/// work to a band of the cost matrix.
+///
+/// # Errors
+///
+/// Returns `TensorError::ShapeError` if a sequence is empty or not 1D or 2D,
+/// or the sequences have different numbers of features.
+///
+/// # Examples
+///
+/// ```
+/// use tiny_tensor::tensor::Tensor;
+/// use tiny_tensor::timeseries::dtw;
+///
+/// let a = Tensor::new(vec![0.0, 1.0, 2.0, 1.0], vec![4]).unwrap();
+/// let slow = Tensor::new(vec![0.0, 0.0, 1.0, 1.0, 2.0, 1.0], vec![6]).unwrap();
+/// assert_eq!(dtw(&a, &slow, None).unwrap(), 0.0);
+/// ```
+pub fn dtw<T: Float>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    window: Option<usize>,
+) -> Result<T, TensorError> {
+    Ok(accumulate(a, b, window, false)?.0)
+}
+
+/// Like [`dtw`], but also returns the optimal warping path as a `[steps, 2]`
+/// tensor of `(index in a, index in b)` pairs, from `(0, 0)` to the last
+/// samples of both sequences.
+///
+/// This keeps the whole `[len(a), len(b)]` cost matrix in memory.
+///
+/// # Errors
+///
+/// See [`dtw`].
+pub fn dtw_path<T: Float>(
+    a: &Tensor<T>,
+    b: &Tensor<T>,
+    window: Option<usize>,
+) -> Result<(T, Tensor<usize>), TensorError> {
+    let (cost, full) = accumulate(a, b, window, true)?;
+    let full = full.unwrap_or_default();
+    let m = b.shape()[0];
+
+    let (mut i, mut j) = (a.shape()[0] - 1, m - 1);
+    let mut path = vec![[i, j]];
+    while (i, j) != (0, 0) {
+        (i, j) = match (i, j) {
+            (0, _) => (0, j - 1),
+            (_, 0) => (i - 1, 0),
+            _ => [(i - 1, j - 1), (i - 1, j), (i, j - 1)]
+                .into_iter()
+                .reduce(|best, (p, q)| {
+                    if full[p * m + q] < full[best.0 * m + best.1] {
+                        (p, q)
+                    } else {
+                        best
+                    }
+                })
+                .unwrap_or((i - 1, j - 1)),
+        };
+        path.push([i, j]);
+    }
+    path.reverse();
+
+    let steps = path.len();
+    Ok((cost, Tensor::new(path.concat(), vec![steps, 2])?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dtw_aligns_shifted_and_stretched_sequences() {
+        let a = Tensor::new(vec![1.0, 2.0, 3.0], vec![3]).unwrap();
+        let b = Tensor::new(vec![1.0, 2.0, 2.0, 3.0, 5.0], vec![5]).unwrap();
+
+        let (cost, path) = dtw_path(&a, &b, None).unwrap();
+
+        assert_eq!(cost, 2.0);
+        assert_eq!(dtw(&a, &b, None).unwrap(), 2.0);
+        assert_eq!(path.as_slice(), Some(&[0, 0, 1, 1, 1, 2, 2, 3, 2, 4][..]));
+        assert_eq!(dtw(&b, &a, Some(0)).unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_dtw_window_restricts_alignment() {
+        // Aligning the spikes needs a shift of three steps.
+        let a = Tensor::new(vec![0.0, 5.0, 0.0, 0.0, 0.0, 0.0], vec![6]).unwrap();
+        let b = Tensor::new(vec![0.0, 0.0, 0.0, 0.0, 5.0, 0.0], vec![6]).unwrap();
+
+        assert_eq!(dtw(&a, &b, None).unwrap(), 0.0);
+        assert_eq!(dtw(&a, &b, Some(3)).unwrap(), 0.0);
+        assert_eq!(dtw(&a, &b, Some(1)).unwrap(), 10.0);
+        assert_eq!(dtw_path(&a, &b, Some(1)).unwrap().0, 10.0);
+        assert_eq!(dtw(&a, &b, Some(usize::MAX)).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_dtw_band_matches_direct_recurrence() {
+        let a = Tensor::from_fn(vec![9], |i| ((i[0] * 7) % 5) as f64);
+        let b = Tensor::from_fn(vec![12], |i| ((i[0] * 3) % 4) as f64 - 0.5);
+        let (x, y): (Vec<f64>, Vec<f64>) =
+            (a.iter().copied().collect(), b.iter().copied().collect());
+
+        for window in [0, 1, 2, 3, 5, 20] {
+            // The textbook recurrence over the full matrix, with out-of-band cells infinite.
+            let w = window.max(y.len() - x.len());
+            let mut cost = vec![vec![f64::INFINITY; y.len() + 1]; x.len() + 1];
+            cost[0][0] = 0.0;
+            for i in 1..=x.len() {
+                for j in 1..=y.len() {
+                    if i.abs_diff(j) <= w {
+                        let best = cost[i - 1][j].min(cost[i][j - 1]).min(cost[i - 1][j - 1]);
+                        cost[i][j] = best + (x[i - 1] - y[j - 1]).abs();
+                    }
+                }
+            }
+            assert_eq!(
+                dtw(&a, &b, Some(window)).unwrap(),
+                cost[x.len()][y.len()],
+                "window {}",
+                window
+            );
+        }
+    }
+
+    #[test]
+    fn test_dtw_multivariate_and_errors() {
+        let a = Tensor::new(vec![0.0, 0.0, 3.0, 4.0], vec![2, 2]).unwrap();
+        let b = Tensor::new(vec![0.0, 0.0], vec![1, 2]).unwrap();
+
+        assert_eq!(dtw(&a, &b, None).unwrap(), 5.0);
+        let (_, path) = dtw_path(&a, &b, None).unwrap();
+        assert_eq!(path.shape(), &[2, 2]);
+
+        let empty = Tensor::<f64>::new(vec![], vec![0]).unwrap();
+        assert!(matches!(
+            dtw(&empty, &empty, None),
+            Err(TensorError::ShapeError(_))
+        ));
+        let one_feature = Tensor::new(vec![0.0, 1.0], vec![2]).unwrap();
+        assert!(dtw(&a, &one_feature, None).is_err());
+    }
+}